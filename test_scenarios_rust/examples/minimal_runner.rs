@@ -0,0 +1,35 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Minimal consumer of the public API, built with and without `tracing` feature.
+use std::process::ExitCode;
+use test_scenarios_rust::cli::{create_tracing_subscriber, run_cli_app_exit};
+use test_scenarios_rust::monotonic_clock::MonotonicClock;
+use test_scenarios_rust::scenario::{FnScenario, ScenarioGroupImpl};
+use test_scenarios_rust::test_context::TestContext;
+
+fn main() -> ExitCode {
+    let clock = MonotonicClock::new();
+    let _subscriber = create_tracing_subscriber();
+
+    let echo = FnScenario::new("echo", |input: &str| {
+        println!("{input}");
+        Ok(())
+    });
+    let root_group = ScenarioGroupImpl::new("root", vec![Box::new(echo)], Vec::new());
+    let test_context = TestContext::new(Box::new(root_group));
+
+    let raw_arguments: Vec<String> = std::env::args().collect();
+    let exit_code = run_cli_app_exit(&raw_arguments, &test_context, |_| None);
+    eprintln!("Finished after {} us", clock.elapsed_micros());
+    exit_code
+}
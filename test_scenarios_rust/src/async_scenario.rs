@@ -0,0 +1,144 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Asynchronous test scenarios, run on a `tokio` runtime.
+//!
+//! Available with `tokio` feature.
+use crate::outcome::Outcome;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Future returned by [`AsyncScenario::run`].
+pub type ScenarioFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Asynchronous scenario definition.
+pub trait AsyncScenario: Send + Sync {
+    /// Get scenario name.
+    fn name(&self) -> &str;
+
+    /// Run test scenario.
+    ///
+    /// * `input` - Test scenario input.
+    fn run<'a>(&'a self, input: &'a str) -> ScenarioFuture<'a>;
+
+    /// Maximum scenario execution time.
+    /// Unlimited by default.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Run asynchronous test scenario with timeout.
+/// Unlike blocking scenarios, scenario future is dropped when timeout expires,
+/// so it is cancelled at its current await point.
+/// Must be called within a `tokio` runtime with time driver enabled.
+///
+/// Timeout precedence:
+/// 1. `timeout` provided to this function.
+/// 2. [`AsyncScenario::timeout`] declared by the scenario.
+/// 3. Unlimited.
+///
+/// * `scenario` - Scenario to run.
+/// * `input` - Test scenario input.
+/// * `timeout` - Maximum scenario execution time, overrides scenario timeout.
+pub async fn run_with_timeout(
+    scenario: &dyn AsyncScenario,
+    input: &str,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let future = scenario.run(input);
+    match timeout.or_else(|| scenario.timeout()) {
+        Some(timeout) => match tokio::time::timeout(timeout, future).await {
+            Ok(result) => result.into(),
+            Err(_) => Outcome::TimedOut,
+        },
+        None => future.await.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::async_scenario::{run_with_timeout, AsyncScenario, ScenarioFuture};
+    use crate::outcome::Outcome;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Sets flag when dropped.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    struct SleepScenarioStub {
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl AsyncScenario for SleepScenarioStub {
+        fn name(&self) -> &str {
+            "sleep_scenario"
+        }
+
+        fn run<'a>(&'a self, input: &'a str) -> ScenarioFuture<'a> {
+            Box::pin(async move {
+                let _flag = DropFlag(self.dropped.clone());
+                let millis = input.parse::<u64>().map_err(|e| e.to_string())?;
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                Ok(())
+            })
+        }
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_run_with_timeout_timed_out() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let scenario = SleepScenarioStub {
+            dropped: dropped.clone(),
+        };
+
+        let outcome = block_on(run_with_timeout(
+            &scenario,
+            "10000",
+            Some(Duration::from_millis(10)),
+        ));
+        assert_eq!(outcome, Outcome::TimedOut);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_run_with_timeout_finished() {
+        let scenario = SleepScenarioStub {
+            dropped: Arc::new(AtomicBool::new(false)),
+        };
+
+        let outcome = block_on(run_with_timeout(
+            &scenario,
+            "1",
+            Some(Duration::from_secs(5)),
+        ));
+        assert_eq!(outcome, Outcome::Passed);
+        let outcome = block_on(run_with_timeout(&scenario, "abc", None));
+        assert!(matches!(outcome, Outcome::Failed(_)));
+    }
+}
@@ -10,66 +10,743 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 // *******************************************************************************
-use crate::monotonic_clock::MonotonicClock;
-use crate::test_context::TestContext;
-use tracing::Level;
+use crate::color::{paint_status, ColorMode};
+use crate::data_scenario::Expected;
+use crate::error::{CliError, ScenarioError};
+use crate::filter::{matches_pattern, ScenarioFilter, ScenarioToggles, Shard};
+use crate::hash::stable_hash;
+use crate::logging::{
+    init_tracing, init_tracing_with_event_log, tracing_initialized, EventLog, Level, LogFormat,
+    LogGuard, LogOutput, TimestampMode, TracingConfig,
+};
+use crate::metrics::MetricsSink;
+#[cfg(feature = "tracing")]
+use crate::monotonic_clock::{shared_start, MonotonicClock};
+use crate::outcome::Outcome;
+use crate::regex::Regex;
+use crate::report::{
+    format_failure, format_summary_by_tag, metrics_json, scenario_end_marker,
+    scenario_start_marker, scenario_start_marker_with_input, summary_by_tag, to_csv_entries,
+    to_junit, to_tap_entries, truncate_input, CsvEntry, ErrorAnnotation, FlakinessReport, TapEntry,
+};
+use crate::resource_usage::ResourceUsage;
+use crate::subprocess::Isolation;
+use crate::test_context::{
+    run_with_retries, NoopObserver, PartialResults, RunObserver, RunOptions, RunResult, RunStats,
+    TestContext, DISABLED_REASON, GLOBAL_TIMEOUT_REASON, MAX_FAILURES_REASON,
+};
+use serde_json::{json, Map, Value};
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+#[cfg(feature = "tracing")]
 use tracing_subscriber::fmt::format::{Format, JsonFields};
+#[cfg(feature = "tracing")]
 use tracing_subscriber::FmtSubscriber;
 
+/// Subscriber created by [`create_tracing_subscriber`].
+#[cfg(feature = "tracing")]
+pub type TracingSubscriber =
+    FmtSubscriber<JsonFields, Format<tracing_subscriber::fmt::format::Json, MonotonicClock>>;
+
+/// Subscriber created by [`create_tracing_subscriber`], no-op when `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug)]
+pub struct TracingSubscriber;
+
 /// Create a tracing subscriber that outputs logs in JSON format with monotonic timestamps.
 /// # Returns
 /// configured `FmtSubscriber`.
-pub fn create_tracing_subscriber(
-) -> FmtSubscriber<JsonFields, Format<tracing_subscriber::fmt::format::Json, MonotonicClock>> {
+#[cfg(feature = "tracing")]
+pub fn create_tracing_subscriber() -> TracingSubscriber {
     FmtSubscriber::builder()
         .with_max_level(Level::TRACE)
         .with_thread_ids(true)
-        .with_timer(MonotonicClock::new())
+        .with_timer(MonotonicClock::from_start(shared_start()))
         .json()
         .finish()
 }
 
+/// No-op, logs are not emitted when `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub fn create_tracing_subscriber() -> TracingSubscriber {
+    TracingSubscriber
+}
+
+/// Environment variable with default test scenario name.
+const NAME_ENV_VAR: &str = "TEST_SCENARIO_NAME";
+
+/// Environment variable with default test scenario input.
+pub(crate) const INPUT_ENV_VAR: &str = "TEST_SCENARIO_INPUT";
+
+/// Default maximum size of test scenario input.
+const DEFAULT_MAX_INPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Maximum length of NDJSON line read from stdin in addition to maximum input size,
+/// for scenario name and JSON syntax.
+const NDJSON_LINE_OVERHEAD: usize = 4096;
+
+/// Default maximum number of input characters reported in markers and reports.
+const DEFAULT_MAX_REPORTED_INPUT: usize = 256;
+
+/// Results of scenario runs.
+type RunResults = Vec<RunResult>;
+
+/// Scenario runs, or errors aborting the run when reached.
+type Runs = Vec<Result<(String, String), String>>;
+
 /// Test scenario arguments.
 #[derive(Default)]
 struct ScenarioArguments {
-    /// Test scenario name.
-    name: Option<String>,
+    /// Test scenario or group names.
+    names: Vec<String>,
+
+    /// Regular expression selecting scenarios by fully-qualified name.
+    name_regex: Option<String>,
 
     /// Test scenario input.
     input: Option<String>,
+
+    /// Number of concurrent copies of the scenario to run.
+    concurrency: Option<usize>,
+
+    /// Test scenario timeout, overrides timeout declared by scenario.
+    timeout: Option<Duration>,
+
+    /// Time after which cancellation of the scenario is requested.
+    soft_timeout: Option<Duration>,
+
+    /// Number of times the scenario is run.
+    repeat: Option<usize>,
+
+    /// Wall-clock duration for which the scenario is repeatedly run.
+    until_duration: Option<Duration>,
+
+    /// Number of discarded runs before measured runs.
+    warmup: Option<usize>,
+
+    /// Path to flakiness report file.
+    /// All repeats are run regardless of failures if set.
+    flakiness_report: Option<String>,
+
+    /// Print structured scenario output to stdout on success.
+    emit_output: bool,
+
+    /// Seed used to shuffle order of multi-scenario runs.
+    seed: Option<u64>,
+
+    /// Narrow down multi-scenario run to a minimal failing subsequence.
+    bisect: bool,
+
+    /// Number of failures after which remaining scenarios are not run.
+    max_failures: Option<usize>,
+
+    /// Maximum size of test scenario input, `DEFAULT_MAX_INPUT_BYTES` if not set.
+    max_input_bytes: Option<usize>,
+
+    /// Scenario toggles, disabled scenarios are reported as skipped.
+    toggles: ScenarioToggles,
+
+    /// Working directory of single-scenario runs.
+    workdir: Option<String>,
+
+    /// Wall-clock time after which multi-scenario run stops starting scenarios.
+    global_timeout: Option<Duration>,
+
+    /// Instant after which multi-scenario run stops starting scenarios, set once run starts.
+    deadline: Option<Instant>,
+
+    /// Number of times a failed scenario is run again.
+    retries: Option<usize>,
+
+    /// Failures are retried only if error contains this text.
+    retry_if_contains: Option<String>,
+
+    /// Run each scenario in a separate runner process.
+    isolate: bool,
+
+    /// Place each runner process in its own process group, requires `isolate`.
+    process_group: bool,
+
+    /// Collect resource usage of each scenario.
+    resource_stats: bool,
+
+    /// Only validate inputs against scenario input schemas, scenarios are not run.
+    validate_inputs: bool,
+
+    /// Arguments forwarded to runner processes of isolated scenarios, e.g., log configuration.
+    isolation_arguments: Vec<String>,
+}
+
+impl ScenarioArguments {
+    /// Check that test scenario input does not exceed size limit.
+    ///
+    /// * `input` - Test scenario input.
+    fn check_input_size(&self, input: &str) -> Result<(), String> {
+        let limit = self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        if input.len() > limit {
+            return Err(format!(
+                "Test scenario input of {} bytes exceeds limit of {limit} bytes",
+                input.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that working directory is not used with multi-scenario runs.
+    /// Working directory is global to the process, scenarios run in parallel would share it.
+    fn check_single_scenario_workdir(&self) -> Result<(), String> {
+        if self.workdir.is_some() {
+            return Err("Working directory is only supported for single-scenario runs".to_string());
+        }
+        Ok(())
+    }
+
+    /// Isolation of scenario runs, `None` if scenarios are run in this process.
+    /// Working directory and retries are forwarded to runner processes, with `isolation_arguments`.
+    /// Timeouts are passed by [`Isolation`] itself.
+    fn isolation(&self) -> Result<Option<Isolation>, String> {
+        if !self.isolate {
+            return Ok(None);
+        }
+        let mut arguments = self.isolation_arguments.clone();
+        if let Some(workdir) = &self.workdir {
+            arguments.extend(["--workdir".to_string(), workdir.clone()]);
+        }
+        if let Some(retries) = self.retries {
+            arguments.extend(["--retries".to_string(), retries.to_string()]);
+        }
+        if let Some(text) = &self.retry_if_contains {
+            arguments.extend(["--retry-if-contains".to_string(), text.clone()]);
+        }
+        Ok(Some(Isolation {
+            arguments,
+            ..Isolation::current_exe(self.process_group)?
+        }))
+    }
 }
 
 /// CLI arguments.
+/// Created by parsing command line in [`run_cli_app`] or with [`CliArguments::builder`].
 #[derive(Default)]
-struct CliArguments {
+pub struct CliArguments {
     /// Test scenario arguments.
     scenario_arguments: ScenarioArguments,
 
+    /// Path to playlist file.
+    playlist: Option<String>,
+
+    /// Path to parameter matrix file.
+    matrix: Option<String>,
+
+    /// Path to manifest file with scenario inputs.
+    manifest: Option<String>,
+
+    /// Path to JSON report of previous run, scenarios which did not pass are run again.
+    rerun_failed: Option<String>,
+
+    /// Read scenario names and inputs from stdin as JSON Lines.
+    stdin_ndjson: bool,
+
+    /// Path to test scenario input template.
+    input_template: Option<String>,
+
+    /// Path to JSON file with values substituted into input template.
+    values: Option<String>,
+
+    /// Path to TAP report file.
+    report_tap: Option<String>,
+
+    /// Path to CSV report file.
+    report_csv: Option<String>,
+
+    /// Directory run artifacts are written to, see [`write_output_dir`].
+    output_dir: Option<String>,
+
+    /// Tracing configuration.
+    tracing_config: TracingConfig,
+
+    /// Path to file all events of the run are written to, as JSON array.
+    event_log: Option<String>,
+
+    /// Scenario selection filter.
+    filter: ScenarioFilter,
+
+    /// Print scenario start and end markers in multi-scenario runs.
+    markers: bool,
+
+    /// Stream NDJSON line with result of each scenario to stdout.
+    stream_results: bool,
+
+    /// Print outcome counts grouped by tag after the run.
+    summary_by_tag: bool,
+
+    /// Maximum number of reported input characters, `DEFAULT_MAX_REPORTED_INPUT` if not set.
+    max_reported_input: Option<usize>,
+
+    /// Color mode of scenario status output.
+    color: ColorMode,
+
+    /// Format of scenario failure lines printed to stderr.
+    error_annotation: ErrorAnnotation,
+
+    /// Path to quarantine file.
+    quarantine_file: Option<String>,
+
+    /// Expected result of the run scenarios, overall result passes if all scenarios match it.
+    expect: Option<Expected>,
+
+    /// Text expected in errors of failed scenarios, requires `expect` to be `fail`.
+    expect_error_contains: Option<String>,
+
+    /// Run id attached to all log records, generated if not provided.
+    run_id: Option<String>,
+
+    /// Path to file to which results are appended as JSON lines, used by runner processes of isolated scenarios.
+    result_file: Option<String>,
+
     /// List scenarios.
     list_scenarios: bool,
 
+    /// Print number of scenarios.
+    count: bool,
+
+    /// Describe how provided scenario names are resolved, without running them.
+    explain: bool,
+
+    /// List groups and scenarios as tree.
+    list_tree: bool,
+
+    /// List scenarios as JSON Lines catalog.
+    catalog_ndjson: bool,
+
+    /// Annotate tree listing with number of scenarios of each group.
+    counts: bool,
+
+    /// Print effective configuration.
+    print_config: bool,
+
+    /// Print errors to stderr as single-line JSON objects.
+    json_errors: bool,
+
+    /// Log each parsed argument and resulting configuration at debug level.
+    trace_args: bool,
+
+    /// Recognized arguments with consumed values, in order of parsing.
+    parsed_arguments: Vec<(String, Vec<String>)>,
+
     /// Show help.
     help: bool,
 }
 
-/// Parse CLI arguments.
+impl CliArguments {
+    /// Create builder of CLI arguments, for programmatic invocation with [`run_cli`].
+    pub fn builder() -> CliArgumentsBuilder {
+        CliArgumentsBuilder::default()
+    }
+}
+
+/// Configuration of CLI application, for embedding the runner in other tools.
+#[derive(Clone, Debug)]
+pub struct CliConfig {
+    /// Program name shown in usage line of help, usage line is omitted if not set.
+    pub program_name: Option<String>,
+
+    /// Description shown in first line of help.
+    pub about: String,
+
+    /// Lookup of environment variables providing default test scenario name and input,
+    /// reads process environment by default.
+    pub env_var: fn(&str) -> Option<String>,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        CliConfig {
+            program_name: None,
+            about: "Test scenario runner".to_string(),
+            env_var: |key| std::env::var(key).ok(),
+        }
+    }
+}
+
+/// Builder of [`CliArguments`].
+/// Options not set are left at their defaults, same as when not provided on command line.
+#[derive(Default)]
+pub struct CliArgumentsBuilder {
+    arguments: CliArguments,
+}
+
+impl CliArgumentsBuilder {
+    /// Add test scenario or group name, see `--name`.
+    ///
+    /// * `name` - Test scenario or group name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.arguments.scenario_arguments.names.push(name.into());
+        self
+    }
+
+    /// Set test scenario input, see `--input`.
+    ///
+    /// * `input` - Test scenario input.
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.arguments.scenario_arguments.input = Some(input.into());
+        self
+    }
+
+    /// Set test scenario timeout, see `--timeout`.
+    ///
+    /// * `timeout` - Test scenario timeout, overrides timeout declared by scenario.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.arguments.scenario_arguments.timeout = Some(timeout);
+        self
+    }
+
+    /// Set number of times the scenario is run, see `--repeat`.
+    ///
+    /// * `repeat` - Number of runs.
+    pub fn repeat(mut self, repeat: usize) -> Self {
+        self.arguments.scenario_arguments.repeat = Some(repeat);
+        self
+    }
+
+    /// Set number of concurrent copies of the scenario, see `--concurrency`.
+    ///
+    /// * `concurrency` - Number of copies.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.arguments.scenario_arguments.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Set path to playlist file, see `--playlist`.
+    ///
+    /// * `path` - Path to playlist file.
+    pub fn playlist(mut self, path: impl Into<String>) -> Self {
+        self.arguments.playlist = Some(path.into());
+        self
+    }
+
+    /// Set path to TAP report file, see `--report-tap`.
+    ///
+    /// * `path` - Path to TAP report file.
+    pub fn report_tap(mut self, path: impl Into<String>) -> Self {
+        self.arguments.report_tap = Some(path.into());
+        self
+    }
+
+    /// Set path to CSV report file, see `--report-csv`.
+    ///
+    /// * `path` - Path to CSV report file.
+    pub fn report_csv(mut self, path: impl Into<String>) -> Self {
+        self.arguments.report_csv = Some(path.into());
+        self
+    }
+
+    /// Set tracing configuration, see `--log-level` and related options.
+    ///
+    /// * `tracing_config` - Tracing configuration.
+    pub fn tracing_config(mut self, tracing_config: TracingConfig) -> Self {
+        self.arguments.tracing_config = tracing_config;
+        self
+    }
+
+    /// Print scenario start and end markers, see `--markers`.
+    pub fn markers(mut self) -> Self {
+        self.arguments.markers = true;
+        self
+    }
+
+    /// List scenarios instead of running them, see `--list-scenarios`.
+    pub fn list_scenarios(mut self) -> Self {
+        self.arguments.list_scenarios = true;
+        self
+    }
+
+    /// Print errors to stderr as JSON, see `--json-errors`.
+    pub fn json_errors(mut self) -> Self {
+        self.arguments.json_errors = true;
+        self
+    }
+
+    /// Set run id attached to log records, see `--run-id`.
+    ///
+    /// * `run_id` - Run id.
+    pub fn run_id(mut self, run_id: impl Into<String>) -> Self {
+        self.arguments.run_id = Some(run_id.into());
+        self
+    }
+
+    /// Create CLI arguments.
+    pub fn build(self) -> CliArguments {
+        self.arguments
+    }
+}
+
+/// Expand `@path` response files into arguments read from the file.
+/// File content is split on whitespace. Nested response files are rejected.
+///
+/// * `arguments` - Arguments, without executable name.
+fn expand_response_files(arguments: &[String]) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::new();
+    for arg in arguments {
+        let Some(path) = arg.strip_prefix('@') else {
+            expanded.push(arg.clone());
+            continue;
+        };
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read response file {path}: {e}"))?;
+        for token in content.split_whitespace() {
+            if token.starts_with('@') {
+                return Err(format!("Nested response files are not supported: {token}"));
+            }
+            expanded.push(token.to_string());
+        }
+    }
+    Ok(expanded)
+}
+
+/// Known long options, used to resolve abbreviations.
+const LONG_OPTIONS: &[&str] = &[
+    "--name",
+    "--name-regex",
+    "--input",
+    "--input-template",
+    "--values",
+    "--concurrency",
+    "--timeout",
+    "--soft-timeout-ms",
+    "--hard-timeout-ms",
+    "--repeat",
+    "--until-duration",
+    "--warmup",
+    "--flakiness-report",
+    "--emit-output",
+    "--seed",
+    "--max-failures",
+    "--global-timeout",
+    "--retries",
+    "--retry-if-contains",
+    "--isolate",
+    "--process-group",
+    "--resource-stats",
+    "--result-file",
+    "--validate-inputs",
+    "--max-input-bytes",
+    "--bisect",
+    "--workdir",
+    "--playlist",
+    "--matrix",
+    "--manifest",
+    "--rerun-failed",
+    "--stdin-ndjson",
+    "--report-tap",
+    "--report-csv",
+    "--output-dir",
+    "--log-level",
+    "--color",
+    "--error-annotation",
+    "--expect",
+    "--expect-error-contains",
+    "--log-nonblocking",
+    "--log-thread-ids",
+    "--log-format",
+    "--timestamp-mode",
+    "--log-output",
+    "--tag",
+    "--skip",
+    "--shard",
+    "--enable",
+    "--disable",
+    "--markers",
+    "--summary-by-tag",
+    "--stream-results",
+    "--max-reported-input",
+    "--count",
+    "--explain",
+    "--list-tree",
+    "--catalog-ndjson",
+    "--counts",
+    "--print-config",
+    "--json-errors",
+    "--trace-args",
+    "--event-log",
+    "--quarantine-file",
+    "--run-id",
+    "--list-scenarios",
+    "--help",
+];
+
+/// Resolve unambiguous prefix of a known long option, e.g., `--conc` to `--concurrency`.
+/// Exact matches, short options and unknown arguments are returned unchanged.
+///
+/// * `arg` - Argument to resolve.
+fn resolve_long_option(arg: &str) -> Result<&str, String> {
+    if !arg.starts_with("--") || LONG_OPTIONS.contains(&arg) {
+        return Ok(arg);
+    }
+
+    let candidates: Vec<&str> = LONG_OPTIONS
+        .iter()
+        .copied()
+        .filter(|option| option.starts_with(arg))
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(arg),
+        [option] => Ok(option),
+        _ => Err(format!(
+            "Ambiguous argument {arg}, matches: {}",
+            candidates.join(", ")
+        )),
+    }
+}
+
+/// Check whether argument is a bundle of short flags, e.g., `-lh`.
+///
+/// * `arg` - Argument to check.
+fn is_short_flag_bundle(arg: &str) -> bool {
+    arg.len() > 2 && arg.starts_with('-') && !arg.starts_with("--")
+}
+
+/// Apply bundle of boolean short flags, e.g., `-lh` is same as `-l -h`.
+/// Flags taking a value cannot be bundled.
+///
+/// * `bundle` - Bundle of short flags, including leading dash.
+/// * `cli_arguments` - CLI arguments to update.
+fn apply_short_flag_bundle(bundle: &str, cli_arguments: &mut CliArguments) -> Result<(), String> {
+    for flag in bundle.chars().skip(1) {
+        match flag {
+            'l' => cli_arguments.list_scenarios = true,
+            'h' => cli_arguments.help = true,
+            'n' | 'i' => {
+                return Err(format!(
+                    "Flag -{flag} takes a value and cannot be combined: {bundle}"
+                ))
+            }
+            _ => return Err(format!("Unknown flag -{flag} in combined flags: {bundle}")),
+        }
+    }
+    Ok(())
+}
+
+/// Check that mutually exclusive arguments are not combined.
+/// Listing modes cannot be combined with each other or with selection of scenarios to run.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+fn check_conflicts(cli_arguments: &CliArguments) -> Result<(), String> {
+    let modes = [
+        ("--list-scenarios", cli_arguments.list_scenarios),
+        ("--list-tree", cli_arguments.list_tree),
+        ("--catalog-ndjson", cli_arguments.catalog_ndjson),
+        ("--count", cli_arguments.count),
+    ];
+    let selections = [
+        ("--name", !cli_arguments.scenario_arguments.names.is_empty()),
+        (
+            "--name-regex",
+            cli_arguments.scenario_arguments.name_regex.is_some(),
+        ),
+        ("--playlist", cli_arguments.playlist.is_some()),
+        ("--matrix", cli_arguments.matrix.is_some()),
+        ("--manifest", cli_arguments.manifest.is_some()),
+        ("--rerun-failed", cli_arguments.rerun_failed.is_some()),
+        ("--stdin-ndjson", cli_arguments.stdin_ndjson),
+    ];
+
+    let mut modes = modes.iter().filter(|(_, set)| *set).map(|(name, _)| name);
+    let Some(mode) = modes.next() else {
+        return Ok(());
+    };
+    let conflict = modes.next().or_else(|| {
+        selections
+            .iter()
+            .find(|(_, set)| *set)
+            .map(|(name, _)| name)
+    });
+    match conflict {
+        Some(conflict) => Err(format!("Conflicting arguments: {mode} and {conflict}")),
+        None => Ok(()),
+    }
+}
+
+/// Parse numeric CLI argument value.
+///
+/// * `name` - Option name, without leading dashes.
+/// * `value` - Option value.
+fn parse_numeric_arg<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("--{name} expects a number, got '{value}'"))
+}
+
+/// Parse numeric CLI argument value not lower than `min`.
+///
+/// * `name` - Option name, without leading dashes.
+/// * `value` - Option value.
+/// * `min` - Minimal accepted value.
+fn parse_numeric_arg_min<T: FromStr + PartialOrd + Display>(
+    name: &str,
+    value: &str,
+    min: T,
+) -> Result<T, String> {
+    let number = parse_numeric_arg::<T>(name, value)?;
+    if number < min {
+        return Err(format!("--{name} must be at least {min}, got '{value}'"));
+    }
+    Ok(number)
+}
+
+/// Parse CLI arguments, see [`parse_cli_arguments_into`].
 ///
 /// * `raw_arguments` - Collected arguments from `std::env::args()`.
+#[cfg(test)]
 fn parse_cli_arguments(raw_arguments: &[String]) -> Result<CliArguments, String> {
     let mut cli_arguments = CliArguments::default();
+    parse_cli_arguments_into(raw_arguments, &mut cli_arguments)?;
+    Ok(cli_arguments)
+}
 
+/// Parse CLI arguments into `cli_arguments`.
+/// Long options can be abbreviated to an unambiguous prefix.
+/// `@path` arguments are expanded with arguments read from the file.
+/// On error, `cli_arguments` holds arguments parsed before the invalid one.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `cli_arguments` - CLI arguments to update.
+fn parse_cli_arguments_into(
+    raw_arguments: &[String],
+    cli_arguments: &mut CliArguments,
+) -> Result<(), String> {
     // Process arguments.
     // First argument (executable name) is skipped.
-    let mut args_it = raw_arguments.iter().skip(1);
+    let arguments = expand_response_files(raw_arguments.get(1..).unwrap_or_default())?;
+    let mut args_it = arguments.iter();
     while let Some(arg) = args_it.next() {
-        match arg.as_str() {
+        let values_start = args_it.as_slice();
+        let option = resolve_long_option(arg)?;
+        match option {
             "-n" | "--name" => {
                 if let Some(value) = args_it.next() {
-                    cli_arguments.scenario_arguments.name = Some(value.clone());
+                    cli_arguments.scenario_arguments.names.push(value.clone());
                 } else {
                     return Err("Failed to read name parameter".to_string());
                 }
             }
+            "--name-regex" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.name_regex = Some(value.clone());
+                } else {
+                    return Err("Failed to read name-regex parameter".to_string());
+                }
+            }
             "-i" | "--input" => {
                 if let Some(value) = args_it.next() {
                     cli_arguments.scenario_arguments.input = Some(value.clone());
@@ -77,377 +754,8318 @@ fn parse_cli_arguments(raw_arguments: &[String]) -> Result<CliArguments, String>
                     return Err("Failed to read input parameter".to_string());
                 }
             }
-            "-l" | "--list-scenarios" => {
-                cli_arguments.list_scenarios = true;
+            "--input-template" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.input_template = Some(value.clone());
+                } else {
+                    return Err("Failed to read input-template parameter".to_string());
+                }
             }
-            "-h" | "--help" => {
-                cli_arguments.help = true;
+            "--values" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.values = Some(value.clone());
+                } else {
+                    return Err("Failed to read values parameter".to_string());
+                }
             }
-            _ => {
-                return Err(format!("Unknown argument provided: {arg}"));
+            "--concurrency" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.concurrency =
+                        Some(parse_numeric_arg_min("concurrency", value, 1)?);
+                } else {
+                    return Err("Failed to read concurrency parameter".to_string());
+                }
             }
-        }
-    }
-
-    Ok(cli_arguments)
-}
-
-/// Runs CLI application based on provided arguments and test context.
-///
-/// * `raw_arguments` - Collected arguments from `std::env::args()`.
-/// * `test_context` - Test context to use.
-///
-/// # Examples
-///
-/// ```rust
-/// use test_scenarios_rust::test_context::TestContext;
-/// use test_scenarios_rust::scenario::ScenarioGroupImpl;
-/// use test_scenarios_rust::cli::run_cli_app;
-///
-/// let raw_arguments = Vec::from(["example".to_string(), "--list-scenarios".to_string()]);
-/// let root_group = ScenarioGroupImpl::new("root", Vec::new(), Vec::new());
-/// let test_context = TestContext::new(Box::new(root_group));
-///
-/// let result = run_cli_app(&raw_arguments, &test_context);
-/// ```
-pub fn run_cli_app(raw_arguments: &[String], test_context: &TestContext) -> Result<(), String> {
-    // Parse CLI arguments.
-    let cli_arguments = parse_cli_arguments(raw_arguments)?;
-
-    // Show help and return.
-    if cli_arguments.help {
-        eprintln!("Test scenario runner");
-        eprintln!("'-n', '--name' - test scenario name");
-        eprintln!("'-i', '--input' - test scenario input");
-        eprintln!("'-l', '--list-scenarios' - list available scenarios");
-        eprintln!("'-h', '--help' - show help");
-        return Ok(());
-    }
-
-    // List scenarios and return.
-    if cli_arguments.list_scenarios {
-        let scenario_names = test_context.list_scenarios();
-        for scenario_name in scenario_names {
-            println!("{scenario_name}");
-        }
-        return Ok(());
-    }
-
-    // Find scenario.
-    let scenario = cli_arguments.scenario_arguments;
-    let scenario_name = match scenario.name {
-        Some(n) => {
-            if n.is_empty() {
-                return Err("Test scenario name must not be empty".to_string());
-            } else {
-                n
+            "--timeout" => {
+                if let Some(value) = args_it.next() {
+                    let seconds = parse_numeric_arg::<f64>("timeout", value)?;
+                    match Duration::try_from_secs_f64(seconds) {
+                        Ok(timeout) if !timeout.is_zero() => {
+                            cli_arguments.scenario_arguments.timeout = Some(timeout)
+                        }
+                        _ => {
+                            return Err(format!(
+                                "--timeout must be a positive number of seconds, got '{value}'"
+                            ))
+                        }
+                    }
+                } else {
+                    return Err("Failed to read timeout parameter".to_string());
+                }
             }
-        }
-        None => return Err("Test scenario name must be provided".to_string()),
-    };
-
-    // Check input is provided.
-    let scenario_input = match scenario.input {
-        Some(input) => input,
-        None => return Err("Test scenario input must be provided".to_string()),
-    };
-
-    test_context.run(&scenario_name, &scenario_input)
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::cli::{parse_cli_arguments, run_cli_app};
-    use crate::scenario::{Scenario, ScenarioGroupImpl};
-    use crate::test_context::TestContext;
-
-    struct ScenarioStub {
-        name: String,
-    }
-
-    impl ScenarioStub {
-        fn new(name: &str) -> Self {
-            Self {
-                name: name.to_string(),
+            "--soft-timeout-ms" => {
+                if let Some(value) = args_it.next() {
+                    let millis = parse_numeric_arg_min("soft-timeout-ms", value, 1)?;
+                    cli_arguments.scenario_arguments.soft_timeout =
+                        Some(Duration::from_millis(millis));
+                } else {
+                    return Err("Failed to read soft-timeout-ms parameter".to_string());
+                }
             }
-        }
-    }
-
+            "--hard-timeout-ms" => {
+                if let Some(value) = args_it.next() {
+                    let millis = parse_numeric_arg_min("hard-timeout-ms", value, 1)?;
+                    cli_arguments.scenario_arguments.timeout = Some(Duration::from_millis(millis));
+                } else {
+                    return Err("Failed to read hard-timeout-ms parameter".to_string());
+                }
+            }
+            "--repeat" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.repeat =
+                        Some(parse_numeric_arg_min("repeat", value, 1)?);
+                } else {
+                    return Err("Failed to read repeat parameter".to_string());
+                }
+            }
+            "--until-duration" => {
+                if let Some(value) = args_it.next() {
+                    let seconds = parse_numeric_arg::<f64>("until-duration", value)?;
+                    match Duration::try_from_secs_f64(seconds) {
+                        Ok(duration) if !duration.is_zero() => {
+                            cli_arguments.scenario_arguments.until_duration = Some(duration)
+                        }
+                        _ => {
+                            return Err(format!(
+                            "--until-duration must be a positive number of seconds, got '{value}'"
+                        ))
+                        }
+                    }
+                } else {
+                    return Err("Failed to read until-duration parameter".to_string());
+                }
+            }
+            "--warmup" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.warmup =
+                        Some(parse_numeric_arg("warmup", value)?);
+                } else {
+                    return Err("Failed to read warmup parameter".to_string());
+                }
+            }
+            "--flakiness-report" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.flakiness_report = Some(value.clone());
+                } else {
+                    return Err("Failed to read flakiness-report parameter".to_string());
+                }
+            }
+            "--emit-output" => {
+                cli_arguments.scenario_arguments.emit_output = true;
+            }
+            "--seed" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.seed = Some(parse_numeric_arg("seed", value)?);
+                } else {
+                    return Err("Failed to read seed parameter".to_string());
+                }
+            }
+            "--max-failures" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.max_failures =
+                        Some(parse_numeric_arg_min("max-failures", value, 1)?);
+                } else {
+                    return Err("Failed to read max-failures parameter".to_string());
+                }
+            }
+            "--retries" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.retries =
+                        Some(parse_numeric_arg("retries", value)?);
+                } else {
+                    return Err("Failed to read retries parameter".to_string());
+                }
+            }
+            "--retry-if-contains" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.retry_if_contains = Some(value.clone());
+                } else {
+                    return Err("Failed to read retry-if-contains parameter".to_string());
+                }
+            }
+            "--global-timeout" => {
+                if let Some(value) = args_it.next() {
+                    let seconds = parse_numeric_arg::<f64>("global-timeout", value)?;
+                    match Duration::try_from_secs_f64(seconds) {
+                        Ok(timeout) if !timeout.is_zero() => {
+                            cli_arguments.scenario_arguments.global_timeout = Some(timeout)
+                        }
+                        _ => {
+                            return Err(format!(
+                            "--global-timeout must be a positive number of seconds, got '{value}'"
+                        ))
+                        }
+                    }
+                } else {
+                    return Err("Failed to read global-timeout parameter".to_string());
+                }
+            }
+            "--max-input-bytes" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.max_input_bytes =
+                        Some(parse_numeric_arg_min("max-input-bytes", value, 1)?);
+                } else {
+                    return Err("Failed to read max-input-bytes parameter".to_string());
+                }
+            }
+            "--bisect" => {
+                cli_arguments.scenario_arguments.bisect = true;
+            }
+            "--isolate" => {
+                cli_arguments.scenario_arguments.isolate = true;
+            }
+            "--process-group" => {
+                cli_arguments.scenario_arguments.process_group = true;
+            }
+            "--resource-stats" => {
+                cli_arguments.scenario_arguments.resource_stats = true;
+            }
+            "--validate-inputs" => {
+                cli_arguments.scenario_arguments.validate_inputs = true;
+            }
+            "--workdir" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.scenario_arguments.workdir = Some(value.clone());
+                } else {
+                    return Err("Failed to read workdir parameter".to_string());
+                }
+            }
+            "--playlist" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.playlist = Some(value.clone());
+                } else {
+                    return Err("Failed to read playlist parameter".to_string());
+                }
+            }
+            "--matrix" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.matrix = Some(value.clone());
+                } else {
+                    return Err("Failed to read matrix parameter".to_string());
+                }
+            }
+            "--manifest" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.manifest = Some(value.clone());
+                } else {
+                    return Err("Failed to read manifest parameter".to_string());
+                }
+            }
+            "--stdin-ndjson" => {
+                cli_arguments.stdin_ndjson = true;
+            }
+            "--rerun-failed" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.rerun_failed = Some(value.clone());
+                } else {
+                    return Err("Failed to read rerun failed parameter".to_string());
+                }
+            }
+            "--report-tap" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.report_tap = Some(value.clone());
+                } else {
+                    return Err("Failed to read report-tap parameter".to_string());
+                }
+            }
+            "--report-csv" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.report_csv = Some(value.clone());
+                } else {
+                    return Err("Failed to read report-csv parameter".to_string());
+                }
+            }
+            "--output-dir" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.output_dir = Some(value.clone());
+                } else {
+                    return Err("Failed to read output-dir parameter".to_string());
+                }
+            }
+            "--log-level" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.tracing_config.level = Level::from_str(value)
+                        .map_err(|_| format!("Invalid log level: {value}"))?;
+                } else {
+                    return Err("Failed to read log-level parameter".to_string());
+                }
+            }
+            "--color" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.color = ColorMode::from_str(value)?;
+                } else {
+                    return Err("Failed to read color parameter".to_string());
+                }
+            }
+            "--error-annotation" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.error_annotation = ErrorAnnotation::from_str(value)?;
+                } else {
+                    return Err("Failed to read error-annotation parameter".to_string());
+                }
+            }
+            "--expect" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.expect = Some(Expected::from_str(value)?);
+                } else {
+                    return Err("Failed to read expect parameter".to_string());
+                }
+            }
+            "--expect-error-contains" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.expect_error_contains = Some(value.clone());
+                } else {
+                    return Err("Failed to read expect-error-contains parameter".to_string());
+                }
+            }
+            "--log-nonblocking" => {
+                cli_arguments.tracing_config.nonblocking = true;
+            }
+            "--log-thread-ids" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.tracing_config.thread_ids = bool::from_str(value)
+                        .map_err(|_| format!("Invalid log-thread-ids parameter: {value}"))?;
+                } else {
+                    return Err("Failed to read log-thread-ids parameter".to_string());
+                }
+            }
+            "--log-format" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.tracing_config.format = LogFormat::from_str(value)?;
+                } else {
+                    return Err("Failed to read log-format parameter".to_string());
+                }
+            }
+            "--timestamp-mode" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.tracing_config.timestamp_mode = TimestampMode::from_str(value)?;
+                } else {
+                    return Err("Failed to read timestamp-mode parameter".to_string());
+                }
+            }
+            "--log-output" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.tracing_config.output = LogOutput::from_str(value)?;
+                } else {
+                    return Err("Failed to read log-output parameter".to_string());
+                }
+            }
+            "--tag" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.filter.tags.push(value.clone());
+                } else {
+                    return Err("Failed to read tag parameter".to_string());
+                }
+            }
+            "--skip" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.filter.skip.push(value.clone());
+                } else {
+                    return Err("Failed to read skip parameter".to_string());
+                }
+            }
+            "--shard" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.filter.shard = Some(Shard::from_str(value)?);
+                } else {
+                    return Err("Failed to read shard parameter".to_string());
+                }
+            }
+            "--enable" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments
+                        .scenario_arguments
+                        .toggles
+                        .enable
+                        .push(value.clone());
+                } else {
+                    return Err("Failed to read enable parameter".to_string());
+                }
+            }
+            "--disable" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments
+                        .scenario_arguments
+                        .toggles
+                        .disable
+                        .push(value.clone());
+                } else {
+                    return Err("Failed to read disable parameter".to_string());
+                }
+            }
+            "--markers" => {
+                cli_arguments.markers = true;
+            }
+            "--summary-by-tag" => {
+                cli_arguments.summary_by_tag = true;
+            }
+            "--stream-results" => {
+                cli_arguments.stream_results = true;
+            }
+            "--max-reported-input" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.max_reported_input =
+                        Some(parse_numeric_arg("max-reported-input", value)?);
+                } else {
+                    return Err("Failed to read max-reported-input parameter".to_string());
+                }
+            }
+            "--print-config" => {
+                cli_arguments.print_config = true;
+            }
+            "--json-errors" => {
+                cli_arguments.json_errors = true;
+            }
+            "--trace-args" => {
+                cli_arguments.trace_args = true;
+            }
+            "--event-log" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.event_log = Some(value.clone());
+                } else {
+                    return Err("Failed to read event-log parameter".to_string());
+                }
+            }
+            "--count" => {
+                cli_arguments.count = true;
+            }
+            "--explain" => {
+                cli_arguments.explain = true;
+            }
+            "--list-tree" => {
+                cli_arguments.list_tree = true;
+            }
+            "--catalog-ndjson" => {
+                cli_arguments.catalog_ndjson = true;
+            }
+            "--counts" => {
+                cli_arguments.counts = true;
+            }
+            "--quarantine-file" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.quarantine_file = Some(value.clone());
+                } else {
+                    return Err("Failed to read quarantine-file parameter".to_string());
+                }
+            }
+            "--result-file" => {
+                if let Some(value) = args_it.next() {
+                    cli_arguments.result_file = Some(value.clone());
+                } else {
+                    return Err("Failed to read result-file parameter".to_string());
+                }
+            }
+            "--run-id" => {
+                if let Some(value) = args_it.next() {
+                    if value.is_empty() {
+                        return Err(format!("Invalid run-id parameter: {value}"));
+                    }
+                    cli_arguments.run_id = Some(value.clone());
+                } else {
+                    return Err("Failed to read run-id parameter".to_string());
+                }
+            }
+            "-l" | "--list-scenarios" => {
+                cli_arguments.list_scenarios = true;
+            }
+            "-h" | "--help" => {
+                cli_arguments.help = true;
+            }
+            _ if is_short_flag_bundle(arg) => {
+                apply_short_flag_bundle(arg, cli_arguments)?;
+            }
+            _ => {
+                return Err(format!("Unknown argument provided: {arg}"));
+            }
+        }
+
+        // Values consumed by the argument are recorded for argument tracing.
+        let consumed = values_start.len() - args_it.as_slice().len();
+        cli_arguments
+            .parsed_arguments
+            .push((option.to_string(), values_start[..consumed].to_vec()));
+    }
+    // Traced arguments are logged at debug level, so less verbose levels are raised.
+    let level = &mut cli_arguments.tracing_config.level;
+    if cli_arguments.trace_args && *level != Level::TRACE && *level != Level::DEBUG {
+        *level = Level::DEBUG;
+    }
+
+    validate_cli_arguments(cli_arguments)
+}
+
+/// Check that CLI arguments are valid and consistent with each other.
+/// Arguments set with [`CliArgumentsBuilder`] are not validated when set, so they are checked here.
+///
+/// * `cli_arguments` - CLI arguments to check.
+fn validate_cli_arguments(cli_arguments: &CliArguments) -> Result<(), String> {
+    // Help takes precedence over all other arguments, so conflicts are not reported.
+    if !cli_arguments.help {
+        check_conflicts(cli_arguments)?;
+    }
+    let scenario_arguments = &cli_arguments.scenario_arguments;
+    for (name, value) in [
+        ("repeat", scenario_arguments.repeat),
+        ("concurrency", scenario_arguments.concurrency),
+    ] {
+        if value == Some(0) {
+            return Err(format!("--{name} must be at least 1, got '0'"));
+        }
+    }
+    if scenario_arguments
+        .timeout
+        .is_some_and(|timeout| timeout.is_zero())
+    {
+        return Err("--timeout must be a positive number of seconds, got '0'".to_string());
+    }
+    if cli_arguments.run_id.as_ref().is_some_and(String::is_empty) {
+        return Err("Invalid run-id parameter: ".to_string());
+    }
+    if cli_arguments.counts && !cli_arguments.list_tree {
+        return Err("--counts requires --list-tree".to_string());
+    }
+    if cli_arguments.expect_error_contains.is_some() && cli_arguments.expect != Some(Expected::Fail)
+    {
+        return Err("--expect-error-contains requires --expect fail".to_string());
+    }
+    if scenario_arguments.retry_if_contains.is_some() && scenario_arguments.retries.is_none() {
+        return Err("--retry-if-contains requires --retries".to_string());
+    }
+    if scenario_arguments.process_group && !scenario_arguments.isolate {
+        return Err("--process-group requires --isolate".to_string());
+    }
+    if let Some(pattern) = &scenario_arguments.name_regex {
+        if !scenario_arguments.names.is_empty() {
+            return Err("--name-regex cannot be combined with --name".to_string());
+        }
+        Regex::new(pattern).map_err(|e| format!("Invalid --name-regex pattern: {e}"))?;
+    }
+    if cli_arguments.explain && scenario_arguments.names.is_empty() {
+        return Err("--explain requires --name".to_string());
+    }
+    if cli_arguments.values.is_some() && cli_arguments.input_template.is_none() {
+        return Err("--values requires --input-template".to_string());
+    }
+    if cli_arguments.input_template.is_some() && cli_arguments.scenario_arguments.input.is_some() {
+        return Err("Input template cannot be combined with test scenario input".to_string());
+    }
+
+    // Validate timeouts.
+    let scenario_arguments = &cli_arguments.scenario_arguments;
+    if let (Some(soft_timeout), Some(hard_timeout)) =
+        (scenario_arguments.soft_timeout, scenario_arguments.timeout)
+    {
+        if soft_timeout > hard_timeout {
+            return Err("Soft timeout must not exceed hard timeout".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Fill in test scenario name and input from environment variables.
+/// Values provided with CLI arguments take precedence.
+/// Name is not taken from environment for run sources providing their own names,
+/// i.e., playlist, manifest, stdin NDJSON and rerun of failed scenarios.
+/// Input is not taken from environment for manifest and stdin NDJSON runs, they provide inputs.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+/// * `env_var` - Lookup of environment variables, see [`CliConfig::env_var`].
+fn apply_env_defaults(cli_arguments: &mut CliArguments, env_var: fn(&str) -> Option<String>) {
+    let scenario_arguments = &mut cli_arguments.scenario_arguments;
+    if scenario_arguments.names.is_empty()
+        && scenario_arguments.name_regex.is_none()
+        && cli_arguments.playlist.is_none()
+        && cli_arguments.manifest.is_none()
+        && cli_arguments.rerun_failed.is_none()
+        && !cli_arguments.stdin_ndjson
+    {
+        if let Some(name) = env_var(NAME_ENV_VAR) {
+            scenario_arguments.names.push(name);
+        }
+    }
+    if scenario_arguments.input.is_none()
+        && cli_arguments.manifest.is_none()
+        && !cli_arguments.stdin_ndjson
+    {
+        scenario_arguments.input = env_var(INPUT_ENV_VAR);
+    }
+}
+
+/// Create JSON with effective configuration, with defaults and environment fallbacks resolved.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+fn config_json(cli_arguments: &CliArguments) -> Value {
+    let scenario = &cli_arguments.scenario_arguments;
+    let tracing_config = &cli_arguments.tracing_config;
+    // Configuration is created in two parts, single `json!` would exceed macro recursion limit.
+    let mut config = json!({
+        "names": scenario.names,
+        "input": scenario.input,
+        "concurrency": scenario.concurrency,
+        "timeout": scenario.timeout.map(|timeout| timeout.as_secs_f64()),
+        "soft_timeout": scenario.soft_timeout.map(|timeout| timeout.as_secs_f64()),
+        "repeat": scenario.repeat,
+        "until_duration": scenario
+            .until_duration
+            .map(|duration| duration.as_secs_f64()),
+        "warmup": scenario.warmup,
+        "flakiness_report": scenario.flakiness_report,
+        "emit_output": scenario.emit_output,
+        "seed": scenario.seed,
+        "bisect": scenario.bisect,
+        "max_failures": scenario.max_failures,
+        "global_timeout": scenario.global_timeout.map(|timeout| timeout.as_secs_f64()),
+        "retries": scenario.retries,
+        "retry_if_contains": scenario.retry_if_contains,
+        "isolate": scenario.isolate,
+        "name_regex": scenario.name_regex,
+        "process_group": scenario.process_group,
+        "resource_stats": scenario.resource_stats,
+        "validate_inputs": scenario.validate_inputs,
+        "max_input_bytes": scenario.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES),
+        "workdir": scenario.workdir,
+        "enable": scenario.toggles.enable,
+        "disable": scenario.toggles.disable,
+    });
+    let other_config = json!({
+        "playlist": cli_arguments.playlist,
+        "matrix": cli_arguments.matrix,
+        "manifest": cli_arguments.manifest,
+        "rerun_failed": cli_arguments.rerun_failed,
+        "stdin_ndjson": cli_arguments.stdin_ndjson,
+        "input_template": cli_arguments.input_template,
+        "values": cli_arguments.values,
+        "report_tap": cli_arguments.report_tap,
+        "report_csv": cli_arguments.report_csv,
+        "output_dir": cli_arguments.output_dir,
+        "log": {
+            "level": tracing_config.level.to_string().to_lowercase(),
+            "format": tracing_config.format.to_string(),
+            "timestamp_mode": tracing_config.timestamp_mode.to_string(),
+            "output": tracing_config.output.to_string(),
+            "nonblocking": tracing_config.nonblocking,
+            "thread_ids": tracing_config.thread_ids,
+            "event_log": cli_arguments.event_log,
+        },
+        "tags": cli_arguments.filter.tags,
+        "skip": cli_arguments.filter.skip,
+        "shard": cli_arguments.filter.shard.map(|shard| shard.to_string()),
+        "markers": cli_arguments.markers,
+        "stream_results": cli_arguments.stream_results,
+        "summary_by_tag": cli_arguments.summary_by_tag,
+        "explain": cli_arguments.explain,
+        "max_reported_input": cli_arguments
+            .max_reported_input
+            .unwrap_or(DEFAULT_MAX_REPORTED_INPUT),
+        "color": cli_arguments.color.to_string(),
+        "error_annotation": cli_arguments.error_annotation.to_string(),
+        "expect": cli_arguments.expect.map(|expect| expect.to_string()),
+        "expect_error_contains": cli_arguments.expect_error_contains,
+        "quarantine_file": cli_arguments.quarantine_file,
+        "run_id": cli_arguments.run_id,
+        "result_file": cli_arguments.result_file,
+        "json_errors": cli_arguments.json_errors,
+        "trace_args": cli_arguments.trace_args,
+    });
+    if let (Value::Object(config), Value::Object(other_config)) = (&mut config, other_config) {
+        config.extend(other_config);
+    }
+    config
+}
+
+/// Log each parsed argument with its values and resulting configuration at debug level.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+fn trace_arguments(cli_arguments: &CliArguments) {
+    for (option, values) in &cli_arguments.parsed_arguments {
+        if values.is_empty() {
+            log_debug!("Parsed argument {option}");
+        } else {
+            log_debug!("Parsed argument {option}: {}", values.join(" "));
+        }
+    }
+    log_debug!("Parsed configuration: {}", config_json(cli_arguments));
+}
+
+/// Initialize tracing, unless global subscriber was already set by the caller.
+/// Returns guard of the non-blocking writer, which must be kept alive until the end of the run.
+///
+/// * `tracing_config` - Tracing configuration.
+/// * `event_log` - Event log capturing all events, if requested.
+fn init_run_tracing(
+    tracing_config: &TracingConfig,
+    event_log: Option<&EventLog>,
+) -> Option<LogGuard> {
+    if tracing_initialized() {
+        return None;
+    }
+    // Might still fail if global subscriber was set in the meantime, this is not an error.
+    match event_log {
+        Some(event_log) => init_tracing_with_event_log(tracing_config, event_log),
+        None => init_tracing(tracing_config),
+    }
+    .ok()
+    .flatten()
+}
+
+/// Event log written to file when dropped, so it is written on every return path of the run.
+struct EventLogWriter<'a, 'w> {
+    /// Event log capturing all events.
+    event_log: EventLog,
+
+    /// Path to event log file.
+    path: String,
+
+    /// Write events as NDJSON instead of JSON array.
+    ndjson: bool,
+
+    /// Output errors of writing the event log are printed to.
+    err: &'a SharedWriter<'w>,
+}
+
+impl Drop for EventLogWriter<'_, '_> {
+    fn drop(&mut self) {
+        let result = if self.ndjson {
+            self.event_log.write_ndjson(&self.path)
+        } else {
+            self.event_log.write(&self.path)
+        };
+        if let Err(e) = result {
+            let _ = writeln!(&mut { self.err }, "{e}");
+        }
+    }
+}
+
+/// Name of the log file of a scenario, characters other than alphanumerics, `.`, `_` and `-`
+/// are replaced with `_`.
+/// If any character is replaced, stable hash of the name is appended, so names differing only
+/// in replaced characters, e.g., `a/b` and `a_b`, are written to different files.
+///
+/// * `name` - Name of the scenario.
+fn scenario_log_file_name(name: &str) -> String {
+    let file_name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    if file_name == name {
+        format!("{file_name}.log")
+    } else {
+        format!("{file_name}-{:016x}.log", stable_hash(name))
+    }
+}
+
+/// Outcome of a scenario as judged by the run, used by output directory reports.
+/// Failures of quarantined scenarios are skipped. With `--expect fail`, failures meeting
+/// the expectation pass and other outcomes fail.
+///
+/// * `result` - Result of the scenario run.
+/// * `quarantine` - Names of quarantined scenarios.
+/// * `expect_fail` - Scenarios are expected to fail.
+/// * `error_contains` - Text expected in each error, used with `expect_fail`.
+fn judged_outcome(
+    result: &RunResult,
+    quarantine: &[String],
+    expect_fail: bool,
+    error_contains: Option<&str>,
+) -> Outcome {
+    let (name, outcome) = (&result.name, &result.outcome);
+    if expect_fail {
+        return match unmet_failure_expectation(name, outcome, error_contains) {
+            Some(message) => Outcome::Failed(message),
+            None => Outcome::Passed,
+        };
+    }
+    match report_status(name, outcome, quarantine) {
+        "quarantined-failed" => Outcome::Skipped(format!("quarantined-failed: {outcome}")),
+        _ => outcome.clone(),
+    }
+}
+
+/// Write run artifacts into output directory:
+/// - `summary.json` - run statistics and result of each scenario,
+///   `expected` tells whether the outcome meets expectation of the run,
+///   `input` is not truncated and `not_run` marks scenarios skipped without being run,
+///   so scenarios which did not pass can be run again with `--rerun-failed`,
+/// - `junit.xml` - JUnit XML report with outcomes judged by quarantine and `--expect`,
+/// - `<name>.log` - result details of each scenario, all runs of the scenario in order.
+///
+/// Event log `events.ndjson` is written separately, when the run ends.
+///
+/// * `output_dir` - Output directory, must exist.
+/// * `results` - Results of run scenarios.
+/// * `max_reported_input` - Maximum number of reported input characters.
+/// * `quarantine` - Names of quarantined scenarios.
+/// * `expect_fail` - Scenarios are expected to fail, see `--expect`.
+/// * `error_contains` - Text expected in each error, see `--expect-error-contains`.
+fn write_output_dir(
+    output_dir: &Path,
+    results: &[RunResult],
+    max_reported_input: usize,
+    quarantine: &[String],
+    expect_fail: bool,
+    error_contains: Option<&str>,
+) -> Result<(), String> {
+    let write = |file_name: &str, content: String| {
+        std::fs::write(output_dir.join(file_name), content)
+            .map_err(|e| format!("Failed to write {file_name}: {e}"))
+    };
+    let judged: Vec<Outcome> = results
+        .iter()
+        .map(|result| judged_outcome(result, quarantine, expect_fail, error_contains))
+        .collect();
+
+    let summary_results: Vec<Value> = results
+        .iter()
+        .zip(&judged)
+        .map(|(result, judged)| {
+            let (name, outcome) = (&result.name, &result.outcome);
+            let error = (!outcome.is_ok()).then(|| outcome.to_string());
+            json!({
+                "name": name,
+                "status": report_status(name, outcome, quarantine),
+                "duration_us": result.duration.as_micros() as u64,
+                "error": error,
+                "input": result.input,
+                "not_run": is_not_run(outcome),
+                "expected": judged.is_ok(),
+                "slow": result.slow,
+                "metrics": metrics_json(&result.metrics),
+            })
+        })
+        .collect();
+    let summary = json!({
+        "stats": RunStats::from_outcomes_with_quarantine(results, quarantine).to_json(),
+        "results": summary_results,
+    });
+    write("summary.json", summary.to_string())?;
+
+    let junit_results: Vec<(String, Outcome, Duration)> = results
+        .iter()
+        .zip(judged)
+        .map(|(result, judged)| (result.name.clone(), judged, result.duration))
+        .collect();
+    write("junit.xml", to_junit(&junit_results))?;
+
+    let mut logs: BTreeMap<String, String> = BTreeMap::new();
+    for result in results {
+        let log = logs
+            .entry(scenario_log_file_name(&result.name))
+            .or_default();
+        if !log.is_empty() {
+            log.push('\n');
+        }
+        log.push_str(&format!("name: {}\n", result.name));
+        log.push_str(&format!("status: {}\n", result.outcome.status()));
+        log.push_str(&format!("duration_us: {}\n", result.duration.as_micros()));
+        let input = truncate_input(&result.input, max_reported_input);
+        log.push_str(&format!("input: {input}\n"));
+        if result.outcome != Outcome::Passed {
+            log.push_str(&format!("outcome: {}\n", result.outcome));
+        }
+        for (key, value) in &result.metrics {
+            log.push_str(&format!("metric {key}: {value}\n"));
+        }
+    }
+    for (file_name, log) in logs {
+        write(&file_name, log)?;
+    }
+    Ok(())
+}
+
+/// Runs CLI application based on provided arguments and test context.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+///
+/// # Examples
+///
+/// ```rust
+/// use test_scenarios_rust::test_context::TestContext;
+/// use test_scenarios_rust::scenario::ScenarioGroupImpl;
+/// use test_scenarios_rust::cli::run_cli_app;
+///
+/// let raw_arguments = Vec::from(["example".to_string(), "--list-scenarios".to_string()]);
+/// let root_group = ScenarioGroupImpl::new("root", Vec::new(), Vec::new());
+/// let test_context = TestContext::new(Box::new(root_group));
+///
+/// let result = run_cli_app(&raw_arguments, &test_context);
+/// ```
+pub fn run_cli_app(raw_arguments: &[String], test_context: &TestContext) -> Result<(), CliError> {
+    run_cli_app_with_config(raw_arguments, test_context, &CliConfig::default())
+}
+
+/// Runs CLI application based on provided arguments, test context and application configuration.
+/// Same as [`run_cli_app`], with help rendered using `config`.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+pub fn run_cli_app_with_config(
+    raw_arguments: &[String],
+    test_context: &TestContext,
+    config: &CliConfig,
+) -> Result<(), CliError> {
+    run_cli_app_with_io(
+        raw_arguments,
+        test_context,
+        config,
+        &mut BufReader::new(std::io::stdin()),
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+    )
+}
+
+/// Runs CLI application based on provided arguments, test context and application configuration.
+/// All output of the application, e.g., help, listings, markers, streamed results and errors,
+/// is written to provided outputs instead of stdout and stderr.
+/// Output printed by scenarios themselves and logs are not redirected.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+/// * `input` - Input replacing stdin, read by `--stdin-ndjson`.
+/// * `out` - Output replacing stdout.
+/// * `err` - Output replacing stderr.
+pub fn run_cli_app_with_io(
+    raw_arguments: &[String],
+    test_context: &TestContext,
+    config: &CliConfig,
+    input: &mut (dyn BufRead + Send),
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+) -> Result<(), CliError> {
+    run_cli_app_with_stats(
+        raw_arguments,
+        test_context,
+        config,
+        input,
+        out,
+        err,
+        &mut AppReport::default(),
+    )
+}
+
+/// Default process exit code of CLI application run, 0 if the run passed,
+/// [`CliError::exit_code`] otherwise.
+///
+/// * `result` - Result of the run, e.g., returned by [`run_cli_app`].
+pub fn default_exit_code(result: &Result<(), CliError>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => ExitCode::from(error.exit_code() as u8),
+    }
+}
+
+/// Runs CLI application based on provided arguments and test context, returning process exit code.
+/// Statistics of the run scenarios are mapped to exit code by `exit_code`,
+/// [`default_exit_code`] is used if it returns `None`.
+/// Mapping returns `Option<ExitCode>` rather than `ExitCode`, so it only has to handle
+/// runs it assigns own meaning to, instead of reimplementing the default mapping,
+/// which also depends on the error of the run not available in statistics.
+/// Mapping is not used for usage and I/O errors, exceeded global timeout and runs without scenarios,
+/// e.g., listing. Errors are printed to stderr.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+/// * `exit_code` - Mapping of statistics of the run scenarios to exit code.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::process::ExitCode;
+/// use test_scenarios_rust::test_context::TestContext;
+/// use test_scenarios_rust::scenario::ScenarioGroupImpl;
+/// use test_scenarios_rust::cli::run_cli_app_exit;
+///
+/// let raw_arguments = Vec::from(["example".to_string(), "--list-scenarios".to_string()]);
+/// let root_group = ScenarioGroupImpl::new("root", Vec::new(), Vec::new());
+/// let test_context = TestContext::new(Box::new(root_group));
+///
+/// // Runs in which scenarios only timed out are retried by CI.
+/// let exit_code = run_cli_app_exit(&raw_arguments, &test_context, |stats| {
+///     (stats.failed == 0 && stats.timed_out > 0).then(|| ExitCode::from(75))
+/// });
+/// ```
+pub fn run_cli_app_exit(
+    raw_arguments: &[String],
+    test_context: &TestContext,
+    exit_code: impl Fn(&RunStats) -> Option<ExitCode>,
+) -> ExitCode {
+    run_cli_app_exit_with_io(
+        raw_arguments,
+        test_context,
+        &CliConfig::default(),
+        &mut BufReader::new(std::io::stdin()),
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+        exit_code,
+    )
+}
+
+/// Same as [`run_cli_app_exit`], with provided application configuration and outputs.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+/// * `input` - Input replacing stdin, read by `--stdin-ndjson`.
+/// * `out` - Output replacing stdout.
+/// * `err` - Output replacing stderr.
+/// * `exit_code` - Mapping of statistics of the run scenarios to exit code.
+fn run_cli_app_exit_with_io(
+    raw_arguments: &[String],
+    test_context: &TestContext,
+    config: &CliConfig,
+    input: &mut (dyn BufRead + Send),
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+    exit_code: impl Fn(&RunStats) -> Option<ExitCode>,
+) -> ExitCode {
+    let mut report = AppReport::default();
+    let result = run_cli_app_with_stats(
+        raw_arguments,
+        test_context,
+        config,
+        input,
+        out,
+        err,
+        &mut report,
+    );
+    // JSON errors are already printed.
+    if let Err(error) = &result {
+        if !report.json_errors {
+            let _ = writeln!(err, "{error}");
+        }
+    }
+    let mapped = match (&result, report.stats) {
+        (Err(CliError::Usage(_) | CliError::GlobalTimeout(_) | CliError::Io(_)), _) | (_, None) => {
+            None
+        }
+        (_, Some(stats)) => exit_code(&stats),
+    };
+    mapped.unwrap_or_else(|| default_exit_code(&result))
+}
+
+/// Report of CLI application run.
+#[derive(Default)]
+struct AppReport {
+    /// Statistics of the run scenarios, `None` if no scenarios are run.
+    stats: Option<RunStats>,
+    /// Errors are printed as JSON, see `--json-errors`.
+    json_errors: bool,
+}
+
+/// Runs CLI application based on provided arguments, test context and application configuration.
+/// Statistics of the run scenarios and error format are stored in `report`.
+///
+/// * `raw_arguments` - Collected arguments from `std::env::args()`.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+/// * `input` - Input replacing stdin, read by `--stdin-ndjson`.
+/// * `out` - Output replacing stdout.
+/// * `err` - Output replacing stderr.
+/// * `report` - Report of the run.
+fn run_cli_app_with_stats(
+    raw_arguments: &[String],
+    test_context: &TestContext,
+    config: &CliConfig,
+    input: &mut (dyn BufRead + Send),
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+    report: &mut AppReport,
+) -> Result<(), CliError> {
+    // Parse CLI arguments.
+    // If parsing fails, `--json-errors` is honored if it precedes the invalid argument.
+    let mut cli_arguments = CliArguments::default();
+    let parsed = parse_cli_arguments_into(raw_arguments, &mut cli_arguments);
+    report.json_errors = cli_arguments.json_errors;
+    match parsed {
+        Ok(()) => run_cli_with_io(
+            cli_arguments,
+            test_context,
+            config,
+            input,
+            out,
+            err,
+            &mut report.stats,
+        ),
+        Err(e) => {
+            let error = CliError::Usage(e);
+            if report.json_errors {
+                let _ = writeln!(err, "{}", error.to_json());
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Runs CLI application with provided arguments and test context.
+///
+/// * `cli_arguments` - CLI arguments, e.g., created with [`CliArguments::builder`].
+/// * `test_context` - Test context to use.
+///
+/// # Examples
+///
+/// ```rust
+/// use test_scenarios_rust::test_context::TestContext;
+/// use test_scenarios_rust::scenario::ScenarioGroupImpl;
+/// use test_scenarios_rust::cli::{run_cli, CliArguments};
+///
+/// let cli_arguments = CliArguments::builder().list_scenarios().build();
+/// let root_group = ScenarioGroupImpl::new("root", Vec::new(), Vec::new());
+/// let test_context = TestContext::new(Box::new(root_group));
+///
+/// let result = run_cli(cli_arguments, &test_context);
+/// ```
+pub fn run_cli(cli_arguments: CliArguments, test_context: &TestContext) -> Result<(), CliError> {
+    run_cli_with_io(
+        cli_arguments,
+        test_context,
+        &CliConfig::default(),
+        &mut BufReader::new(std::io::stdin()),
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+        &mut None,
+    )
+}
+
+/// Run CLI application with parsed arguments, application configuration and outputs.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+/// * `input` - Input replacing stdin, read by `--stdin-ndjson`.
+/// * `out` - Output replacing stdout.
+/// * `err` - Output replacing stderr.
+/// * `stats` - Statistics of the run scenarios, set if scenarios are run.
+fn run_cli_with_io(
+    cli_arguments: CliArguments,
+    test_context: &TestContext,
+    config: &CliConfig,
+    input: &mut (dyn BufRead + Send),
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+    stats: &mut Option<RunStats>,
+) -> Result<(), CliError> {
+    let json_errors = cli_arguments.json_errors;
+    let result_file = cli_arguments.result_file.clone();
+    validate_cli_arguments(&cli_arguments)
+        .map_err(CliError::Usage)
+        .and_then(|()| run_parsed(cli_arguments, test_context, config, input, out, err, stats))
+        .inspect_err(|error| {
+            if json_errors {
+                let _ = writeln!(err, "{}", error.to_json());
+            }
+            // Errors of runs with results are reported by outcomes of the results.
+            if let (Some(result_file), None) = (&result_file, &stats) {
+                append_result_lines(result_file, [json!({"error": error.to_string()})]);
+            }
+        })
+}
+
+/// CLI application output shared by observers, possibly notified from multiple threads.
+/// Each write is done under lock.
+struct SharedWriter<'a> {
+    writer: Mutex<&'a mut (dyn Write + Send)>,
+}
+
+impl<'a> SharedWriter<'a> {
+    /// Create shared output.
+    ///
+    /// * `writer` - Output to share.
+    fn new(writer: &'a mut (dyn Write + Send)) -> Self {
+        SharedWriter {
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+impl Write for &SharedWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .write(buf)
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> std::io::Result<()> {
+        // Formatted line is written at once, so lines of parallel runs are not interleaved.
+        self.writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .write_fmt(args)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .flush()
+    }
+}
+
+/// Error of writing to CLI application output.
+fn output_error(e: std::io::Error) -> CliError {
+    CliError::Io(format!("Failed to write output: {e}"))
+}
+
+/// Run CLI application with parsed arguments.
+///
+/// * `cli_arguments` - Parsed CLI arguments.
+/// * `test_context` - Test context to use.
+/// * `config` - CLI application configuration.
+/// * `input` - Input replacing stdin, read by `--stdin-ndjson`.
+/// * `out` - Output replacing stdout.
+/// * `err` - Output replacing stderr.
+/// * `stats` - Statistics of the run scenarios, set if scenarios are run.
+fn run_parsed(
+    mut cli_arguments: CliArguments,
+    test_context: &TestContext,
+    config: &CliConfig,
+    input: &mut (dyn BufRead + Send),
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+    stats: &mut Option<RunStats>,
+) -> Result<(), CliError> {
+    // Outputs are shared with observers and event log writer.
+    let (shared_out, shared_err) = (SharedWriter::new(out), SharedWriter::new(err));
+    let (mut out, mut err) = (&shared_out, &shared_err);
+
+    // Show help and return.
+    // Help takes precedence over all other arguments, nothing else is read, printed or logged.
+    if cli_arguments.help {
+        write_help(&mut err, config).map_err(output_error)?;
+        return Ok(());
+    }
+
+    // Global timeout covers the whole invocation, including reading input files.
+    let scenario_arguments = &mut cli_arguments.scenario_arguments;
+    scenario_arguments.deadline = scenario_arguments
+        .global_timeout
+        .map(|timeout| Instant::now() + timeout);
+
+    // Output directory is created first, event log might be written into it.
+    if let Some(output_dir) = &cli_arguments.output_dir {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| CliError::Io(format!("Failed to create output directory: {e}")))?;
+    }
+
+    // Event log can only be captured by subscriber initialized here.
+    // Event log of output directory is written even if empty, to keep its layout.
+    let event_log_writer = match (&cli_arguments.event_log, &cli_arguments.output_dir) {
+        (Some(_), _) if tracing_initialized() => {
+            log_warn!("Event log is not written, tracing subscriber was already set");
+            None
+        }
+        (Some(path), _) => Some(EventLogWriter {
+            event_log: EventLog::default(),
+            path: path.clone(),
+            ndjson: false,
+            err: &shared_err,
+        }),
+        (None, Some(output_dir)) => {
+            if tracing_initialized() {
+                log_warn!("Event log is empty, tracing subscriber was already set");
+            }
+            Some(EventLogWriter {
+                event_log: EventLog::default(),
+                path: Path::new(output_dir)
+                    .join("events.ndjson")
+                    .to_string_lossy()
+                    .to_string(),
+                ndjson: true,
+                err: &shared_err,
+            })
+        }
+        (None, None) => None,
+    };
+    let event_log = event_log_writer.as_ref().map(|writer| &writer.event_log);
+
+    // Arguments are traced before anything else is done, so tracing is initialized early.
+    let _early_log_guard = if cli_arguments.trace_args {
+        let guard = init_run_tracing(&cli_arguments.tracing_config, event_log);
+        trace_arguments(&cli_arguments);
+        guard
+    } else {
+        None
+    };
+
+    // Template is rendered before environment defaults, so it takes precedence over them.
+    if let Some(template_path) = &cli_arguments.input_template {
+        let input = read_input_template(template_path, cli_arguments.values.as_deref())
+            .map_err(CliError::Usage)?;
+        cli_arguments.scenario_arguments.input = Some(input);
+    }
+    apply_env_defaults(&mut cli_arguments, config.env_var);
+
+    // Print configuration and return.
+    // Done before tracing initialization, so logs are not mixed with configuration.
+    if cli_arguments.print_config {
+        writeln!(out, "{}", config_json(&cli_arguments)).map_err(output_error)?;
+        return Ok(());
+    }
+
+    // Guard of the non-blocking writer is kept alive until the end of the run.
+    let _log_guard = init_run_tracing(&cli_arguments.tracing_config, event_log);
+
+    // Attach run id to all log records of this run.
+    let run_id = cli_arguments.run_id.clone().unwrap_or_else(generate_run_id);
+    cli_arguments.scenario_arguments.isolation_arguments =
+        isolation_arguments(&cli_arguments.tracing_config, &run_id);
+    // Highest level is used, so run id is attached regardless of log level.
+    #[cfg(feature = "tracing")]
+    let _run_span = tracing::span!(Level::ERROR, "run", run_id = %run_id).entered();
+    log_info!("Run {run_id} started");
+    // Monotonic timestamps are related to wall-clock time, so external logs can be aligned.
+    #[cfg(feature = "tracing")]
+    {
+        let clock_start = SystemTime::now() - shared_start().elapsed();
+        let clock_start_us = clock_start
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_micros());
+        log_info!("Monotonic clock started {clock_start_us} us after UNIX epoch");
+    }
+
+    // List scenarios and return.
+    if cli_arguments.list_scenarios {
+        if test_context.count_scenarios() == 0 {
+            writeln!(err, "(no scenarios registered)").map_err(output_error)?;
+            return Ok(());
+        }
+        let scenario_names = test_context.list_scenarios_filtered(&cli_arguments.filter);
+        for scenario_name in scenario_names {
+            writeln!(out, "{scenario_name}").map_err(output_error)?;
+        }
+        return Ok(());
+    }
+
+    // List tree of groups and scenarios and return.
+    if cli_arguments.list_tree {
+        if test_context.count_scenarios() == 0 {
+            writeln!(err, "(no scenarios registered)").map_err(output_error)?;
+            return Ok(());
+        }
+        for line in test_context.list_tree(cli_arguments.counts) {
+            writeln!(out, "{line}").map_err(output_error)?;
+        }
+        return Ok(());
+    }
+
+    // List catalog of scenarios, one JSON object per line, and return.
+    if cli_arguments.catalog_ndjson {
+        for line in test_context.catalog_ndjson(&cli_arguments.filter) {
+            writeln!(out, "{line}").map_err(output_error)?;
+        }
+        return Ok(());
+    }
+
+    // Print number of scenarios and return.
+    if cli_arguments.count {
+        let count = test_context
+            .list_scenarios_filtered(&cli_arguments.filter)
+            .len();
+        writeln!(out, "{count}").map_err(output_error)?;
+        return Ok(());
+    }
+
+    // Describe name resolution and return.
+    if cli_arguments.explain {
+        for name in &cli_arguments.scenario_arguments.names {
+            let explanation = explain_name(name, &cli_arguments.filter, test_context);
+            writeln!(out, "{name}: {explanation}").map_err(output_error)?;
+        }
+        return Ok(());
+    }
+
+    // Running by name is not possible without scenarios.
+    if test_context.count_scenarios() == 0 {
+        return Err(CliError::Usage("no scenarios registered".to_string()));
+    }
+
+    let scenario_arguments = &cli_arguments.scenario_arguments;
+    if let Some(input) = &scenario_arguments.input {
+        scenario_arguments
+            .check_input_size(input)
+            .map_err(CliError::Usage)?;
+    }
+
+    let quarantine = match &cli_arguments.quarantine_file {
+        Some(quarantine_path) => read_quarantine_file(quarantine_path).map_err(CliError::Usage)?,
+        None => Vec::new(),
+    };
+
+    let max_reported_input = cli_arguments
+        .max_reported_input
+        .unwrap_or(DEFAULT_MAX_REPORTED_INPUT);
+    let observer: Box<dyn RunObserver + '_> = if cli_arguments.markers {
+        Box::new(MarkerObserver {
+            out: &shared_out,
+            quarantine: quarantine.clone(),
+            color: cli_arguments.color.use_color_stderr(),
+            max_reported_input,
+        })
+    } else {
+        Box::new(NoopObserver)
+    };
+    let observer: Box<dyn RunObserver + '_> = if cli_arguments.stream_results {
+        Box::new(StreamObserver::new(observer, Box::new(&shared_out)))
+    } else {
+        observer
+    };
+    let validate_inputs = cli_arguments.scenario_arguments.validate_inputs;
+    let run_sources = [
+        &cli_arguments.playlist,
+        &cli_arguments.matrix,
+        &cli_arguments.manifest,
+        &cli_arguments.rerun_failed,
+    ];
+    let run_sources_count = run_sources.iter().filter(|source| source.is_some()).count()
+        + usize::from(cli_arguments.stdin_ndjson);
+    // Results of the scenarios run before abort are reported, error is returned afterwards.
+    let PartialResults { results, error } = if run_sources_count > 1 {
+        Err(
+            "Only one of playlist, matrix, manifest, rerun failed and stdin NDJSON can be used"
+                .to_string(),
+        )
+    } else if let Some(playlist_path) = &cli_arguments.playlist {
+        run_playlist(
+            playlist_path,
+            cli_arguments.scenario_arguments,
+            observer.as_ref(),
+            out,
+            test_context,
+        )
+    } else if let Some(matrix_path) = &cli_arguments.matrix {
+        run_matrix(
+            matrix_path,
+            cli_arguments.scenario_arguments,
+            observer.as_ref(),
+            out,
+            test_context,
+        )
+    } else if let Some(manifest_path) = &cli_arguments.manifest {
+        run_manifest(
+            manifest_path,
+            cli_arguments.scenario_arguments,
+            observer.as_ref(),
+            out,
+            test_context,
+        )
+    } else if cli_arguments.stdin_ndjson {
+        run_ndjson(
+            input,
+            cli_arguments.scenario_arguments,
+            observer.as_ref(),
+            test_context,
+        )
+    } else if let Some(report_path) = &cli_arguments.rerun_failed {
+        run_failed(
+            report_path,
+            cli_arguments.scenario_arguments,
+            observer.as_ref(),
+            out,
+            test_context,
+        )
+    } else {
+        run_by_name(
+            cli_arguments.scenario_arguments,
+            &cli_arguments.filter,
+            observer.as_ref(),
+            out,
+            test_context,
+        )
+    }
+    .map_err(CliError::Usage)?;
+
+    // Report all input violations, nothing was run.
+    if validate_inputs {
+        if let Some(error) = error {
+            return Err(CliError::Usage(error));
+        }
+        return report_input_violations(&results, &mut err);
+    }
+    *stats = Some(RunStats::from_outcomes_with_quarantine(
+        &results,
+        &quarantine,
+    ));
+    if let Some(result_file) = &cli_arguments.result_file {
+        let lines = results.iter().map(|result| {
+            json!({
+                "name": result.name,
+                "outcome": result.outcome.to_json(),
+                "input": result.input,
+            })
+        });
+        append_result_lines(result_file, lines);
+    }
+
+    // Write TAP report.
+    if let Some(report_path) = cli_arguments.report_tap {
+        let entries: Vec<TapEntry> = results
+            .iter()
+            .map(|result| {
+                let (name, outcome) = (&result.name, &result.outcome);
+                let outcome = match report_status(name, outcome, &quarantine) {
+                    "quarantined-failed" => {
+                        Outcome::Failed(format!("quarantined-failed: {outcome}"))
+                    }
+                    _ => outcome.clone(),
+                };
+                TapEntry {
+                    name: name.clone(),
+                    not_run: is_not_run(&outcome),
+                    outcome,
+                    input: Some(result.input.clone()),
+                }
+            })
+            .collect();
+        let tap = to_tap_entries(&entries, error.as_deref());
+        std::fs::write(&report_path, tap)
+            .map_err(|e| CliError::Io(format!("Failed to write TAP report: {e}")))?;
+    }
+
+    // Write CSV report.
+    if let Some(report_path) = cli_arguments.report_csv {
+        let entries: Vec<CsvEntry> = results
+            .iter()
+            .map(|result| CsvEntry {
+                name: result.name.clone(),
+                outcome: result.outcome.clone(),
+                duration: result.duration,
+                input: truncate_input(&result.input, max_reported_input),
+                deprecated: result.deprecated,
+                slow: result.slow,
+                quarantined: quarantine.contains(&result.name),
+                resource_usage: result.resource_usage,
+                metrics: result.metrics.clone(),
+            })
+            .collect();
+        std::fs::write(&report_path, to_csv_entries(&entries))
+            .map_err(|e| CliError::Io(format!("Failed to write CSV report: {e}")))?;
+    }
+
+    // Write run artifacts into output directory.
+    let expect_fail = cli_arguments.expect == Some(Expected::Fail);
+    if let Some(output_dir) = &cli_arguments.output_dir {
+        write_output_dir(
+            Path::new(output_dir),
+            &results,
+            max_reported_input,
+            &quarantine,
+            expect_fail,
+            cli_arguments.expect_error_contains.as_deref(),
+        )
+        .map_err(CliError::Io)?;
+    }
+
+    // Print failures, quarantined failures are only logged.
+    // Expected failures are not printed.
+    for result in &results {
+        let (name, outcome) = (&result.name, &result.outcome);
+        if !outcome.is_ok() && !quarantine.contains(name) && !expect_fail {
+            let failure =
+                format_failure(cli_arguments.error_annotation, name, &outcome.to_string());
+            writeln!(err, "{failure}").map_err(output_error)?;
+        }
+    }
+
+    // Print outcome counts per tag.
+    if cli_arguments.summary_by_tag {
+        let outcomes: Vec<(String, Outcome)> = results
+            .iter()
+            .map(|result| (result.name.clone(), result.outcome.clone()))
+            .collect();
+        let summary = summary_by_tag(&outcomes, |name| test_context.tags(name));
+        write!(out, "{}", format_summary_by_tag(&summary)).map_err(output_error)?;
+    }
+
+    if let Some(error) = error {
+        return Err(CliError::Usage(error));
+    }
+    let not_run: Vec<String> = results
+        .iter()
+        .filter(|result| result.outcome == Outcome::Skipped(GLOBAL_TIMEOUT_REASON.to_string()))
+        .map(|result| result.name.clone())
+        .collect();
+    if !not_run.is_empty() {
+        // Failures of scenarios run before the timeout take precedence.
+        if !expect_fail {
+            combine_results(results.clone(), &quarantine).map_err(CliError::Scenario)?;
+        }
+        let message = format!(
+            "Global timeout exceeded, {} of {} scenarios not run",
+            not_run.len(),
+            results.len()
+        );
+        return Err(CliError::GlobalTimeout(ScenarioError::new(
+            not_run, message,
+        )));
+    }
+    if expect_fail {
+        let error_contains = cli_arguments.expect_error_contains.as_deref();
+        return check_expected_failures(&results, error_contains).map_err(CliError::Scenario);
+    }
+    combine_results(results, &quarantine).map_err(CliError::Scenario)
+}
+
+/// Write help text.
+///
+/// * `out` - Output help is written to.
+/// * `config` - CLI application configuration.
+fn write_help(out: &mut dyn Write, config: &CliConfig) -> std::io::Result<()> {
+    writeln!(out, "{}", config.about)?;
+    if let Some(program_name) = &config.program_name {
+        writeln!(out, "Usage: {program_name} [options]")?;
+    }
+    writeln!(
+        out,
+        "'-n', '--name' - test scenario or group name, can be repeated, defaults to {NAME_ENV_VAR}"
+    )?;
+    writeln!(
+        out,
+        "'--name-regex' - run all scenarios with fully-qualified name matching regular expression"
+    )?;
+    writeln!(
+        out,
+        "'-i', '--input' - test scenario input, defaults to {INPUT_ENV_VAR}"
+    )?;
+    writeln!(
+        out,
+        "'--input-template' - path to test scenario input template with '{{ key }}' placeholders"
+    )?;
+    writeln!(
+        out,
+        "'--values' - path to JSON object file with values substituted into input template"
+    )?;
+    writeln!(
+        out,
+        "'--concurrency' - number of concurrent copies of the scenario to run"
+    )?;
+    writeln!(
+        out,
+        "'--timeout' - test scenario timeout in seconds, overrides scenario timeout"
+    )?;
+    writeln!(
+        out,
+        "'--soft-timeout-ms' - time in milliseconds after which cancellation is requested"
+    )?;
+    writeln!(
+        out,
+        "'--hard-timeout-ms' - test scenario timeout in milliseconds, same as '--timeout'"
+    )?;
+    writeln!(
+        out,
+        "'--repeat' - number of times the scenario is run, stops on first failure"
+    )?;
+    writeln!(
+        out,
+        "'--until-duration' - repeatedly run the scenario for given number of seconds, all iterations are run"
+    )?;
+    writeln!(
+        out,
+        "'--warmup' - number of discarded runs before measured runs"
+    )?;
+    writeln!(
+        out,
+        "'--flakiness-report' - path to flakiness report, runs all repeats"
+    )?;
+    writeln!(
+        out,
+        "'--emit-output' - print JSON output of the scenario on success, single scenario only"
+    )?;
+    writeln!(
+        out,
+        "'--seed' - shuffle order of multi-scenario runs using seed"
+    )?;
+    writeln!(
+        out,
+        "'--max-failures' - stop multi-scenario run after number of failures"
+    )?;
+    writeln!(
+        out,
+        "'--global-timeout' - stop starting scenarios of multi-scenario run after number of seconds"
+    )?;
+    writeln!(
+        out,
+        "'--retries' - number of times a failed scenario is run again"
+    )?;
+    writeln!(
+        out,
+        "'--retry-if-contains' - retry only failures with error containing text, requires '--retries'"
+    )?;
+    writeln!(
+        out,
+        "'--isolate' - run each scenario in a separate runner process"
+    )?;
+    writeln!(
+        out,
+        "'--process-group' - place each runner process in its own process group, requires '--isolate', interrupt is not forwarded to the groups"
+    )?;
+    writeln!(
+        out,
+        "'--result-file' - append results as JSON lines to file, used by runner processes of '--isolate'"
+    )?;
+    writeln!(
+        out,
+        "'--resource-stats' - include maximum RSS and CPU time of each scenario in CSV report, Unix only"
+    )?;
+    writeln!(
+        out,
+        "'--validate-inputs' - check inputs against scenario input schemas without running scenarios"
+    )?;
+    writeln!(
+        out,
+        "'--max-input-bytes' - maximum size of test scenario input, defaults to {DEFAULT_MAX_INPUT_BYTES}"
+    )?;
+    writeln!(
+        out,
+        "'--bisect' - find minimal ordered subset of scenarios reproducing a failure"
+    )?;
+    writeln!(
+        out,
+        "'--workdir' - working directory of single-scenario runs, not supported for multi-scenario runs"
+    )?;
+    writeln!(
+        out,
+        "'--playlist' - path to file with scenario names to run, one per line"
+    )?;
+    writeln!(
+        out,
+        "'--matrix' - path to JSON file with parameter lists, scenario is run for each combination"
+    )?;
+    writeln!(
+        out,
+        "'--manifest' - path to JSON file mapping scenario names to inputs"
+    )?;
+    writeln!(
+        out,
+        "'--stdin-ndjson' - run scenarios from stdin, one JSON object with 'name' and 'input' per line"
+    )?;
+    writeln!(
+        out,
+        "'--rerun-failed' - path to JSON report of previous run, 'summary.json' of '--output-dir' or '--result-file', scenarios which did not pass or were not run are run again with recorded inputs"
+    )?;
+    writeln!(out, "'--report-tap' - path to TAP report file")?;
+    writeln!(
+        out,
+        "'--report-csv' - path to CSV report file with scenario timings and metrics"
+    )?;
+    writeln!(
+        out,
+        "'--output-dir' - directory for summary.json, junit.xml, events.ndjson and scenario logs"
+    )?;
+    writeln!(
+        out,
+        "'--log-level' - maximum log level (trace, debug, info, warn, error)"
+    )?;
+    writeln!(out, "'--log-format' - log format (json, text)")?;
+    writeln!(
+        out,
+        "'--timestamp-mode' - log timestamp mode (monotonic, wall-clock, none, both)"
+    )?;
+    writeln!(out, "'--log-output' - log output stream (stdout, stderr)")?;
+    writeln!(
+        out,
+        "'--log-nonblocking' - write logs from background thread"
+    )?;
+    writeln!(
+        out,
+        "'--log-thread-ids' - include thread ids in logs (true, false)"
+    )?;
+    writeln!(out, "'--tag' - select scenarios with tag, can be repeated")?;
+    writeln!(out, "'--skip' - skip scenario or group, can be repeated")?;
+    writeln!(
+        out,
+        "'--shard' - select only scenarios of shard '<index>/<count>', assigned by stable hash of scenario name"
+    )?;
+    writeln!(
+        out,
+        "'--enable' - run only matching scenarios or groups, '*' is a wildcard, can be repeated"
+    )?;
+    writeln!(
+        out,
+        "'--disable' - report matching scenarios or groups as skipped, overrides '--enable', can be repeated"
+    )?;
+    writeln!(
+        out,
+        "'--color' - color scenario status output (auto, always, never)"
+    )?;
+    writeln!(
+        out,
+        "'--error-annotation' - format of failure lines (plain, github, json)"
+    )?;
+    writeln!(
+        out,
+        "'--expect' - expected result of run scenarios (pass, fail)"
+    )?;
+    writeln!(
+        out,
+        "'--expect-error-contains' - text expected in errors of failed scenarios, requires '--expect fail'"
+    )?;
+    writeln!(
+        out,
+        "'--markers' - print scenario start and end markers in multi-scenario runs"
+    )?;
+    writeln!(
+        out,
+        "'--summary-by-tag' - print passed, failed and skipped counts per tag after the run"
+    )?;
+    writeln!(
+        out,
+        "'--stream-results' - print NDJSON line with result of each scenario as it finishes"
+    )?;
+    writeln!(
+        out,
+        "'--max-reported-input' - maximum number of input characters in markers and reports, defaults to {DEFAULT_MAX_REPORTED_INPUT}"
+    )?;
+    writeln!(
+        out,
+        "'--count' - print number of available scenarios, matching '--tag' and '--skip' filters"
+    )?;
+    writeln!(
+        out,
+        "'--explain' - describe how '--name' values are resolved, without running scenarios"
+    )?;
+    writeln!(out, "'--list-tree' - list groups and scenarios as tree")?;
+    writeln!(
+        out,
+        "'--catalog-ndjson' - list scenarios as JSON objects with name, tags and group path, one per line"
+    )?;
+    writeln!(
+        out,
+        "'--counts' - annotate '--list-tree' groups with number of scenarios"
+    )?;
+    writeln!(
+        out,
+        "'--print-config' - print effective configuration as JSON and exit"
+    )?;
+    writeln!(
+        out,
+        "'--json-errors' - print errors to stderr as single-line JSON objects"
+    )?;
+    writeln!(
+        out,
+        "'--trace-args' - log each parsed argument and resulting configuration at debug level"
+    )?;
+    writeln!(
+        out,
+        "'--event-log' - path to file all events of the run are written to as JSON array"
+    )?;
+    writeln!(
+        out,
+        "'--quarantine-file' - path to file with scenario names whose failures are ignored"
+    )?;
+    writeln!(
+        out,
+        "'--run-id' - run id attached to log records, generated by default"
+    )?;
+    writeln!(out, "'-l', '--list-scenarios' - list available scenarios")?;
+    writeln!(
+        out,
+        "'-h', '--help' - show help, takes precedence over all other arguments"
+    )?;
+    writeln!(out, "Boolean short flags can be combined, e.g., '-lh'")?;
+    writeln!(out, "'@path' - read additional arguments from file")?;
+    writeln!(
+        out,
+        "Long options can be abbreviated to an unambiguous prefix, e.g. '--conc'"
+    )?;
+    Ok(())
+}
+
+/// Print input violations found by `--validate-inputs` and fail if there are any.
+///
+/// * `results` - Validation results, failed results are violations.
+/// * `err` - Output violations are printed to.
+fn report_input_violations(results: &RunResults, err: &mut dyn Write) -> Result<(), CliError> {
+    let mut names = Vec::new();
+    for result in results {
+        if let Outcome::Failed(error) = &result.outcome {
+            writeln!(err, "Input of scenario {} is invalid: {error}", result.name)
+                .map_err(output_error)?;
+            names.push(result.name.clone());
+        }
+    }
+    if names.is_empty() {
+        return Ok(());
+    }
+    let message = format!(
+        "{} of {} inputs are invalid: {}",
+        names.len(),
+        results.len(),
+        names.join(", ")
+    );
+    Err(CliError::Scenario(ScenarioError::new(names, message)))
+}
+
+/// Message of unmet `--expect fail` expectation, `None` if the scenario failed as expected.
+///
+/// * `name` - Name of the scenario.
+/// * `outcome` - Outcome of the scenario.
+/// * `error_contains` - Text expected in the error.
+fn unmet_failure_expectation(
+    name: &str,
+    outcome: &Outcome,
+    error_contains: Option<&str>,
+) -> Option<String> {
+    let error = outcome.to_string();
+    if outcome.is_ok() {
+        Some(format!(
+            "Scenario {name} was expected to fail, but {}",
+            outcome.status()
+        ))
+    } else {
+        error_contains
+            .filter(|expected| !error.contains(expected))
+            .map(|expected| {
+                format!("Error of scenario {name} does not contain '{expected}': {error}")
+            })
+    }
+}
+
+/// Check that all scenarios failed, as expected with `--expect fail`.
+/// Passed and skipped scenarios do not meet the expectation.
+///
+/// * `results` - Scenario run results.
+/// * `error_contains` - Text expected in each error.
+fn check_expected_failures(
+    results: &RunResults,
+    error_contains: Option<&str>,
+) -> Result<(), ScenarioError> {
+    let mut unmet: Vec<(String, String)> = results
+        .iter()
+        .filter_map(|RunResult { name, outcome, .. }| {
+            unmet_failure_expectation(name, outcome, error_contains)
+                .map(|message| (name.clone(), message))
+        })
+        .collect();
+
+    if results.len() == 1 {
+        return match unmet.pop() {
+            Some((name, message)) => Err(ScenarioError::new(vec![name], message)),
+            None => Ok(()),
+        };
+    }
+
+    if unmet.is_empty() {
+        Ok(())
+    } else {
+        let names: Vec<String> = unmet.into_iter().map(|(name, _)| name).collect();
+        let message = format!(
+            "{} of {} scenarios did not fail as expected: {}",
+            names.len(),
+            results.len(),
+            names.join(", ")
+        );
+        Err(ScenarioError::new(names, message))
+    }
+}
+
+/// Counter making run ids generated by this process unique.
+static RUN_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Generate run id from current time, process id and counter.
+fn generate_run_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{nanos:x}-{:x}-{}",
+        std::process::id(),
+        RUN_ID_COUNTER.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+/// Arguments forwarded to runner processes of isolated scenarios, so they log same as this run.
+///
+/// * `tracing_config` - Tracing configuration.
+/// * `run_id` - Run id attached to log records.
+fn isolation_arguments(tracing_config: &TracingConfig, run_id: &str) -> Vec<String> {
+    let mut arguments = vec![
+        "--log-level".to_string(),
+        tracing_config.level.to_string().to_lowercase(),
+        "--log-format".to_string(),
+        tracing_config.format.to_string(),
+        "--timestamp-mode".to_string(),
+        tracing_config.timestamp_mode.to_string(),
+        "--log-output".to_string(),
+        tracing_config.output.to_string(),
+        "--log-thread-ids".to_string(),
+        tracing_config.thread_ids.to_string(),
+        "--run-id".to_string(),
+        run_id.to_string(),
+    ];
+    if tracing_config.nonblocking {
+        arguments.push("--log-nonblocking".to_string());
+    }
+    arguments
+}
+
+/// Append JSON lines to result file read by parent process of isolated run, see [`Isolation`].
+///
+/// * `path` - Path to result file.
+/// * `lines` - JSON lines to append.
+fn append_result_lines(path: &str, lines: impl IntoIterator<Item = Value>) {
+    let content: String = lines.into_iter().map(|line| format!("{line}\n")).collect();
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()));
+    // Parent process falls back to exit status of the runner.
+    if let Err(e) = result {
+        log_warn!("Failed to write results to {path}: {e}");
+    }
+}
+
+/// Observer printing scenario start and end markers to CLI application output.
+struct MarkerObserver<'a, 'w> {
+    /// Output markers are printed to.
+    out: &'a SharedWriter<'w>,
+
+    /// Quarantined scenario names.
+    quarantine: Vec<String>,
+
+    /// Color status.
+    color: bool,
+
+    /// Maximum number of reported input characters.
+    max_reported_input: usize,
+}
+
+impl RunObserver for MarkerObserver<'_, '_> {
+    fn on_start(&self, name: &str) {
+        let _ = writeln!(&mut { self.out }, "{}", scenario_start_marker(name));
+    }
+
+    fn on_start_with_input(&self, name: &str, input: &str) {
+        let input = truncate_input(input, self.max_reported_input);
+        let marker = scenario_start_marker_with_input(name, &input);
+        let _ = writeln!(&mut { self.out }, "{marker}");
+    }
+
+    fn on_finish(&self, name: &str, outcome: &Outcome, duration: Duration) {
+        let status = report_status(name, outcome, &self.quarantine);
+        let status = paint_status(status, self.color);
+        let marker = scenario_end_marker(name, &status, duration);
+        let _ = writeln!(&mut { self.out }, "{marker}");
+    }
+}
+
+/// Observer streaming NDJSON line with result of each scenario, delegating to inner observer.
+/// Lines are written under lock, so results of parallel runs are not interleaved.
+struct StreamObserver<'a> {
+    /// Observer notified about all events.
+    inner: Box<dyn RunObserver + 'a>,
+
+    /// Writer results are streamed to.
+    writer: Mutex<Box<dyn Write + Send + 'a>>,
+}
+
+impl<'a> StreamObserver<'a> {
+    /// Create streaming observer.
+    ///
+    /// * `inner` - Observer notified about all events.
+    /// * `writer` - Writer results are streamed to.
+    fn new(inner: Box<dyn RunObserver + 'a>, writer: Box<dyn Write + Send + 'a>) -> Self {
+        StreamObserver {
+            inner,
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+impl RunObserver for StreamObserver<'_> {
+    fn on_start(&self, name: &str) {
+        self.inner.on_start(name);
+    }
+
+    fn on_start_with_input(&self, name: &str, input: &str) {
+        self.inner.on_start_with_input(name, input);
+    }
+
+    fn on_finish(&self, name: &str, outcome: &Outcome, duration: Duration) {
+        self.inner.on_finish(name, outcome, duration);
+    }
+
+    fn on_result(&self, result: &RunResult) {
+        self.inner.on_result(result);
+        let line = json!({
+            "name": result.name,
+            "status": result.outcome.status(),
+            "duration_us": result.duration.as_micros(),
+            "metrics": metrics_json(&result.metrics),
+        });
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Streaming is best effort, failure to write does not fail the run.
+        if let Err(e) = writeln!(writer, "{line}").and_then(|_| writer.flush()) {
+            log_warn!("Failed to stream result of {}: {e}", result.name);
+        }
+    }
+}
+
+/// Number of single-character edits needed to change `left` into `right`.
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+/// Describe how name provided with `--name` is resolved.
+/// Name is either a scenario, an alias of a scenario, or a group.
+/// Wildcard patterns are not resolved by `--name`, scenarios matched with `--enable` are listed.
+/// Similar scenario names are suggested for names that are not found.
+///
+/// * `name` - Name to explain.
+/// * `filter` - Filter applied to scenarios of groups.
+/// * `test_context` - Test context.
+fn explain_name(name: &str, filter: &ScenarioFilter, test_context: &TestContext) -> String {
+    let group_scenarios = test_context.list_group_scenarios(name, filter);
+    if let Some(canonical) = test_context.resolve(name) {
+        if group_scenarios.is_some() {
+            return "ambiguous, matches both scenario and group".to_string();
+        }
+        if canonical == name {
+            return format!("exact match of scenario {canonical}");
+        }
+        return format!("alias of scenario {canonical}");
+    }
+    if let Some(group_scenarios) = group_scenarios {
+        return format!("group of scenarios {}", group_scenarios.join(", "));
+    }
+
+    let scenarios = test_context.list_scenarios();
+    if name.contains('*') {
+        let matching: Vec<String> = scenarios
+            .into_iter()
+            .filter(|scenario| matches_pattern(name, scenario))
+            .collect();
+        if matching.is_empty() {
+            return "pattern matching no scenarios".to_string();
+        }
+        return format!(
+            "pattern matching scenarios {}, patterns are only accepted by '--enable' and '--disable'",
+            matching.join(", ")
+        );
+    }
+
+    // Suggest scenarios differing in case, separators or few characters.
+    let normalize = |name: &str| name.to_lowercase().replace("::", ".").replace('/', ".");
+    let query = normalize(name);
+    let suggestions: Vec<String> = scenarios
+        .into_iter()
+        .filter(|scenario| {
+            let candidate = normalize(scenario);
+            let last_segment = candidate.rsplit('.').next().unwrap_or_default();
+            candidate.contains(&query)
+                || edit_distance(&query, &candidate) <= 2
+                || edit_distance(&query, last_segment) <= 2
+        })
+        .collect();
+    if suggestions.is_empty() {
+        "not found".to_string()
+    } else {
+        format!("not found, did you mean {}", suggestions.join(", "))
+    }
+}
+
+/// Run test scenario selected by CLI arguments.
+/// If name matches a group, all scenarios from that group selected by filter are run.
+fn run_by_name(
+    scenario: ScenarioArguments,
+    filter: &ScenarioFilter,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    // Select scenarios matching regular expression, run as multiple names.
+    if let Some(pattern) = &scenario.name_regex {
+        let regex =
+            Regex::new(pattern).map_err(|e| format!("Invalid --name-regex pattern: {e}"))?;
+        let names: Vec<String> = test_context
+            .list_scenarios_filtered(filter)
+            .into_iter()
+            .filter(|name| regex.is_match(name))
+            .collect();
+        if names.is_empty() {
+            return Err(format!("No scenarios match regular expression {pattern}"));
+        }
+        scenario.check_single_scenario_workdir()?;
+        return run_names(
+            ScenarioArguments { names, ..scenario },
+            filter,
+            true,
+            observer,
+            out,
+            test_context,
+        );
+    }
+
+    // Find scenario.
+    let scenario_name = match scenario.names.as_slice() {
+        [] => return Err("Test scenario name must be provided".to_string()),
+        [name] if name.is_empty() => return Err("Test scenario name must not be empty".to_string()),
+        [name] => name.clone(),
+        _ => {
+            scenario.check_single_scenario_workdir()?;
+            return run_names(scenario, filter, false, observer, out, test_context);
+        }
+    };
+
+    // Check input is provided.
+    let scenario_input = match scenario.input.clone() {
+        Some(input) => input,
+        None => return Err("Test scenario input must be provided".to_string()),
+    };
+
+    // Run all scenarios from group.
+    if let Some(group_scenarios) = test_context.list_group_scenarios(&scenario_name, filter) {
+        if test_context.contains_scenario(&scenario_name) {
+            return Err(format!(
+                "Name {scenario_name} is ambiguous, matches both scenario and group"
+            ));
+        }
+        if scenario.concurrency.is_some()
+            || scenario.repeat.is_some()
+            || scenario.until_duration.is_some()
+            || scenario.flakiness_report.is_some()
+            || scenario.warmup.is_some()
+        {
+            return Err("Concurrency and repeat are not supported for groups".to_string());
+        }
+        if scenario.emit_output {
+            return Err("Emit output is not supported for groups".to_string());
+        }
+        scenario.check_single_scenario_workdir()?;
+
+        let runs = group_scenarios
+            .into_iter()
+            .map(|name| Ok((name, scenario_input.clone())))
+            .collect();
+        return Ok(run_list(runs, &scenario, true, observer, out, test_context));
+    }
+
+    if scenario.bisect {
+        return Err("Bisect is not supported for a single scenario".to_string());
+    }
+
+    if !test_context.contains_scenario(&scenario_name) {
+        return Err(format!("Scenario {scenario_name} not found"));
+    }
+
+    if scenario.validate_inputs {
+        let runs = vec![Ok((scenario_name, scenario_input))];
+        return Ok(validate_runs(runs, test_context));
+    }
+
+    let deprecated = test_context.is_deprecated(&scenario_name);
+    let usage_start = scenario.resource_stats.then(ResourceUsage::current);
+    // Working directory of isolated run is changed by the runner process.
+    let results = match scenario.workdir.as_ref().filter(|_| !scenario.isolate) {
+        Some(workdir) => with_workdir(workdir, || {
+            run_single(
+                &scenario,
+                &scenario_name,
+                &scenario_input,
+                out,
+                test_context,
+            )
+        })?,
+        None => run_single(
+            &scenario,
+            &scenario_name,
+            &scenario_input,
+            out,
+            test_context,
+        )?,
+    };
+    // Usage of isolated runs is set by `run_single`, usage of the process is measured otherwise.
+    let resource_usage = usage_start.map(|start| ResourceUsage::current().since(&start));
+    let results: RunResults = results
+        .into_iter()
+        .map(|result| RunResult {
+            deprecated,
+            slow: test_context.check_slow(&scenario_name, result.duration),
+            resource_usage: result.resource_usage.or(resource_usage),
+            ..result.with_input(&scenario_input)
+        })
+        .collect();
+    // Single scenario is not observed while running, only its result is reported.
+    for result in &results {
+        observer.on_result(result);
+    }
+    Ok(results.into())
+}
+
+/// Run scenarios which did not pass in previous run, as listed in its JSON report.
+/// Scenarios are run with input recorded in the report,
+/// input from CLI arguments is used for entries without recorded input.
+/// Matrix labels of recorded names are kept, labeled names resolve to the scenario.
+///
+/// * `report_path` - Path to JSON report of previous run, see [`json_failed_runs`].
+fn run_failed(
+    report_path: &str,
+    scenario: ScenarioArguments,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    if !scenario.names.is_empty() {
+        return Err("Rerun of failed scenarios cannot be combined with scenario names".to_string());
+    }
+    let report = std::fs::read_to_string(report_path)
+        .map_err(|e| format!("Failed to read report file: {e}"))?;
+    let failed = json_failed_runs(&report)?;
+    if failed.is_empty() {
+        log_info!("No failed scenarios in report {report_path}");
+        return Ok(PartialResults::default());
+    }
+
+    // Scenarios might have been removed since report was written.
+    let mut missing: Vec<&str> = Vec::new();
+    for (name, _) in &failed {
+        if !test_context.contains_scenario(name) && !missing.contains(&name.as_str()) {
+            missing.push(name);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "Scenarios from report not found: {}",
+            missing.join(", ")
+        ));
+    }
+
+    let mut runs = Vec::new();
+    for (name, input) in failed {
+        let input = input
+            .or_else(|| scenario.input.clone())
+            .ok_or_else(|| format!("Test scenario input must be provided for {name}"))?;
+        scenario.check_input_size(&input)?;
+        runs.push(Ok((name, input)));
+    }
+    Ok(run_list(
+        runs,
+        &scenario,
+        false,
+        observer,
+        out,
+        test_context,
+    ))
+}
+
+/// Check whether scenario was skipped without being run,
+/// due to global timeout or after reaching maximum number of failures.
+///
+/// * `outcome` - Scenario outcome.
+fn is_not_run(outcome: &Outcome) -> bool {
+    [GLOBAL_TIMEOUT_REASON, MAX_FAILURES_REASON]
+        .iter()
+        .any(|reason| *outcome == Outcome::Skipped(reason.to_string()))
+}
+
+/// Read names and inputs of scenario runs which did not pass from JSON report of previous run.
+/// Report is either `summary.json` written to `--output-dir`, or JSON lines written to `--result-file`.
+/// Scenarios skipped without being run, e.g., after reaching maximum number of failures, did not pass.
+/// Input is `None` if it is not recorded. Each run is listed once, in order of first failure.
+///
+/// * `report` - Content of JSON report.
+fn json_failed_runs(report: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    let entries: Vec<Value> = match serde_json::from_str::<Value>(report) {
+        Ok(Value::Object(summary)) if summary.contains_key("results") => summary["results"]
+            .as_array()
+            .cloned()
+            .ok_or("Report results must be an array")?,
+        _ => report
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| format!("Invalid report line: {e}")))
+            .collect::<Result<_, _>>()?,
+    };
+    let mut runs: Vec<(String, Option<String>)> = Vec::new();
+    for entry in &entries {
+        // Result lines of runs aborted by an error contain only the error.
+        let Some(name) = entry["name"].as_str() else {
+            continue;
+        };
+        // Summary contains status and not run flag, result lines contain outcome.
+        let (status, not_run) = match entry.get("outcome") {
+            Some(outcome) => (
+                outcome["status"].as_str(),
+                Outcome::from_json(outcome).is_ok_and(|outcome| is_not_run(&outcome)),
+            ),
+            None => (
+                entry["status"].as_str(),
+                entry["not_run"].as_bool().unwrap_or(false),
+            ),
+        };
+        let passed = match status {
+            Some("passed") => true,
+            Some("skipped") => !not_run,
+            Some(_) => false,
+            None => return Err(format!("Report entry must contain status: {entry}")),
+        };
+        let run = (
+            name.to_string(),
+            entry["input"].as_str().map(str::to_string),
+        );
+        if !passed && !runs.contains(&run) {
+            runs.push(run);
+        }
+    }
+    Ok(runs)
+}
+
+/// Run with working directory changed to `workdir`.
+/// Previous working directory is restored afterwards.
+///
+/// * `workdir` - Working directory, must exist.
+/// * `run` - Runs the scenario.
+fn with_workdir<T>(workdir: &str, run: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    if !Path::new(workdir).is_dir() {
+        return Err(format!("Working directory {workdir} does not exist"));
+    }
+    let previous = std::env::current_dir()
+        .map_err(|e| format!("Failed to read current working directory: {e}"))?;
+    std::env::set_current_dir(workdir)
+        .map_err(|e| format!("Failed to change working directory to {workdir}: {e}"))?;
+    let result = run();
+    // Failure to restore is not fatal, scenario result is still reported.
+    if let Err(e) = std::env::set_current_dir(&previous) {
+        log_warn!("Failed to restore working directory: {e}");
+    }
+    result
+}
+
+/// Run scenario repeatedly until elapsed time exceeds budget.
+/// Clock is checked between iterations, running iteration is not interrupted.
+/// At least one iteration is run. Returns number of iterations and number of failed iterations.
+///
+/// * `budget` - Wall-clock duration of the loop.
+/// * `run_once` - Runs single iteration.
+fn run_until_duration(budget: Duration, mut run_once: impl FnMut() -> Outcome) -> (usize, usize) {
+    let start = Instant::now();
+    let (mut iterations, mut failures) = (0, 0);
+    loop {
+        if !run_once().is_ok() {
+            failures += 1;
+        }
+        iterations += 1;
+        if start.elapsed() >= budget {
+            return (iterations, failures);
+        }
+    }
+}
+
+/// Run single test scenario, with concurrency, warmup and repeats selected by CLI arguments.
+/// Output of the scenario requested by `emit_output` is printed to `out`.
+fn run_single(
+    scenario: &ScenarioArguments,
+    scenario_name: &str,
+    scenario_input: &str,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<RunResults, String> {
+    if !scenario.toggles.is_enabled(scenario_name) {
+        let outcome = Outcome::Skipped(DISABLED_REASON.to_string());
+        return Ok(vec![RunResult::new(scenario_name, outcome, Duration::ZERO)]);
+    }
+
+    // Run scenario once and print its output.
+    if scenario.emit_output {
+        if scenario.concurrency.is_some()
+            || scenario.repeat.is_some()
+            || scenario.flakiness_report.is_some()
+            || scenario.until_duration.is_some()
+            || scenario.warmup.is_some()
+        {
+            return Err("Emit output is not supported with concurrency or repeat".to_string());
+        }
+        if scenario.isolate {
+            return Err("Emit output is not supported with isolation".to_string());
+        }
+
+        let start = Instant::now();
+        let (outcome, output) = test_context.run_output_with_deadlines(
+            scenario_name,
+            scenario_input,
+            scenario.soft_timeout,
+            scenario.timeout,
+        );
+        if let Some(output) = output {
+            writeln!(&mut { out }, "{output}")
+                .map_err(|e| format!("Failed to write output: {e}"))?;
+        }
+        return Ok(vec![RunResult::new(
+            scenario_name,
+            outcome,
+            start.elapsed(),
+        )]);
+    }
+
+    if scenario.until_duration.is_some()
+        && (scenario.repeat.is_some() || scenario.flakiness_report.is_some())
+    {
+        return Err("Until duration cannot be combined with repeat".to_string());
+    }
+
+    let isolation = scenario.isolation()?;
+    if isolation.is_some() && scenario.concurrency.is_some() {
+        return Err("Isolation is not supported with concurrency".to_string());
+    }
+
+    let warmup_metrics = MetricsSink::new();
+    let metrics = MetricsSink::new();
+    // Usage of isolated runs is collected from runner processes, summed over retries and repeats.
+    let isolated_usage = Cell::new(ResourceUsage::default());
+    let run_attempt = |metrics: &MetricsSink| match (&isolation, scenario.concurrency) {
+        (Some(isolation), _) => {
+            let (outcome, usage) = isolation.run_scenario_with_usage(
+                scenario_name,
+                scenario_input,
+                scenario.soft_timeout,
+                scenario.timeout,
+            );
+            isolated_usage.set(isolated_usage.get().combine(&usage));
+            outcome
+        }
+        (None, Some(concurrency)) => test_context
+            .run_concurrent(scenario_name, scenario_input, concurrency)
+            .into(),
+        (None, None) => test_context.run_with_deadlines(
+            scenario_name,
+            scenario_input,
+            scenario.soft_timeout,
+            scenario.timeout,
+            metrics,
+        ),
+    };
+    let run_once = |metrics: &MetricsSink| {
+        // Isolated runs are retried by the runner process.
+        let retries = scenario
+            .retries
+            .or_else(|| test_context.policy(scenario_name).retries)
+            .filter(|_| isolation.is_none())
+            .unwrap_or(0);
+        let retry_if_contains = scenario.retry_if_contains.as_deref();
+        run_with_retries(retries, retry_if_contains, || run_attempt(metrics))
+    };
+    // Run warmup iterations, results are discarded unless failed.
+    for _ in 0..scenario.warmup.unwrap_or(0) {
+        let outcome = run_once(&warmup_metrics);
+        if !outcome.is_ok() {
+            let outcome = Outcome::Failed(format!("Warmup failed: {outcome}"));
+            return Ok(vec![RunResult::new(scenario_name, outcome, Duration::ZERO)]);
+        }
+    }
+    isolated_usage.take();
+    let resource_usage =
+        || (scenario.resource_stats && isolation.is_some()).then(|| isolated_usage.get());
+
+    let repeat = scenario.repeat.unwrap_or(1);
+    let start = Instant::now();
+
+    // Run repeatedly until duration budget is exhausted.
+    if let Some(budget) = scenario.until_duration {
+        let (iterations, failures) = run_until_duration(budget, || run_once(&metrics));
+        log_info!("Completed {iterations} iterations, {failures} failed");
+        let outcome = if failures == 0 {
+            Outcome::Passed
+        } else {
+            Outcome::Failed(format!("{failures} of {iterations} iterations failed"))
+        };
+        return Ok(vec![RunResult {
+            metrics: metrics.snapshot(),
+            resource_usage: resource_usage(),
+            ..RunResult::new(scenario_name, outcome, start.elapsed())
+        }]);
+    }
+
+    // Run all repeats and write flakiness report.
+    if let Some(report_path) = &scenario.flakiness_report {
+        let outcomes: Vec<Outcome> = (0..repeat).map(|_| run_once(&metrics)).collect();
+        let report = FlakinessReport::from_outcomes(&outcomes);
+        std::fs::write(report_path, report.to_json())
+            .map_err(|e| format!("Failed to write flakiness report: {e}"))?;
+
+        let outcome = if report.failures == 0 {
+            Outcome::Passed
+        } else {
+            Outcome::Failed(format!(
+                "{} of {} runs failed",
+                report.failures, report.runs
+            ))
+        };
+        return Ok(vec![RunResult {
+            metrics: metrics.snapshot(),
+            resource_usage: resource_usage(),
+            ..RunResult::new(scenario_name, outcome, start.elapsed())
+        }]);
+    }
+
+    // Run repeats until first failure.
+    let mut outcome = Outcome::Passed;
+    for _ in 0..repeat {
+        outcome = run_once(&metrics);
+        if !outcome.is_ok() {
+            break;
+        }
+    }
+
+    Ok(vec![RunResult {
+        metrics: metrics.snapshot(),
+        resource_usage: resource_usage(),
+        ..RunResult::new(scenario_name, outcome, start.elapsed())
+    }])
+}
+
+/// Run test scenarios and groups selected by multiple names, in order.
+/// All names are validated before any scenario is run.
+/// Scenarios of each group are ordered by priority, see [`run_list`].
+///
+/// * `prioritized` - Names were selected from all scenarios and are run by priority.
+fn run_names(
+    scenario: ScenarioArguments,
+    filter: &ScenarioFilter,
+    prioritized: bool,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    if scenario.names.iter().any(|name| name.is_empty()) {
+        return Err("Test scenario name must not be empty".to_string());
+    }
+    if scenario.concurrency.is_some()
+        || scenario.repeat.is_some()
+        || scenario.flakiness_report.is_some()
+        || scenario.until_duration.is_some()
+        || scenario.warmup.is_some()
+        || scenario.emit_output
+    {
+        return Err("Concurrency and repeat are not supported for multiple names".to_string());
+    }
+
+    // Validate all names exist.
+    let unknown: Vec<&str> = scenario
+        .names
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !test_context.contains_scenario(name) && !test_context.contains_group(name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("Scenarios not found: {}", unknown.join(", ")));
+    }
+
+    // Check input is provided.
+    let scenario_input = match scenario.input.clone() {
+        Some(input) => input,
+        None => return Err("Test scenario input must be provided".to_string()),
+    };
+
+    // Resolve groups into scenarios.
+    let mut runs = Vec::new();
+    for name in &scenario.names {
+        match test_context.list_group_scenarios(name, filter) {
+            Some(_) if test_context.contains_scenario(name) => {
+                return Err(format!(
+                    "Name {name} is ambiguous, matches both scenario and group"
+                ));
+            }
+            Some(mut group_scenarios) => {
+                group_scenarios.sort_by_key(|name| Reverse(test_context.priority(name)));
+                runs.extend(
+                    group_scenarios
+                        .into_iter()
+                        .map(|group_scenario| Ok((group_scenario, scenario_input.clone()))),
+                );
+            }
+            None => runs.push(Ok((name.clone(), scenario_input.clone()))),
+        }
+    }
+
+    Ok(run_list(
+        runs,
+        &scenario,
+        prioritized,
+        observer,
+        out,
+        test_context,
+    ))
+}
+
+/// Shuffle items deterministically using seed.
+///
+/// * `items` - Items to shuffle.
+/// * `seed` - Shuffle seed.
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    // SplitMix64 generator.
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+
+    // Fisher-Yates shuffle.
+    for i in (1..items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Narrow down ordered runs to a minimal subsequence reproducing the first failure.
+/// Failing scenario is kept last, its predecessors are reduced by halving,
+/// then by removing them one by one.
+/// Returns `None` if runs do not fail.
+///
+/// * `runs` - Names of the scenarios to run with their inputs.
+/// * `timeout` - Maximum execution time of each scenario.
+/// * `test_context` - Test context to use.
+fn bisect(
+    runs: &[(String, String)],
+    timeout: Option<Duration>,
+    test_context: &TestContext,
+) -> Option<Vec<(String, String)>> {
+    let options = RunOptions {
+        timeout,
+        ..Default::default()
+    };
+    let results = test_context.run_many(runs, &options, &NoopObserver);
+    let failing_index = results.iter().position(|result| !result.outcome.is_ok())?;
+    let failing = runs[failing_index].clone();
+
+    // Check whether failing scenario still fails when run after predecessors.
+    let fails_after = |predecessors: &[(String, String)]| {
+        let mut trial = predecessors.to_vec();
+        trial.push(failing.clone());
+        let results = test_context.run_many(&trial, &options, &NoopObserver);
+        results.last().is_some_and(|result| !result.outcome.is_ok())
+    };
+
+    let mut predecessors = runs[..failing_index].to_vec();
+    while predecessors.len() > 1 {
+        let (first, second) = predecessors.split_at(predecessors.len() / 2);
+        if fails_after(first) {
+            predecessors = first.to_vec();
+        } else if fails_after(second) {
+            predecessors = second.to_vec();
+        } else {
+            break;
+        }
+    }
+
+    let mut index = 0;
+    while index < predecessors.len() {
+        let mut candidate = predecessors.clone();
+        candidate.remove(index);
+        if fails_after(&candidate) {
+            predecessors = candidate;
+        } else {
+            index += 1;
+        }
+    }
+
+    predecessors.push(failing);
+    Some(predecessors)
+}
+
+/// Run multiple test scenarios in order, shuffled if seed is provided.
+/// In bisect mode, minimal failing subsequence is printed and run instead.
+/// Run is aborted when an erroneous run is reached.
+///
+/// * `prioritized` - Scenarios are sorted by descending priority, order of equal priorities
+///   is kept. Used for groups and scenarios selected from all scenarios, explicit lists,
+///   e.g., playlists, are run in their order. Erroneous runs keep their position.
+fn run_list(
+    mut runs: Runs,
+    scenario: &ScenarioArguments,
+    prioritized: bool,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> PartialResults {
+    // Inputs are validated in order of runs.
+    if scenario.validate_inputs {
+        return validate_runs(runs, test_context);
+    }
+
+    if let Some(seed) = scenario.seed {
+        shuffle(&mut runs, seed);
+    }
+    if prioritized {
+        let mut valid: Vec<(String, String)> = runs
+            .iter_mut()
+            .filter_map(|run| run.as_mut().ok().map(std::mem::take))
+            .collect();
+        valid.sort_by_key(|(name, _)| Reverse(test_context.priority(name)));
+        for (run, valid) in runs.iter_mut().filter(|run| run.is_ok()).zip(valid) {
+            *run = Ok(valid);
+        }
+    }
+
+    if scenario.bisect {
+        // Bisect reruns scenarios, so all runs must be valid before any is run.
+        let mut valid_runs = match runs.into_iter().collect::<Result<Vec<_>, String>>() {
+            Ok(valid_runs) => valid_runs,
+            Err(error) => {
+                return PartialResults {
+                    results: Vec::new(),
+                    error: Some(error),
+                }
+            }
+        };
+        // Disabled scenarios cannot reproduce a failure.
+        valid_runs.retain(|(name, _)| {
+            scenario
+                .toggles
+                .is_enabled(test_context.unlabeled_name(name))
+        });
+        match bisect(&valid_runs, scenario.timeout, test_context) {
+            Some(reduced) => {
+                for (name, _) in &reduced {
+                    let _ = writeln!(&mut { out }, "{name}");
+                }
+                valid_runs = reduced;
+            }
+            None => log_warn!("Scenarios did not fail, nothing to bisect"),
+        }
+        runs = valid_runs.into_iter().map(Ok).collect();
+    }
+
+    run_in_order(runs, scenario, observer, test_context)
+}
+
+/// Run multiple test scenarios in order of `runs`.
+/// Runs are taken from `runs` only when they are about to start.
+/// Run is aborted when an erroneous run is reached.
+fn run_in_order<I>(
+    runs: I,
+    scenario: &ScenarioArguments,
+    observer: &dyn RunObserver,
+    test_context: &TestContext,
+) -> PartialResults
+where
+    I: IntoIterator<Item = Result<(String, String), String>>,
+    I::IntoIter: Send,
+{
+    let isolation = match scenario.isolation() {
+        Ok(isolation) => isolation,
+        Err(error) => {
+            return PartialResults {
+                results: Vec::new(),
+                error: Some(error),
+            }
+        }
+    };
+    let options = RunOptions {
+        timeout: scenario.timeout,
+        soft_timeout: scenario.soft_timeout,
+        max_failures: scenario.max_failures,
+        deadline: scenario.deadline,
+        retries: scenario.retries,
+        retry_if_contains: scenario.retry_if_contains.clone(),
+        isolation,
+        resource_stats: scenario.resource_stats,
+        toggles: scenario.toggles.clone(),
+        ..Default::default()
+    };
+    let partial = test_context.run_many_fallible(runs, &options, observer);
+
+    let not_run = partial
+        .results
+        .iter()
+        .filter(|result| result.outcome == Outcome::Skipped(MAX_FAILURES_REASON.to_string()))
+        .count();
+    if not_run > 0 {
+        log_warn!(
+            "Stopped early after reaching maximum number of failures, {not_run} scenarios not run"
+        );
+    }
+    partial
+}
+
+/// Validate inputs of runs against scenario input schemas, without running scenarios.
+/// Valid runs are reported as passed, violations as failed.
+///
+/// * `runs` - Scenario runs, or errors aborting the validation when reached.
+/// * `test_context` - Test context to use.
+fn validate_runs(
+    runs: impl IntoIterator<Item = Result<(String, String), String>>,
+    test_context: &TestContext,
+) -> PartialResults {
+    let mut results = Vec::new();
+    for run in runs {
+        let (name, input) = match run {
+            Ok(run) => run,
+            Err(error) => {
+                return PartialResults {
+                    results,
+                    error: Some(error),
+                }
+            }
+        };
+        let outcome = match test_context.validate_input(&name, &input) {
+            Ok(()) => Outcome::Passed,
+            Err(error) => Outcome::Failed(error),
+        };
+        results.push(RunResult::new(&name, outcome, Duration::ZERO).with_input(&input));
+    }
+    results.into()
+}
+
+/// Read playlist file.
+/// Each line contains scenario name, optionally followed by a tab and scenario input.
+/// Blank lines and lines starting with `#` are ignored.
+///
+/// * `playlist_path` - Path to playlist file.
+fn read_playlist(playlist_path: &str) -> Result<Vec<(String, Option<String>)>, String> {
+    let content = std::fs::read_to_string(playlist_path)
+        .map_err(|e| format!("Failed to read playlist file: {e}"))?;
+
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        match line.split_once('\t') {
+            Some((name, input)) => entries.push((name.trim().to_string(), Some(input.to_string()))),
+            None => entries.push((line.trim().to_string(), None)),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Run test scenarios listed in playlist file, in order.
+/// All listed scenarios are validated to exist before any is run.
+fn run_playlist(
+    playlist_path: &str,
+    scenario: ScenarioArguments,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    if !scenario.names.is_empty() {
+        return Err("Playlist cannot be combined with test scenario name".to_string());
+    }
+    scenario.check_single_scenario_workdir()?;
+
+    let entries = read_playlist(playlist_path)?;
+
+    // Validate all scenarios exist.
+    let missing: Vec<&str> = entries
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !test_context.contains_scenario(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Scenarios from playlist not found: {}",
+            missing.join(", ")
+        ));
+    }
+
+    // Resolve and validate all inputs before any scenario is run.
+    let mut runs = Vec::new();
+    for (name, input) in entries {
+        let input = input
+            .or_else(|| scenario.input.clone())
+            .ok_or_else(|| format!("Test scenario input must be provided for {name}"))?;
+        scenario.check_input_size(&input)?;
+        runs.push(Ok((name, input)));
+    }
+
+    Ok(run_list(
+        runs,
+        &scenario,
+        false,
+        observer,
+        out,
+        test_context,
+    ))
+}
+
+/// Read parameter matrix file.
+/// File contains JSON object mapping parameter names to lists of values.
+/// Returns Cartesian product of all parameter lists, as JSON objects.
+///
+/// * `matrix_path` - Path to matrix file.
+fn read_matrix(matrix_path: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let content = std::fs::read_to_string(matrix_path)
+        .map_err(|e| format!("Failed to read matrix file: {e}"))?;
+    let matrix: Map<String, Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid matrix file: {e}"))?;
+    if matrix.is_empty() {
+        return Err("Matrix file must contain at least one parameter".to_string());
+    }
+
+    let mut combinations = vec![Map::new()];
+    for (param, values) in matrix {
+        let values = match values {
+            Value::Array(values) if !values.is_empty() => values,
+            _ => return Err(format!("Matrix parameter {param} must be a non-empty list")),
+        };
+        let mut extended = Vec::with_capacity(combinations.len() * values.len());
+        for combination in &combinations {
+            for value in &values {
+                let mut combination = combination.clone();
+                combination.insert(param.clone(), value.clone());
+                extended.push(combination);
+            }
+        }
+        combinations = extended;
+    }
+
+    Ok(combinations)
+}
+
+/// Label scenario run with its parameter combination, e.g. `name[param=a,other=1]`.
+///
+/// * `name` - Name of the scenario.
+/// * `combination` - Parameter combination.
+fn matrix_label(name: &str, combination: &Map<String, Value>) -> String {
+    let params: Vec<String> = combination
+        .iter()
+        .map(|(param, value)| match value {
+            // Strings are shown without quotes.
+            Value::String(value) => format!("{param}={value}"),
+            value => format!("{param}={value}"),
+        })
+        .collect();
+    format!("{name}[{}]", params.join(","))
+}
+
+/// Run test scenario for each parameter combination from matrix file.
+/// Combination is serialized as scenario input, each run is labeled with its combination.
+fn run_matrix(
+    matrix_path: &str,
+    scenario: ScenarioArguments,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    let name = match scenario.names.as_slice() {
+        [name] => name.clone(),
+        [] => return Err("Test scenario name must be provided".to_string()),
+        _ => return Err("Matrix cannot be combined with multiple names".to_string()),
+    };
+    if scenario.input.is_some() {
+        return Err("Matrix cannot be combined with test scenario input".to_string());
+    }
+    scenario.check_single_scenario_workdir()?;
+    if !test_context.contains_scenario(&name) {
+        return Err(format!("Scenario {name} not found"));
+    }
+
+    let combinations = read_matrix(matrix_path)?;
+    // Label is carried by the run, so it is kept when runs are reordered.
+    let runs = combinations
+        .iter()
+        .map(|combination| {
+            let input = Value::Object(combination.clone()).to_string();
+            Ok((matrix_label(&name, combination), input))
+        })
+        .collect();
+    Ok(run_list(
+        runs,
+        &scenario,
+        false,
+        observer,
+        out,
+        test_context,
+    ))
+}
+
+/// Substitute `{{ key }}` placeholders of the template with values.
+/// String values are used as-is, other values are serialized to JSON.
+///
+/// * `template` - Template content.
+/// * `values` - Values substituted into the template.
+fn render_template(template: &str, values: &Map<String, Value>) -> Result<String, String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_start = &rest[start + 2..];
+        let end = after_start
+            .find("}}")
+            .ok_or_else(|| "Unclosed template placeholder".to_string())?;
+        let key = after_start[..end].trim();
+        match values.get(key) {
+            Some(Value::String(value)) => rendered.push_str(value),
+            Some(value) => rendered.push_str(&value.to_string()),
+            None => return Err(format!("Missing value for template placeholder: {key}")),
+        }
+        rest = &after_start[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Read input template and render it with values from JSON object file.
+///
+/// * `template_path` - Path to input template file.
+/// * `values_path` - Path to values file, no values are substituted if not set.
+fn read_input_template(template_path: &str, values_path: Option<&str>) -> Result<String, String> {
+    let template = std::fs::read_to_string(template_path)
+        .map_err(|e| format!("Failed to read input template file: {e}"))?;
+    let values = match values_path {
+        Some(values_path) => {
+            let content = std::fs::read_to_string(values_path)
+                .map_err(|e| format!("Failed to read values file: {e}"))?;
+            serde_json::from_str(&content).map_err(|e| format!("Invalid values file: {e}"))?
+        }
+        None => Map::new(),
+    };
+    render_template(&template, &values)
+}
+
+/// Read manifest file.
+/// File contains JSON object mapping scenario names to inputs.
+/// String inputs are used as-is, other values are serialized to JSON.
+/// Entries are returned in order of scenario names.
+///
+/// * `manifest_path` - Path to manifest file.
+fn read_manifest(manifest_path: &str) -> Result<Vec<(String, String)>, String> {
+    let content = std::fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Failed to read manifest file: {e}"))?;
+    let manifest: Map<String, Value> =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid manifest file: {e}"))?;
+
+    Ok(manifest
+        .into_iter()
+        .map(|(name, input)| match input {
+            Value::String(input) => (name, input),
+            input => (name, input.to_string()),
+        })
+        .collect())
+}
+
+/// Run test scenarios listed in manifest file with their mapped inputs.
+/// All listed scenarios are validated to exist before any is run.
+fn run_manifest(
+    manifest_path: &str,
+    scenario: ScenarioArguments,
+    observer: &dyn RunObserver,
+    out: &SharedWriter<'_>,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    if !scenario.names.is_empty() {
+        return Err("Manifest cannot be combined with test scenario name".to_string());
+    }
+    scenario.check_single_scenario_workdir()?;
+    if scenario.input.is_some() {
+        return Err("Manifest cannot be combined with test scenario input".to_string());
+    }
+
+    let runs = read_manifest(manifest_path)?;
+
+    // Validate all scenarios exist.
+    let missing: Vec<&str> = runs
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !test_context.contains_scenario(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Scenarios from manifest not found: {}",
+            missing.join(", ")
+        ));
+    }
+    for (_, input) in &runs {
+        scenario.check_input_size(input)?;
+    }
+
+    let runs = runs.into_iter().map(Ok).collect();
+    Ok(run_list(
+        runs,
+        &scenario,
+        false,
+        observer,
+        out,
+        test_context,
+    ))
+}
+
+/// Read scenario runs from JSON Lines, one line at a time, when the run is about to start.
+/// Each line contains JSON object with scenario `name` and `input`.
+/// String inputs are used as-is, other values are serialized to JSON.
+/// Blank lines are ignored, invalid lines abort the run when reached.
+/// Lines are limited to maximum input size and [`NDJSON_LINE_OVERHEAD`].
+///
+/// * `reader` - Reader of JSON Lines.
+/// * `scenario` - Scenario arguments, maximum input size is taken from them.
+/// * `test_context` - Test context to use.
+fn ndjson_runs<'a>(
+    reader: &'a mut (dyn BufRead + Send),
+    scenario: &'a ScenarioArguments,
+    test_context: &'a TestContext,
+) -> impl Iterator<Item = Result<(String, String), String>> + Send + 'a {
+    let line_limit =
+        scenario.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES) + NDJSON_LINE_OVERHEAD;
+    let mut line_number = 0;
+    let mut aborted = false;
+    std::iter::from_fn(move || {
+        while !aborted {
+            line_number += 1;
+            let run = match read_ndjson_line(reader, line_limit, line_number) {
+                Ok(None) => return None,
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => parse_ndjson_run(&line, line_number, scenario, test_context),
+                Err(error) => Err(error),
+            };
+            aborted = run.is_err();
+            return Some(run);
+        }
+        None
+    })
+}
+
+/// Read single line of JSON Lines, `None` is returned at the end of input.
+///
+/// * `reader` - Reader of JSON Lines.
+/// * `line_limit` - Maximum length of line in bytes.
+/// * `line_number` - Number of the line, used in errors.
+fn read_ndjson_line(
+    reader: &mut (dyn BufRead + Send),
+    line_limit: usize,
+    line_number: usize,
+) -> Result<Option<String>, String> {
+    let mut line = Vec::new();
+    let read = (&mut *reader)
+        .take(line_limit as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| format!("Failed to read NDJSON line {line_number}: {e}"))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    } else if line.len() > line_limit {
+        return Err(format!(
+            "NDJSON line {line_number} exceeds limit of {line_limit} bytes"
+        ));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|e| format!("Invalid NDJSON line {line_number}: {e}"))
+}
+
+/// Parse scenario run from line of JSON Lines, see [`ndjson_runs`].
+///
+/// * `line` - Line to parse.
+/// * `line_number` - Number of the line, used in errors.
+/// * `scenario` - Scenario arguments, maximum input size is taken from them.
+/// * `test_context` - Test context to use.
+fn parse_ndjson_run(
+    line: &str,
+    line_number: usize,
+    scenario: &ScenarioArguments,
+    test_context: &TestContext,
+) -> Result<(String, String), String> {
+    let mut entry = serde_json::from_str::<Map<String, Value>>(line)
+        .map_err(|e| format!("Invalid NDJSON line {line_number}: {e}"))?;
+    let name = match entry.remove("name") {
+        Some(Value::String(name)) => name,
+        _ => {
+            return Err(format!(
+                "Missing scenario name on NDJSON line {line_number}"
+            ))
+        }
+    };
+    let input = match entry.remove("input") {
+        Some(Value::String(input)) => input,
+        Some(input) => input.to_string(),
+        None => return Err(format!("Missing input on NDJSON line {line_number}")),
+    };
+    if !test_context.contains_scenario(&name) {
+        return Err(format!(
+            "Scenario {name} from NDJSON line {line_number} not found"
+        ));
+    }
+    scenario.check_input_size(&input)?;
+    Ok((name, input))
+}
+
+/// Run test scenarios read from JSON Lines with their inputs, see [`ndjson_runs`].
+/// Each line is read when the previous scenario finished, so runs can be produced dynamically.
+/// Runs are started in order of lines, priorities are not applied.
+/// Runs from lines before an invalid line are kept.
+fn run_ndjson(
+    reader: &mut (dyn BufRead + Send),
+    scenario: ScenarioArguments,
+    observer: &dyn RunObserver,
+    test_context: &TestContext,
+) -> Result<PartialResults, String> {
+    if !scenario.names.is_empty() {
+        return Err("Stdin NDJSON cannot be combined with test scenario name".to_string());
+    }
+    scenario.check_single_scenario_workdir()?;
+    if scenario.input.is_some() {
+        return Err("Stdin NDJSON cannot be combined with test scenario input".to_string());
+    }
+    if scenario.seed.is_some() || scenario.bisect {
+        return Err("Stdin NDJSON cannot be combined with seed or bisect".to_string());
+    }
+
+    let runs = ndjson_runs(reader, &scenario, test_context);
+    if scenario.validate_inputs {
+        return Ok(validate_runs(runs, test_context));
+    }
+    Ok(run_in_order(runs, &scenario, observer, test_context))
+}
+
+/// Read quarantine file.
+/// Each line contains name of a scenario known to be flaky.
+/// Blank lines and lines starting with `#` are ignored.
+///
+/// * `quarantine_path` - Path to quarantine file.
+fn read_quarantine_file(quarantine_path: &str) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(quarantine_path)
+        .map_err(|e| format!("Failed to read quarantine file: {e}"))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Status of scenario outcome as shown in reports.
+/// Failures of quarantined scenarios are reported as `quarantined-failed`.
+fn report_status(name: &str, outcome: &Outcome, quarantine: &[String]) -> &'static str {
+    if !outcome.is_ok() && quarantine.iter().any(|q| q == name) {
+        "quarantined-failed"
+    } else {
+        outcome.status()
+    }
+}
+
+/// Combine scenario results into a single result.
+/// Single result is returned as-is.
+/// Failures of quarantined scenarios are logged as warnings and ignored.
+///
+/// * `results` - Scenario names with outcomes and execution times.
+/// * `quarantine` - Quarantined scenario names.
+fn combine_results(results: RunResults, quarantine: &[String]) -> Result<(), ScenarioError> {
+    let total = results.len();
+    let mut failed: Vec<(String, String)> = Vec::new();
+    for RunResult { name, outcome, .. } in results {
+        let quarantined = quarantine.contains(&name);
+        match (outcome.is_ok(), quarantined) {
+            (true, true) => {
+                log_info!(
+                    "Quarantined scenario {name} passed, consider removing it from quarantine"
+                )
+            }
+            (false, true) => log_warn!("Quarantined scenario {name} failed: {outcome}"),
+            (false, false) => failed.push((name, outcome.to_string())),
+            (true, false) => {}
+        }
+    }
+
+    if total == 1 {
+        return match failed.pop() {
+            Some((name, error)) => Err(ScenarioError::new(vec![name], error)),
+            None => Ok(()),
+        };
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        let names: Vec<String> = failed.into_iter().map(|(name, _)| name).collect();
+        let message = format!(
+            "{} of {total} scenarios failed: {}",
+            names.len(),
+            names.join(", ")
+        );
+        Err(ScenarioError::new(names, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{
+        bisect, config_json, default_exit_code, generate_run_id, isolation_arguments, matrix_label,
+        parse_cli_arguments, parse_cli_arguments_into, parse_numeric_arg, parse_numeric_arg_min,
+        read_manifest, read_matrix, read_playlist, read_quarantine_file, render_template, run_cli,
+        run_cli_app, run_cli_app_exit_with_io, run_cli_app_with_config, run_cli_app_with_io,
+        run_cli_with_io, run_until_duration, scenario_log_file_name, shuffle, write_help,
+        CliArguments, CliConfig, StreamObserver, INPUT_ENV_VAR, NAME_ENV_VAR,
+    };
+    use crate::color::ColorMode;
+    use crate::error::{CliError, ScenarioError};
+    use crate::filter::Shard;
+    use crate::logging::{Level, LogFormat, LogOutput, TimestampMode, TracingConfig};
+    use crate::outcome::Outcome;
+    use crate::report::ErrorAnnotation;
+    use crate::scenario::{Scenario, ScenarioGroupImpl};
+    use crate::scenario_env::ScenarioEnv;
+    use crate::test_context::{NoopObserver, RunOptions, RunStats, TestContext};
+    use crate::test_utils::SharedBuffer;
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+    use std::io::{BufRead, Read};
+    use std::path::PathBuf;
+    use std::process::ExitCode;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::time::{Duration, Instant};
+
+    struct ScenarioStub {
+        name: String,
+    }
+
+    impl ScenarioStub {
+        fn new(name: &str) -> Self {
+            Self {
+                name: name.to_string(),
+            }
+        }
+    }
+
     impl Scenario for ScenarioStub {
         fn name(&self) -> &str {
             &self.name
         }
 
-        fn run(&self, input: &str) -> Result<(), String> {
-            match input {
-                "ok" => Ok(()),
-                "error" => Err("Requested error".to_string()),
-                _ => Err("Unknown value".to_string()),
+        fn run(&self, input: &str) -> Result<(), String> {
+            match input {
+                "ok" => Ok(()),
+                "error" => Err("Requested error".to_string()),
+                _ => Err("Unknown value".to_string()),
+            }
+        }
+    }
+
+    /// Scenario stub recording name and input of each run.
+    struct RecordingScenarioStub {
+        name: String,
+        records: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl Scenario for RecordingScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            self.records
+                .lock()
+                .unwrap()
+                .push((self.name.clone(), input.to_string()));
+            match input {
+                "error" => Err("Requested error".to_string()),
+                "sleep" => {
+                    std::thread::sleep(Duration::from_millis(200));
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+
+    type Records = Arc<Mutex<Vec<(String, String)>>>;
+
+    struct PriorityScenarioStub {
+        name: String,
+        priority: i32,
+        records: Records,
+    }
+
+    impl Scenario for PriorityScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            self.records
+                .lock()
+                .unwrap()
+                .push((self.name.clone(), input.to_string()));
+            Ok(())
+        }
+
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+    }
+
+    fn init_recording_context(names: &[&str]) -> (TestContext, Records) {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let scenarios: Vec<Box<dyn Scenario>> = names
+            .iter()
+            .map(|name| {
+                Box::new(RecordingScenarioStub {
+                    name: name.to_string(),
+                    records: records.clone(),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let root_group = ScenarioGroupImpl::new("root", scenarios, vec![]);
+        (TestContext::new(Box::new(root_group)), records)
+    }
+
+    /// Run CLI application with default configuration, capturing stdout and stderr outputs.
+    fn run_cli_app_captured(
+        raw_arguments: &[String],
+        test_context: &TestContext,
+    ) -> (Result<(), CliError>, String, String) {
+        run_cli_app_captured_with_input(raw_arguments, test_context, &mut std::io::empty())
+    }
+
+    /// Run CLI application with default configuration and provided stdin,
+    /// capturing stdout and stderr outputs.
+    fn run_cli_app_captured_with_input(
+        raw_arguments: &[String],
+        test_context: &TestContext,
+        input: &mut (dyn BufRead + Send),
+    ) -> (Result<(), CliError>, String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let result = run_cli_app_with_io(
+            raw_arguments,
+            test_context,
+            &CliConfig::default(),
+            input,
+            &mut out,
+            &mut err,
+        );
+        let to_string = |output| String::from_utf8(output).unwrap();
+        (result, to_string(out), to_string(err))
+    }
+
+    /// Write file with unique name to temporary directory.
+    fn write_temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_empty() {
+        let raw_arguments = vec![];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        // Default values are expected.
+        assert!(cli_arguments.scenario_arguments.names.is_empty());
+        assert!(cli_arguments.scenario_arguments.input.is_none());
+        assert!(!cli_arguments.list_scenarios);
+        assert!(!cli_arguments.help);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_executable_name_only() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = vec![exe_name];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        // Default values are expected.
+        assert!(cli_arguments.scenario_arguments.names.is_empty());
+        assert!(cli_arguments.scenario_arguments.input.is_none());
+        assert!(!cli_arguments.list_scenarios);
+        assert!(!cli_arguments.help);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_name_ok() {
+        let exe_name = "exe_name".to_string();
+        for arg in ["-n", "--name"] {
+            let example_name = "example_name".to_string();
+            let raw_arguments = vec![exe_name.clone(), arg.to_string(), example_name.clone()];
+            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+            assert!(cli_arguments.scenario_arguments.names == vec![example_name.clone()]);
+            assert!(cli_arguments.scenario_arguments.input.is_none());
+            assert!(!cli_arguments.list_scenarios);
+            assert!(!cli_arguments.help);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_name_missing() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--name".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Failed to read name parameter"))
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_input_ok() {
+        for arg in ["-i", "--input"] {
+            let exe_name = "exe_name".to_string();
+            let example_input = "example_input".to_string();
+            let raw_arguments = [exe_name.clone(), arg.to_string(), example_input.clone()];
+            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+            assert!(cli_arguments.scenario_arguments.names.is_empty());
+            assert!(cli_arguments
+                .scenario_arguments
+                .input
+                .is_some_and(|n| n == example_input));
+            assert!(!cli_arguments.list_scenarios);
+            assert!(!cli_arguments.help);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_input_missing() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--input".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Failed to read input parameter"))
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_concurrency_ok() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--concurrency".to_string(), "4".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert!(cli_arguments
+            .scenario_arguments
+            .concurrency
+            .is_some_and(|c| c == 4));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_concurrency_invalid() {
+        let exe_name = "exe_name".to_string();
+        for (value, expected) in [
+            ("0", "--concurrency must be at least 1, got '0'"),
+            ("-1", "--concurrency expects a number, got '-1'"),
+            ("abc", "--concurrency expects a number, got 'abc'"),
+        ] {
+            let raw_arguments = [
+                exe_name.clone(),
+                "--concurrency".to_string(),
+                value.to_string(),
+            ];
+            let result = parse_cli_arguments(&raw_arguments);
+            assert!(result.is_err_and(|e| e == expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_numeric_arg() {
+        assert_eq!(parse_numeric_arg::<u64>("seed", "42"), Ok(42));
+        assert_eq!(
+            parse_numeric_arg::<u64>("seed", "forty-two"),
+            Err("--seed expects a number, got 'forty-two'".to_string())
+        );
+        assert_eq!(parse_numeric_arg_min::<usize>("repeat", "1", 1), Ok(1));
+        assert_eq!(
+            parse_numeric_arg_min::<usize>("repeat", "0", 1),
+            Err("--repeat must be at least 1, got '0'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_concurrency_missing() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--concurrency".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Failed to read concurrency parameter"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_timeout_ok() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--timeout".to_string(), "1.5".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert!(cli_arguments
+            .scenario_arguments
+            .timeout
+            .is_some_and(|t| t == Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_timeout_invalid() {
+        let exe_name = "exe_name".to_string();
+        for (value, expected) in [
+            (
+                "0",
+                "--timeout must be a positive number of seconds, got '0'",
+            ),
+            (
+                "-1",
+                "--timeout must be a positive number of seconds, got '-1'",
+            ),
+            ("abc", "--timeout expects a number, got 'abc'"),
+        ] {
+            let raw_arguments = [exe_name.clone(), "--timeout".to_string(), value.to_string()];
+            let result = parse_cli_arguments(&raw_arguments);
+            assert!(result.is_err_and(|e| e == expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_repeat() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name.clone(), "--repeat".to_string(), "3".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments
+            .scenario_arguments
+            .repeat
+            .is_some_and(|r| r == 3));
+
+        let raw_arguments = [exe_name.clone(), "--repeat".to_string(), "0".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--repeat must be at least 1, got '0'"));
+
+        let raw_arguments = [exe_name, "--repeat".to_string(), "three".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--repeat expects a number, got 'three'"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_report_tap() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [
+            exe_name,
+            "--report-tap".to_string(),
+            "report.tap".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert!(cli_arguments.report_tap.is_some_and(|p| p == "report.tap"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_tracing_config() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--log-level".to_string(),
+            "info".to_string(),
+            "--log-format".to_string(),
+            "text".to_string(),
+            "--timestamp-mode".to_string(),
+            "none".to_string(),
+            "--log-output".to_string(),
+            "stderr".to_string(),
+            "--log-thread-ids".to_string(),
+            "false".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert_eq!(
+            cli_arguments.tracing_config,
+            TracingConfig {
+                level: Level::INFO,
+                format: LogFormat::Text,
+                timestamp_mode: TimestampMode::None,
+                output: LogOutput::Stderr,
+                nonblocking: false,
+                thread_ids: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_tracing_config_default() {
+        let raw_arguments = ["exe_name".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert_eq!(cli_arguments.tracing_config, TracingConfig::default());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_log_thread_ids_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--log-thread-ids".to_string(),
+            "no".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Invalid log-thread-ids parameter: no"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_log_level_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--log-level".to_string(),
+            "loud".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Invalid log level: loud"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_filter_and_count() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--tag".to_string(),
+            "fast".to_string(),
+            "--skip".to_string(),
+            "group".to_string(),
+            "--tag".to_string(),
+            "smoke".to_string(),
+            "--count".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert!(cli_arguments.count);
+        assert_eq!(cli_arguments.filter.tags, vec!["fast", "smoke"]);
+        assert_eq!(cli_arguments.filter.skip, vec!["group"]);
+    }
+
+    #[test]
+    fn test_run_cli_app_count() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--count".to_string(),
+            "--skip".to_string(),
+            "first".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().is_empty());
+        assert_eq!(out, "1\n");
+        assert_eq!(err, "");
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_shard() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--shard".to_string(),
+            "2/3".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert_eq!(
+            cli_arguments.filter.shard,
+            Some(Shard { index: 2, count: 3 })
+        );
+
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--shard".to_string(),
+            "3/2".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(
+            result.is_err_and(|e| e == "Invalid shard parameter: 3/2, expected <index>/<count>")
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_shard() {
+        let (test_context, records) = init_nested_recording_context();
+        let mut listed = Vec::new();
+        let mut run = Vec::new();
+        for shard in ["1/2", "2/2"] {
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--list-scenarios".to_string(),
+                "--shard".to_string(),
+                shard.to_string(),
+            ];
+            let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+            assert!(result.is_ok());
+            listed.push(out.lines().map(str::to_string).collect::<Vec<_>>());
+
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--name".to_string(),
+                "outer_group".to_string(),
+                "--input".to_string(),
+                "ok".to_string(),
+                "--shard".to_string(),
+                shard.to_string(),
+            ];
+            let result = run_cli_app(&raw_arguments, &test_context);
+            assert!(result.is_ok());
+            run.push(std::mem::take(&mut *records.lock().unwrap()).len());
+        }
+
+        // Shards are disjoint and together contain all scenarios.
+        assert_eq!(
+            listed,
+            vec![
+                vec!["outer_group.outer_scenario", "other_scenario"],
+                vec!["outer_group.inner_group.inner_scenario", "ambiguous"],
+            ]
+        );
+        assert_eq!(run, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_run_cli_app_count_tags() {
+        let scenarios: Vec<Box<dyn Scenario>> = [("first", vec!["smoke"]), ("second", vec![])]
+            .into_iter()
+            .map(|(name, tags)| {
+                Box::new(TaggedScenarioStub {
+                    name: name.to_string(),
+                    tags: tags.into_iter().map(String::from).collect(),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let group = ScenarioGroupImpl::new("tagged_group", scenarios, vec![])
+            .with_tags(vec!["nightly".to_string()]);
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(TaggedScenarioStub {
+                name: "untagged".to_string(),
+                tags: Vec::new(),
+            })],
+            vec![Box::new(group)],
+        );
+        let test_context = TestContext::new(Box::new(root_group));
+
+        // Total count, then count of scenarios with own and group-inherited tags.
+        for (tag, expected) in [
+            (None, "3\n"),
+            (Some("smoke"), "1\n"),
+            (Some("nightly"), "2\n"),
+        ] {
+            let mut raw_arguments = vec!["exe_name".to_string(), "--count".to_string()];
+            if let Some(tag) = tag {
+                raw_arguments.extend(["--tag".to_string(), tag.to_string()]);
+            }
+            let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+            assert!(result.is_ok());
+            assert_eq!(out, expected, "{tag:?}");
+        }
+    }
+
+    #[test]
+    fn test_config_json() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--log-level".to_string(),
+            "info".to_string(),
+            "--timeout".to_string(),
+            "2.5".to_string(),
+            "--print-config".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.print_config);
+
+        let config = config_json(&cli_arguments);
+        assert_eq!(config["log"]["level"], "info");
+        assert_eq!(config["log"]["format"], "json");
+        assert_eq!(config["timeout"], 2.5);
+        assert_eq!(config["repeat"], Value::Null);
+        assert_eq!(config["color"], "auto");
+    }
+
+    #[test]
+    fn test_run_cli_app_print_config() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--print-config".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_list_scenarios() {
+        let exe_name = "exe_name".to_string();
+        for arg in ["-l", "--list-scenarios"] {
+            let raw_arguments = [exe_name.clone(), arg.to_string()];
+            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+            assert!(cli_arguments.scenario_arguments.names.is_empty());
+            assert!(cli_arguments.scenario_arguments.input.is_none());
+            assert!(cli_arguments.list_scenarios);
+            assert!(!cli_arguments.help);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_help() {
+        let exe_name = "exe_name".to_string();
+        for arg in ["-h", "--help"] {
+            let raw_arguments = [exe_name.clone(), arg.to_string()];
+            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+            assert!(cli_arguments.scenario_arguments.names.is_empty());
+            assert!(cli_arguments.scenario_arguments.input.is_none());
+            assert!(!cli_arguments.list_scenarios);
+            assert!(cli_arguments.help);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_unknown_argument() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = [exe_name, "--invalid-arg".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Unknown argument provided: --invalid-arg"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_response_file() {
+        let path = write_temp_file(
+            "test_parse_cli_arguments_response_file.txt",
+            "--name foo\n--input bar\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            format!("@{}", path.display()),
+            "--json-errors".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        std::fs::remove_file(&path).unwrap();
+
+        let cli_arguments = result.unwrap();
+        assert_eq!(
+            cli_arguments.scenario_arguments.names,
+            vec!["foo".to_string()]
+        );
+        assert!(cli_arguments
+            .scenario_arguments
+            .input
+            .is_some_and(|i| i == "bar"));
+        assert!(cli_arguments.json_errors);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_response_file_nested() {
+        let path = write_temp_file(
+            "test_parse_cli_arguments_response_file_nested.txt",
+            "--name foo @other.txt",
+        );
+        let raw_arguments = ["exe_name".to_string(), format!("@{}", path.display())];
+        let result = parse_cli_arguments(&raw_arguments);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| e == "Nested response files are not supported: @other.txt"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_response_file_missing() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "@/nonexistent/arguments.txt".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(
+            |e| e.starts_with("Failed to read response file /nonexistent/arguments.txt")
+        ));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_all() {
+        let exe_name = "exe_name".to_string();
+        let example_name = "example_name".to_string();
+        let example_input = "example_input".to_string();
+        let raw_arguments = [
+            exe_name,
+            "--help".to_string(),
+            "--list-scenarios".to_string(),
+            "--input".to_string(),
+            example_input.clone(),
+            "--name".to_string(),
+            example_name.clone(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert!(cli_arguments.scenario_arguments.names == vec![example_name.clone()]);
+        assert!(cli_arguments
+            .scenario_arguments
+            .input
+            .is_some_and(|n| n == example_input));
+        assert!(cli_arguments.list_scenarios);
+        assert!(cli_arguments.help);
+    }
+
+    #[test]
+    fn test_run_cli_app_show_help() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = vec![exe_name, "--help".to_string()];
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        let mut expected = Vec::new();
+        write_help(&mut expected, &CliConfig::default()).unwrap();
+        assert_eq!(err.as_bytes(), expected);
+        assert!(
+            err.starts_with("Test scenario runner\n'-n', '--name' - test scenario or group name")
+        );
+        assert!(err.ends_with("'--conc'\n"));
+        assert_eq!(out, "");
+    }
+
+    #[test]
+    fn test_run_cli_app_help_precedence() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--help".to_string(),
+            "--print-config".to_string(),
+            "--input-template".to_string(),
+            "missing_template.json".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok(), "{result:?}");
+        assert!(err.starts_with("Test scenario runner\n"));
+        assert_eq!(out, "");
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_help_default_config() {
+        let mut out = Vec::new();
+        write_help(&mut out, &CliConfig::default()).unwrap();
+
+        let help = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = help.lines().collect();
+        assert_eq!(lines[0], "Test scenario runner");
+        assert!(lines[1].starts_with("'-n', '--name'"));
+    }
+
+    #[test]
+    fn test_write_help_custom_config() {
+        let config = CliConfig {
+            program_name: Some("branded_tool".to_string()),
+            about: "Branded scenario tool".to_string(),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        write_help(&mut out, &config).unwrap();
+
+        let help = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = help.lines().collect();
+        assert_eq!(lines[0], "Branded scenario tool");
+        assert_eq!(lines[1], "Usage: branded_tool [options]");
+        assert!(!help.contains("Test scenario runner"));
+    }
+
+    #[test]
+    fn test_run_cli_app_with_config_show_help() {
+        let raw_arguments = vec!["exe_name".to_string(), "--help".to_string()];
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+        let config = CliConfig {
+            program_name: Some("branded_tool".to_string()),
+            ..Default::default()
+        };
+
+        let result = run_cli_app_with_config(&raw_arguments, &test_context, &config);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_run_cli_app_with_io_custom_help() {
+        let raw_arguments = vec!["exe_name".to_string(), "--help".to_string()];
+        let (test_context, _) = init_recording_context(&["first"]);
+        let config = CliConfig {
+            program_name: Some("branded_tool".to_string()),
+            about: "Branded scenario tool".to_string(),
+            ..Default::default()
+        };
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+
+        let result = run_cli_app_with_io(
+            &raw_arguments,
+            &test_context,
+            &config,
+            &mut std::io::empty(),
+            &mut out,
+            &mut err,
+        );
+        assert!(result.is_ok());
+        assert!(out.is_empty());
+        assert!(err.starts_with(b"Branded scenario tool\nUsage: branded_tool [options]\n"));
+    }
+
+    #[test]
+    fn test_run_cli_app_list_scenarios() {
+        let exe_name = "exe_name".to_string();
+        let raw_arguments = vec![exe_name, "--list-scenarios".to_string()];
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(out, "");
+        assert_eq!(err, "(no scenarios registered)\n");
+    }
+
+    #[test]
+    fn test_run_cli_app_list_scenarios_output() {
+        let raw_arguments = ["exe_name".to_string(), "--list-scenarios".to_string()];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(out, "first\nsecond\n");
+        assert_eq!(err, "");
+    }
+
+    /// Scenario stub with tags, fails if its name starts with `failing`.
+    struct TaggedScenarioStub {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    impl Scenario for TaggedScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            if self.name.starts_with("failing") {
+                return Err("Requested error".to_string());
+            }
+            Ok(())
+        }
+
+        fn tags(&self) -> &[String] {
+            &self.tags
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_output_dir() {
+        let output_dir = std::env::temp_dir()
+            .join(format!("{}_output_dir", std::process::id()))
+            .join("run");
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "root_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--output-dir".to_string(),
+            output_dir.to_string_lossy().to_string(),
+        ];
+        let test_context = init_output_dir_context();
+
+        let (result, _, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        let read = |file_name: &str| std::fs::read_to_string(output_dir.join(file_name)).unwrap();
+        let summary: Value = serde_json::from_str(&read("summary.json")).unwrap();
+        let junit = read("junit.xml");
+        let passing_log = read("root_group.passing.log");
+        let failing_log = read("root_group.failing.log");
+        std::fs::remove_dir_all(output_dir.parent().unwrap()).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(summary["stats"]["total"], 2);
+        assert_eq!(summary["stats"]["failed"], 1);
+        assert_eq!(summary["results"][1]["name"], "root_group.failing");
+        assert_eq!(summary["results"][1]["error"], "Requested error");
+        assert!(junit.contains("<testsuite name=\"test_scenarios\" tests=\"2\" failures=\"1\""));
+        assert!(junit.contains("<testcase name=\"failing\" classname=\"root_group\""));
+        assert!(passing_log.starts_with("name: root_group.passing\nstatus: passed\n"));
+        assert!(passing_log.contains("input: ok\n"));
+        assert!(failing_log.contains("status: failed\n"));
+        assert!(failing_log.contains("outcome: Requested error\n"));
+    }
+
+    /// Test context with passing and failing scenario in `root_group`.
+    fn init_output_dir_context() -> TestContext {
+        let scenarios: Vec<Box<dyn Scenario>> = ["passing", "failing"]
+            .into_iter()
+            .map(|name| {
+                Box::new(TaggedScenarioStub {
+                    name: name.to_string(),
+                    tags: Vec::new(),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let group = ScenarioGroupImpl::new("root_group", scenarios, vec![]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_scenario_log_file_name() {
+        assert_eq!(
+            scenario_log_file_name("group.scenario"),
+            "group.scenario.log"
+        );
+        assert_eq!(scenario_log_file_name("a_b"), "a_b.log");
+        assert!(scenario_log_file_name("a/b").starts_with("a_b-"));
+
+        let names = ["a/b", "a_b", "a:b", "s[x=1]", "s[x=2]", "s_x_1_"];
+        let file_names: HashSet<String> = names
+            .iter()
+            .map(|name| scenario_log_file_name(name))
+            .collect();
+        assert_eq!(file_names.len(), names.len());
+    }
+
+    #[test]
+    fn test_run_cli_app_output_dir_expectations() {
+        let output_dir =
+            std::env::temp_dir().join(format!("{}_output_dir_expectations", std::process::id()));
+        let quarantine_path = write_temp_file(
+            "test_run_cli_app_output_dir_expectations_q.txt",
+            "root_group.failing\n",
+        );
+        let test_context = init_output_dir_context();
+        let run = |arguments: [&str; 2]| {
+            let mut raw_arguments: Vec<String> = [
+                "exe_name",
+                "--name",
+                "root_group",
+                "--input",
+                "ok",
+                "--output-dir",
+            ]
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+            raw_arguments.push(output_dir.to_string_lossy().to_string());
+            raw_arguments.extend(arguments.iter().map(|argument| argument.to_string()));
+            let (result, _, _) = run_cli_app_captured(&raw_arguments, &test_context);
+            let read =
+                |file_name: &str| std::fs::read_to_string(output_dir.join(file_name)).unwrap();
+            let summary: Value = serde_json::from_str(&read("summary.json")).unwrap();
+            (result, summary, read("junit.xml"))
+        };
+
+        let (quarantined, quarantined_summary, quarantined_junit) =
+            run(["--quarantine-file", &quarantine_path.to_string_lossy()]);
+        let (expected_fail, expected_fail_summary, expected_fail_junit) = run(["--expect", "fail"]);
+        std::fs::remove_dir_all(&output_dir).unwrap();
+        std::fs::remove_file(&quarantine_path).unwrap();
+
+        assert!(quarantined.is_ok());
+        assert_eq!(quarantined_summary["stats"]["quarantined"], 1);
+        let results = &quarantined_summary["results"];
+        assert_eq!(results[0]["expected"], true);
+        assert_eq!(results[1]["status"], "quarantined-failed");
+        assert_eq!(results[1]["expected"], true);
+        assert!(quarantined_junit.contains("tests=\"2\" failures=\"0\" skipped=\"1\""));
+        assert!(quarantined_junit
+            .contains("<skipped message=\"quarantined-failed: Requested error\"/>"));
+
+        assert!(expected_fail.is_err());
+        let results = &expected_fail_summary["results"];
+        assert_eq!(results[0]["status"], "passed");
+        assert_eq!(results[0]["expected"], false);
+        assert_eq!(results[1]["status"], "failed");
+        assert_eq!(results[1]["expected"], true);
+        assert!(expected_fail_junit.contains("tests=\"2\" failures=\"1\" skipped=\"0\""));
+        assert!(expected_fail_junit.contains(
+            "<failure message=\"Scenario root_group.passing was expected to fail, but passed\"/>"
+        ));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_run_cli_app_output_dir_events() {
+        // Run is done in a child process, so tracing subscriber is set by the run
+        // and not by another test.
+        const OUTPUT_DIR_ENV_VAR: &str = "TEST_RUN_CLI_APP_OUTPUT_DIR_EVENTS";
+        if let Ok(output_dir) = std::env::var(OUTPUT_DIR_ENV_VAR) {
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--name".to_string(),
+                "root_group.passing".to_string(),
+                "--input".to_string(),
+                "ok".to_string(),
+                "--output-dir".to_string(),
+                output_dir,
+            ];
+            let (result, _, _) = run_cli_app_captured(&raw_arguments, &init_output_dir_context());
+            assert!(result.is_ok());
+            return;
+        }
+
+        let output_dir =
+            std::env::temp_dir().join(format!("{}_output_dir_events", std::process::id()));
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "cli::tests::test_run_cli_app_output_dir_events",
+                "--exact",
+                "--quiet",
+            ])
+            .env(OUTPUT_DIR_ENV_VAR, &output_dir)
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        let events = std::fs::read_to_string(output_dir.join("events.ndjson")).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        assert!(status.success());
+        let events: Vec<Value> = events
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(!events.is_empty());
+        assert!(events.iter().any(|event| event["fields"]["message"]
+            .as_str()
+            .is_some_and(|message| message.starts_with("Run ") && message.ends_with(" started"))));
+    }
+
+    #[test]
+    fn test_run_cli_app_summary_by_tag() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "root_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--summary-by-tag".to_string(),
+        ];
+        let scenarios: Vec<Box<dyn Scenario>> = [
+            ("first", vec!["smoke", "fast"]),
+            ("failing", vec!["smoke"]),
+            ("untagged", vec![]),
+        ]
+        .into_iter()
+        .map(|(name, tags)| {
+            Box::new(TaggedScenarioStub {
+                name: name.to_string(),
+                tags: tags.into_iter().map(String::from).collect(),
+            }) as Box<dyn Scenario>
+        })
+        .collect();
+        let group = ScenarioGroupImpl::new("root_group", scenarios, vec![])
+            .with_tags(vec!["suite".to_string()]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_err());
+        assert_eq!(
+            out,
+            "fast: 1 passed, 0 failed, 0 skipped\n\
+             smoke: 1 passed, 1 failed, 0 skipped\n\
+             suite: 2 passed, 1 failed, 0 skipped\n"
+        );
+
+        // Matrix-labelled runs are counted under tags of the scenario.
+        let matrix_path = write_temp_file(
+            "test_run_cli_app_summary_by_tag_matrix.json",
+            r#"{ "param": ["a", "b"] }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "root_group.first".to_string(),
+            "--matrix".to_string(),
+            matrix_path.to_string_lossy().to_string(),
+            "--summary-by-tag".to_string(),
+        ];
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        std::fs::remove_file(&matrix_path).unwrap();
+        assert!(result.is_ok());
+        assert_eq!(
+            out,
+            "fast: 2 passed, 0 failed, 0 skipped\n\
+             smoke: 2 passed, 0 failed, 0 skipped\n\
+             suite: 2 passed, 0 failed, 0 skipped\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_list_tree_output() {
+        let raw_arguments = ["exe_name".to_string(), "--list-tree".to_string()];
+        let (test_context, _) = init_nested_recording_context();
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            vec![
+                "outer_group/",
+                "  inner_group/",
+                "    inner_scenario",
+                "  outer_scenario",
+                "ambiguous/",
+                "other_scenario",
+                "ambiguous",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_catalog_ndjson() {
+        let raw_arguments = ["exe_name".to_string(), "--catalog-ndjson".to_string()];
+        let (test_context, _) = init_nested_recording_context();
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        let entries: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, test_context.list_scenarios());
+        assert_eq!(
+            entries[0],
+            json!({
+                "name": "outer_group.inner_group.inner_scenario",
+                "tags": [],
+                "group_path": ["outer_group", "inner_group"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_catalog_ndjson_tags() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--catalog-ndjson".to_string(),
+            "--tag".to_string(),
+            "smoke".to_string(),
+        ];
+        let scenario = TaggedScenarioStub {
+            name: "tagged".to_string(),
+            tags: vec!["smoke".to_string()],
+        };
+        let other = TaggedScenarioStub {
+            name: "other".to_string(),
+            tags: vec!["slow".to_string()],
+        };
+        let group =
+            ScenarioGroupImpl::new("group", vec![Box::new(scenario), Box::new(other)], vec![]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            out,
+            "{\"group_path\":[\"group\"],\"name\":\"group.tagged\",\"tags\":[\"smoke\"]}\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_failure_output() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--json-errors".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let (result, out, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        let error = result.unwrap_err();
+        assert_eq!(out, "");
+        assert_eq!(
+            err,
+            format!(
+                "Scenario first failed: Requested error\n{}\n",
+                error.to_json()
+            )
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_no_scenarios_registered() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "example_scenario".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+        ];
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e == CliError::Usage("no scenarios registered".to_string())));
+    }
+
+    #[test]
+    fn test_run_cli_app_ok() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [
+            exe_name,
+            "--name".to_string(),
+            scenario_name.to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_run_cli_app_error() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [
+            exe_name,
+            "--name".to_string(),
+            scenario_name.to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+        ];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        // It's expected that test will fail due to error from `ScenarioStub`, not from `run_cli_app`.
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Scenario(_)) && e.to_string() == "Requested error"
+        ));
+    }
+
+    #[test]
+    fn test_run_cli_app_concurrency_error() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [
+            exe_name,
+            "--name".to_string(),
+            scenario_name.to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--concurrency".to_string(),
+            "3".to_string(),
+        ];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Scenario(_))
+            && e.to_string() == "3 of 3 concurrent runs failed, first error: Requested error"));
+    }
+
+    #[test]
+    fn test_run_cli_app_report_tap() {
+        let report_path = std::env::temp_dir().join(format!(
+            "test_run_cli_app_report_tap_{}.tap",
+            std::process::id()
+        ));
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [
+            exe_name,
+            "--name".to_string(),
+            scenario_name.to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--report-tap".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let tap = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Scenario(_)) && e.to_string() == "Requested error"
+        ));
+        assert!(tap.contains("1..1\n"));
+        assert!(tap.contains("not ok 1 - example_scenario\n"));
+        assert!(tap.contains("message: \"Requested error\""));
+    }
+
+    #[test]
+    fn test_run_cli_app_report_write_failure() {
+        let report_path = std::env::temp_dir()
+            .join(format!(
+                "test_run_cli_app_report_write_failure_{}",
+                std::process::id()
+            ))
+            .join("missing")
+            .join("report.tap");
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--report-tap".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Io(message)
+            if message.starts_with("Failed to write TAP report"))
+            && e.exit_code() == 1));
+    }
+
+    #[test]
+    fn test_run_cli_app_report_csv() {
+        let path = std::env::temp_dir().join(format!(
+            "test_run_cli_app_report_csv_{}.csv",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--report-csv".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, _) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| matches!(e, CliError::Scenario(_))));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "name,status,duration_us,error,input,deprecated,slow"
+        );
+        assert!(lines[1].starts_with("outer_group.inner_group.inner_scenario,failed,"));
+        assert!(lines[1].ends_with(",Requested error,error,false,false"));
+        assert!(lines[2].starts_with("outer_group.outer_scenario,failed,"));
+    }
+
+    #[test]
+    fn test_run_cli_app_report_csv_truncated_input() {
+        let path = write_temp_file("test_run_cli_app_report_csv_truncated_input.csv", "");
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "0123456789".to_string(),
+            "--max-reported-input".to_string(),
+            "4".to_string(),
+            "--report-csv".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        // Scenario receives full input.
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("first".to_string(), "0123456789".to_string())]
+        );
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[1].starts_with("first,passed,"));
+        assert!(lines[1].ends_with(",,0123...,false,false"), "{}", lines[1]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_cli_app_report_csv_resource_stats() {
+        let path = write_temp_file("test_run_cli_app_report_csv_resource_stats.csv", "");
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--resource-stats".to_string(),
+            "--report-csv".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "name,status,duration_us,error,input,deprecated,slow,max_rss_bytes,cpu_time_us"
+        );
+        for line in &lines[1..] {
+            let columns: Vec<&str> = line.split(',').collect();
+            assert!(columns[7].parse::<u64>().is_ok_and(|rss| rss > 0), "{line}");
+            assert!(columns[8].parse::<u64>().is_ok(), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_multiple_names() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "-n".to_string(),
+            "second".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert_eq!(
+            cli_arguments.scenario_arguments.names,
+            vec!["first".to_string(), "second".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_multiple_names() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "other_scenario".to_string(),
+            "--name".to_string(),
+            "outer_group.inner_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("other_scenario".to_string(), "ok".to_string()),
+                ("inner_scenario".to_string(), "ok".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_name_regex() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name-regex".to_string(),
+            "^outer_group\\..*_scenario$".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("inner_scenario".to_string(), "ok".to_string()),
+                ("outer_scenario".to_string(), "ok".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_name_regex_no_match() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name-regex".to_string(),
+            "^missing".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result
+            .is_err_and(|e| e.to_string() == "No scenarios match regular expression ^missing"));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_name_regex_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name-regex".to_string(),
+            "(outer_group".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+
+        assert!(result
+            .is_err_and(|e| e == "Invalid --name-regex pattern: unclosed group at position 0"));
+    }
+
+    #[test]
+    fn test_run_cli_app_multiple_names_unknown() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "other_scenario".to_string(),
+            "--name".to_string(),
+            "typo_scenario".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--name".to_string(),
+            "outer_group.typo_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Scenarios not found: typo_scenario, outer_group.typo_group".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_multiple_names_with_repeat() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "other_scenario".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--repeat".to_string(),
+            "2".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Concurrency and repeat are not supported for multiple names".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    /// Scenario stub failing on even iterations.
+    struct EvenFailingScenarioStub {
+        counter: Arc<AtomicUsize>,
+    }
+
+    impl Scenario for EvenFailingScenarioStub {
+        fn name(&self) -> &str {
+            "even_failing_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            let iteration = self.counter.fetch_add(1, Ordering::SeqCst);
+            if iteration % 2 == 0 {
+                Err(format!("Failed on iteration {iteration}"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn init_even_failing_context() -> (TestContext, Arc<AtomicUsize>) {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let scenario = EvenFailingScenarioStub {
+            counter: counter.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        (TestContext::new(Box::new(root_group)), counter)
+    }
+
+    #[test]
+    fn test_run_cli_app_repeat_stops_on_failure() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "even_failing_scenario".to_string(),
+            "--input".to_string(),
+            String::new(),
+            "--repeat".to_string(),
+            "4".to_string(),
+        ];
+        let (test_context, counter) = init_even_failing_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Scenario(_)) && e.to_string() == "Failed on iteration 0"
+        ));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_run_cli_app_flakiness_report() {
+        let report_path = std::env::temp_dir().join(format!(
+            "{}_test_run_cli_app_flakiness_report.json",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "even_failing_scenario".to_string(),
+            "--input".to_string(),
+            String::new(),
+            "--repeat".to_string(),
+            "4".to_string(),
+            "--flakiness-report".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, counter) = init_even_failing_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let report = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Scenario(_)) && e.to_string() == "2 of 4 runs failed"
+        ));
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(report["runs"], 4);
+        assert_eq!(report["passes"], 2);
+        assert_eq!(report["failures"], 2);
+        assert_eq!(report["pass_rate"], 0.5);
+        assert_eq!(
+            report["distinct_errors"],
+            serde_json::json!(["Failed on iteration 0", "Failed on iteration 2"])
+        );
+    }
+
+    fn init_nested_recording_context() -> (TestContext, Records) {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let recording_stub = |name: &str| -> Box<dyn Scenario> {
+            Box::new(RecordingScenarioStub {
+                name: name.to_string(),
+                records: records.clone(),
+            })
+        };
+        let inner_group = ScenarioGroupImpl::new(
+            "inner_group",
+            vec![recording_stub("inner_scenario")],
+            vec![],
+        );
+        let outer_group = ScenarioGroupImpl::new(
+            "outer_group",
+            vec![recording_stub("outer_scenario")],
+            vec![Box::new(inner_group)],
+        );
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![
+                recording_stub("other_scenario"),
+                recording_stub("ambiguous"),
+            ],
+            vec![
+                Box::new(outer_group),
+                Box::new(ScenarioGroupImpl::new("ambiguous", vec![], vec![])),
+            ],
+        );
+        (TestContext::new(Box::new(root_group)), records)
+    }
+
+    struct AliasScenarioStub {
+        name: String,
+        aliases: Vec<String>,
+    }
+
+    impl Scenario for AliasScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn aliases(&self) -> &[String] {
+            &self.aliases
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Run CLI application with `--explain` for provided name, returning printed explanation.
+    fn explain(name: &str) -> String {
+        let (test_context, records) = init_nested_recording_context();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--explain".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+        ];
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().is_empty());
+        out
+    }
+
+    #[test]
+    fn test_run_cli_app_explain_exact_match() {
+        assert_eq!(
+            explain("outer_group.outer_scenario"),
+            "outer_group.outer_scenario: exact match of scenario outer_group.outer_scenario\n"
+        );
+        assert_eq!(
+            explain("outer_group.inner_group"),
+            "outer_group.inner_group: group of scenarios outer_group.inner_group.inner_scenario\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_explain_alias() {
+        let scenario = AliasScenarioStub {
+            name: "scenario".to_string(),
+            aliases: vec!["old_scenario".to_string()],
+        };
+        let group = ScenarioGroupImpl::new("group", vec![Box::new(scenario)], vec![]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let test_context = TestContext::new(Box::new(root_group));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--explain".to_string(),
+            "--name".to_string(),
+            "group.old_scenario".to_string(),
+        ];
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            out,
+            "group.old_scenario: alias of scenario group.scenario\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_explain_pattern() {
+        assert_eq!(
+            explain("outer_group.*"),
+            "outer_group.*: pattern matching scenarios outer_group.inner_group.inner_scenario, \
+             outer_group.outer_scenario, patterns are only accepted by '--enable' and '--disable'\n"
+        );
+        assert_eq!(
+            explain("*_missing"),
+            "*_missing: pattern matching no scenarios\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_explain_not_found() {
+        assert_eq!(
+            explain("Outer_Group/Outer_Scenaro"),
+            "Outer_Group/Outer_Scenaro: not found, did you mean outer_group.outer_scenario\n"
+        );
+        assert_eq!(explain("missing"), "missing: not found\n");
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_explain_without_name() {
+        let raw_arguments = ["exe_name".to_string(), "--explain".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+
+        assert!(result.is_err_and(|e| e == "--explain requires --name"));
+    }
+
+    #[test]
+    fn test_run_cli_app_group() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("inner_scenario".to_string(), "ok".to_string()),
+                ("outer_scenario".to_string(), "ok".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_group_failure() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let expected_names = vec![
+            "outer_group.inner_group.inner_scenario".to_string(),
+            "outer_group.outer_scenario".to_string(),
+        ];
+        let expected_message = format!("2 of 2 scenarios failed: {}", expected_names.join(", "));
+        assert!(result.is_err_and(
+            |e| e == CliError::Scenario(ScenarioError::new(expected_names, expected_message))
+        ));
+        assert_eq!(records.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_run_cli_app_group_markers() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--markers".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(records.lock().unwrap().len(), 2);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{out}");
+        for (line, prefix) in lines.iter().zip([
+            "##SCENARIO_START name=outer_group.inner_group.inner_scenario",
+            "##SCENARIO_END name=outer_group.inner_group.inner_scenario status=",
+            "##SCENARIO_START name=outer_group.outer_scenario",
+            "##SCENARIO_END name=outer_group.outer_scenario status=",
+        ]) {
+            assert!(line.starts_with(prefix), "{out}");
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_group_ambiguous() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "ambiguous".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Name ambiguous is ambiguous, matches both scenario and group".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_playlist() {
+        let path = write_temp_file(
+            "test_read_playlist.txt",
+            "# Comment\nfirst\n\n  \nsecond\tsecond_input\n  # Indented comment\n",
+        );
+        let entries = read_playlist(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            entries.unwrap(),
+            vec![
+                ("first".to_string(), None),
+                ("second".to_string(), Some("second_input".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_enable_disable() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second_slow".to_string(),
+            "--name".to_string(),
+            "third".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--enable".to_string(),
+            "first".to_string(),
+            "--enable".to_string(),
+            "second*".to_string(),
+            "--disable".to_string(),
+            "*_slow".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second_slow", "third"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("first".to_string(), "".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_disabled_single_scenario() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--disable".to_string(),
+            "first".to_string(),
+            "--enable".to_string(),
+            "first".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_max_input_bytes() {
+        for (input, expected) in [
+            ("abcd", Ok(())),
+            (
+                "abcde",
+                Err(CliError::Usage(
+                    "Test scenario input of 5 bytes exceeds limit of 4 bytes".to_string(),
+                )),
+            ),
+        ] {
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--max-input-bytes".to_string(),
+                "4".to_string(),
+                "--name".to_string(),
+                "first".to_string(),
+                "--input".to_string(),
+                input.to_string(),
+            ];
+            let (test_context, _) = init_recording_context(&["first"]);
+
+            let result = run_cli_app(&raw_arguments, &test_context);
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_max_input_bytes_playlist() {
+        let path = write_temp_file(
+            "test_run_cli_app_max_input_bytes_playlist.txt",
+            "first\tabcd\nfirst\tabcde\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--max-input-bytes".to_string(),
+            "4".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Test scenario input of 5 bytes exceeds limit of 4 bytes".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_playlist_ok() {
+        let path = write_temp_file(
+            "test_run_cli_app_playlist_ok.txt",
+            "second\nfirst\tinline_input\nsecond\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "shared_input".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("second".to_string(), "shared_input".to_string()),
+                ("first".to_string(), "inline_input".to_string()),
+                ("second".to_string(), "shared_input".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_playlist_failure() {
+        let path = write_temp_file(
+            "test_run_cli_app_playlist_failure.txt",
+            "first\tok\nsecond\terror\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Scenario(_))
+            && e.to_string() == "1 of 2 scenarios failed: second"));
+        assert_eq!(records.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_run_cli_app_playlist_missing_scenario() {
+        let path = write_temp_file(
+            "test_run_cli_app_playlist_missing_scenario.txt",
+            "first\nmissing_1\nsecond\nmissing_2\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Scenarios from playlist not found: missing_1, missing_2".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    /// Run CLI application with environment variables provided by `env_var`
+    /// instead of process environment, which is shared by tests.
+    fn run_cli_app_with_env(
+        raw_arguments: &[String],
+        test_context: &TestContext,
+        env_var: fn(&str) -> Option<String>,
+    ) -> Result<(), CliError> {
+        let config = CliConfig {
+            env_var,
+            ..Default::default()
+        };
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        run_cli_app_with_io(
+            raw_arguments,
+            test_context,
+            &config,
+            &mut std::io::empty(),
+            &mut out,
+            &mut err,
+        )
+    }
+
+    /// Environment with scenario `first` and input `env_input`.
+    fn scenario_env_var(key: &str) -> Option<String> {
+        match key {
+            NAME_ENV_VAR => Some("first".to_string()),
+            INPUT_ENV_VAR => Some("env_input".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_env_only() {
+        let raw_arguments = ["exe_name".to_string()];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, scenario_env_var);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("first".to_string(), "env_input".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_cli_overrides_env() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "cli_input".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, scenario_env_var);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("second".to_string(), "cli_input".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_missing_input() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [exe_name, "--name".to_string(), scenario_name.to_string()];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        // It's expected that test will fail due to error from `ScenarioStub`, not from `run_cli_app`.
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, |_| None);
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Test scenario input must be provided".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_run_cli_app_missing_name() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = vec![exe_name];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, |_| None);
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Test scenario name must be provided".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_run_cli_app_empty_name() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = vec![exe_name, "--name".to_string(), String::new()];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Test scenario name must not be empty".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_run_cli_app_invalid_name() {
+        let exe_name = "exe_name".to_string();
+        let scenario_name = "example_scenario";
+        let raw_arguments = [
+            exe_name,
+            "--name".to_string(),
+            "invalid_scenario".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+        ];
+        let scenario = ScenarioStub::new(scenario_name);
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        // It's expected that test will fail due to error from `TestContext`, not from `run_cli_app`.
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Scenario invalid_scenario not found".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_read_quarantine_file() {
+        let path = write_temp_file(
+            "test_read_quarantine_file.txt",
+            "# Flaky scenarios\nfirst\n\n  second  \n",
+        );
+        let names = read_quarantine_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            names.unwrap(),
+            vec!["first".to_string(), "second".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_quarantined_failure() {
+        let quarantine_path =
+            write_temp_file("test_run_cli_app_quarantined_failure_q.txt", "second\n");
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_quarantined_failure_p.txt",
+            "first\tok\nsecond\terror\n",
+        );
+        let tap_path = write_temp_file("test_run_cli_app_quarantined_failure.tap", "");
+        let csv_path = write_temp_file("test_run_cli_app_quarantined_failure.csv", "");
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+            "--quarantine-file".to_string(),
+            quarantine_path.to_string_lossy().to_string(),
+            "--markers".to_string(),
+            "--report-tap".to_string(),
+            tap_path.to_string_lossy().to_string(),
+            "--report-csv".to_string(),
+            csv_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        let tap = std::fs::read_to_string(&tap_path).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        for path in [&quarantine_path, &playlist_path, &tap_path, &csv_path] {
+            std::fs::remove_file(path).unwrap();
+        }
+
+        assert!(result.is_ok());
+        assert_eq!(records.lock().unwrap().len(), 2);
+        let end_markers: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("##SCENARIO_END"))
+            .collect();
+        assert_eq!(end_markers.len(), 2, "{out}");
+        assert!(end_markers[1].contains("quarantined-failed"), "{out}");
+        assert!(
+            tap.contains("message: \"quarantined-failed: Requested error\""),
+            "{tap}"
+        );
+        let csv_lines: Vec<&str> = csv.lines().collect();
+        assert!(csv_lines[1].starts_with("first,passed,"), "{csv}");
+        assert!(
+            csv_lines[2].starts_with("second,quarantined-failed,"),
+            "{csv}"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_not_quarantined_failure() {
+        let quarantine_path =
+            write_temp_file("test_run_cli_app_not_quarantined_failure_q.txt", "first\n");
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_not_quarantined_failure_p.txt",
+            "first\terror\nsecond\terror\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+            "--quarantine-file".to_string(),
+            quarantine_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&quarantine_path).unwrap();
+        std::fs::remove_file(&playlist_path).unwrap();
+
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Scenario(_))
+            && e.to_string() == "1 of 2 scenarios failed: second"));
+    }
+
+    #[test]
+    fn test_run_cli_app_quarantine_file_missing() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--quarantine-file".to_string(),
+            "/nonexistent/quarantine.txt".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Usage(m) if m.starts_with("Failed to read quarantine file"))
+        ));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    /// Scenario stub returning structured output.
+    struct OutputScenarioStub {
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl Scenario for OutputScenarioStub {
+        fn name(&self) -> &str {
+            "output_scenario"
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            self.run_output(input).map(|_| ())
+        }
+
+        fn skip_reason(&self, input: &str) -> Option<String> {
+            (input == "skip").then(|| "Requested skip".to_string())
+        }
+
+        fn run_output(&self, input: &str) -> Result<serde_json::Value, String> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            match input {
+                "error" => Err("Requested error".to_string()),
+                "sleep" => {
+                    std::thread::sleep(Duration::from_millis(500));
+                    Ok(serde_json::Value::Null)
+                }
+                _ => Ok(serde_json::json!({"measurement": 1.5, "input": input})),
+            }
+        }
+    }
+
+    fn init_output_context() -> (TestContext, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let scenario = OutputScenarioStub { runs: runs.clone() };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        (TestContext::new(Box::new(root_group)), runs)
+    }
+
+    #[test]
+    fn test_run_cli_app_emit_output() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "output_scenario".to_string(),
+            "--input".to_string(),
+            "value".to_string(),
+            "--emit-output".to_string(),
+        ];
+        let (test_context, runs) = init_output_context();
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            json!({"measurement": 1.5, "input": "value"})
+        );
+        assert!(out.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_run_cli_app_emit_output_error() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "output_scenario".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--emit-output".to_string(),
+        ];
+        let (test_context, _) = init_output_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| matches!(&e, CliError::Scenario(_)) && e.to_string() == "Requested error"
+        ));
+    }
+
+    #[test]
+    fn test_run_cli_app_emit_output_with_repeat() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "output_scenario".to_string(),
+            "--input".to_string(),
+            "value".to_string(),
+            "--emit-output".to_string(),
+            "--repeat".to_string(),
+            "2".to_string(),
+        ];
+        let (test_context, runs) = init_output_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Emit output is not supported with concurrency or repeat".to_string()
+            )));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_run_cli_app_emit_output_skip_and_timeout() {
+        let (test_context, runs) = init_output_context();
+        let run = |input: &str, timeout: &str| {
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--name".to_string(),
+                "output_scenario".to_string(),
+                "--input".to_string(),
+                input.to_string(),
+                "--emit-output".to_string(),
+                "--timeout".to_string(),
+                timeout.to_string(),
+            ];
+            run_cli_app_captured(&raw_arguments, &test_context)
+        };
+
+        // Skip reason of the scenario is checked before running it.
+        let (result, out, _) = run("skip", "1");
+        assert!(result.is_ok());
+        assert_eq!(out, "");
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        let (result, out, _) = run("sleep", "0.05");
+        assert!(result.is_err_and(|e| e.to_string() == "Scenario timed out"));
+        assert_eq!(out, "");
+
+        let (result, out, _) = run("value", "1");
+        assert!(result.is_ok());
+        assert_eq!(out, "{\"input\":\"value\",\"measurement\":1.5}\n");
+    }
+
+    #[test]
+    fn test_run_cli_app_emit_output_group() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "outer_group".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--emit-output".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Emit output is not supported for groups".to_string())
+        ));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    /// Scenario stub failing only if polluting scenario was run before it.
+    struct OrderDependentScenarioStub {
+        name: String,
+        polluted: Arc<AtomicBool>,
+    }
+
+    impl Scenario for OrderDependentScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            match self.name.as_str() {
+                "polluter" => self.polluted.store(true, Ordering::SeqCst),
+                "victim" if self.polluted.swap(false, Ordering::SeqCst) => {
+                    return Err("Polluted state".to_string())
+                }
+                _ => {}
+            }
+            Ok(())
+        }
+    }
+
+    fn init_order_dependent_context(names: &[&str]) -> TestContext {
+        let polluted = Arc::new(AtomicBool::new(false));
+        let scenarios: Vec<Box<dyn Scenario>> = names
+            .iter()
+            .map(|name| {
+                Box::new(OrderDependentScenarioStub {
+                    name: name.to_string(),
+                    polluted: polluted.clone(),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let root_group = ScenarioGroupImpl::new("root", scenarios, vec![]);
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_bisect_finds_pair() {
+        let names = [
+            "first", "second", "polluter", "third", "fourth", "victim", "fifth",
+        ];
+        let test_context = init_order_dependent_context(&names);
+        let runs: Vec<(String, String)> = names
+            .iter()
+            .map(|name| (name.to_string(), "input".to_string()))
+            .collect();
+
+        let reduced = bisect(&runs, None, &test_context);
+        assert_eq!(
+            reduced,
+            Some(vec![
+                ("polluter".to_string(), "input".to_string()),
+                ("victim".to_string(), "input".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bisect_no_failure() {
+        let names = ["first", "victim", "polluter"];
+        let test_context = init_order_dependent_context(&names);
+        let runs: Vec<(String, String)> = names
+            .iter()
+            .map(|name| (name.to_string(), "input".to_string()))
+            .collect();
+
+        assert!(bisect(&runs, None, &test_context).is_none());
+    }
+
+    #[test]
+    fn test_shuffle_deterministic() {
+        let mut first: Vec<usize> = (0..10).collect();
+        let mut second = first.clone();
+        shuffle(&mut first, 42);
+        shuffle(&mut second, 42);
+
+        assert_eq!(first, second);
+        assert_ne!(first, (0..10).collect::<Vec<usize>>());
+        first.sort();
+        assert_eq!(first, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_run_cli_app_bisect() {
+        let path = write_temp_file(
+            "test_run_cli_app_bisect.txt",
+            "first\npolluter\nsecond\nvictim\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "input".to_string(),
+            "--bisect".to_string(),
+        ];
+        let test_context = init_order_dependent_context(&["first", "polluter", "second", "victim"]);
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        // Reduced set is printed, then run again and still fails.
+        assert_eq!(out, "polluter\nvictim\n");
+        let expected_message = "1 of 2 scenarios failed: victim".to_string();
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["victim".to_string()],
+                expected_message
+            ))));
+    }
+
+    #[test]
+    fn test_run_cli_app_max_failures() {
+        let path = write_temp_file(
+            "test_run_cli_app_max_failures.txt",
+            "first\terror\nsecond\tok\nthird\terror\nfourth\terror\nfifth\tok\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--max-failures".to_string(),
+            "2".to_string(),
+        ];
+        let (test_context, records) =
+            init_recording_context(&["first", "second", "third", "fourth", "fifth"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        let expected_names = vec!["first".to_string(), "third".to_string()];
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                expected_names,
+                "2 of 5 scenarios failed: first, third"
+            ))));
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("first".to_string(), "error".to_string()),
+                ("second".to_string(), "ok".to_string()),
+                ("third".to_string(), "error".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_max_failures_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--max-failures".to_string(),
+            "0".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--max-failures must be at least 1, got '0'"));
+    }
+
+    #[test]
+    fn test_run_cli_app_warmup() {
+        let report_path = std::env::temp_dir().join(format!(
+            "test_run_cli_app_warmup_{}.json",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--warmup".to_string(),
+            "2".to_string(),
+            "--repeat".to_string(),
+            "3".to_string(),
+            "--flakiness-report".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let report = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        assert!(result.is_ok());
+        // Warmup runs are performed, but not reported.
+        assert_eq!(records.lock().unwrap().len(), 5);
+        let value: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(value["runs"], 3);
+    }
+
+    #[test]
+    fn test_run_cli_app_warmup_failure() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--warmup".to_string(),
+            "2".to_string(),
+            "--repeat".to_string(),
+            "3".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["first".to_string()],
+                "Warmup failed: Requested error"
+            ))));
+        assert_eq!(records.lock().unwrap().len(), 1);
+    }
+
+    /// Scenario stub recording metrics.
+    struct MetricsScenarioStub;
+
+    impl Scenario for MetricsScenarioStub {
+        fn name(&self) -> &str {
+            "metrics_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+            env.metrics().record("latency_ms", 12.5);
+            env.metrics().record("throughput", 300.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_report_metrics() {
+        let csv_path = write_temp_file("test_run_cli_app_report_metrics.csv", "");
+        let output_dir =
+            std::env::temp_dir().join(format!("{}_report_metrics", std::process::id()));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "metrics_scenario".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--report-csv".to_string(),
+            csv_path.to_string_lossy().to_string(),
+            "--output-dir".to_string(),
+            output_dir.to_string_lossy().to_string(),
+            "--stream-results".to_string(),
+        ];
+        let root_group =
+            ScenarioGroupImpl::new("root", vec![Box::new(MetricsScenarioStub)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let summary = std::fs::read_to_string(output_dir.join("summary.json")).unwrap();
+        std::fs::remove_file(&csv_path).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        assert!(result.is_ok());
+        let expected = json!({"latency_ms": 12.5, "throughput": 300.0});
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].ends_with(",slow,metrics"), "{csv}");
+        let metrics_column = lines[1].split_once(",\"").unwrap().1;
+        let metrics: Value =
+            serde_json::from_str(&metrics_column.trim_end_matches('"').replace("\"\"", "\""))
+                .unwrap();
+        assert_eq!(metrics, expected);
+        let summary: Value = serde_json::from_str(&summary).unwrap();
+        assert_eq!(summary["results"][0]["name"], "metrics_scenario");
+        assert_eq!(summary["results"][0]["metrics"], expected);
+        let streamed: Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+        assert_eq!(streamed["metrics"], expected);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_soft_hard_timeout() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--soft-timeout-ms".to_string(),
+            "100".to_string(),
+            "--hard-timeout-ms".to_string(),
+            "250".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert_eq!(
+            cli_arguments.scenario_arguments.soft_timeout,
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            cli_arguments.scenario_arguments.timeout,
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_soft_timeout_exceeds_hard() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--soft-timeout-ms".to_string(),
+            "300".to_string(),
+            "--hard-timeout-ms".to_string(),
+            "250".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Soft timeout must not exceed hard timeout"));
+    }
+
+    #[test]
+    fn test_read_matrix() {
+        let path = write_temp_file(
+            "test_read_matrix.json",
+            r#"{ "param": ["a", "b"], "other": [1, 2] }"#,
+        );
+        let combinations = read_matrix(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let inputs: Vec<String> = combinations
+            .unwrap()
+            .into_iter()
+            .map(|combination| serde_json::Value::Object(combination).to_string())
+            .collect();
+        assert_eq!(
+            inputs,
+            vec![
+                r#"{"other":1,"param":"a"}"#,
+                r#"{"other":1,"param":"b"}"#,
+                r#"{"other":2,"param":"a"}"#,
+                r#"{"other":2,"param":"b"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_matrix_empty_list() {
+        let path = write_temp_file("test_read_matrix_empty_list.json", r#"{ "param": [] }"#);
+        let result = read_matrix(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| e == "Matrix parameter param must be a non-empty list"));
+    }
+
+    #[test]
+    fn test_matrix_label() {
+        let combination = serde_json::from_str(r#"{"other":1,"param":"a"}"#).unwrap();
+        assert_eq!(
+            matrix_label("scenario", &combination),
+            "scenario[other=1,param=a]"
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_matrix() {
+        let matrix_path = write_temp_file(
+            "test_run_cli_app_matrix.json",
+            r#"{ "param": ["a", "b"], "other": [1, 2] }"#,
+        );
+        let report_path = std::env::temp_dir().join(format!(
+            "{}_test_run_cli_app_matrix.tap",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "scenario".to_string(),
+            "--matrix".to_string(),
+            matrix_path.to_string_lossy().to_string(),
+            "--report-tap".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["scenario"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&matrix_path).unwrap();
+        let tap = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        assert!(result.is_ok());
+        let inputs: Vec<String> = records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, input)| input.clone())
+            .collect();
+        assert_eq!(
+            inputs,
+            vec![
+                r#"{"other":1,"param":"a"}"#,
+                r#"{"other":1,"param":"b"}"#,
+                r#"{"other":2,"param":"a"}"#,
+                r#"{"other":2,"param":"b"}"#,
+            ]
+        );
+        assert!(tap.contains("ok 1 - scenario[other=1,param=a]\n"));
+        assert!(tap.contains("ok 4 - scenario[other=2,param=b]\n"));
+    }
+
+    #[test]
+    fn test_run_cli_app_matrix_shuffled_labels() {
+        let matrix_path = write_temp_file(
+            "test_run_cli_app_matrix_shuffled_labels.json",
+            r#"{ "param": ["a", "b", "c", "d", "e", "f"] }"#,
+        );
+        let report_path = std::env::temp_dir().join(format!(
+            "{}_test_run_cli_app_matrix_shuffled_labels.tap",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "scenario".to_string(),
+            "--matrix".to_string(),
+            matrix_path.to_string_lossy().to_string(),
+            "--seed".to_string(),
+            "7".to_string(),
+            "--report-tap".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["scenario"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&matrix_path).unwrap();
+        let tap = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        // Each result is labeled with the combination it was run with.
+        assert!(result.is_ok());
+        let labels: Vec<String> = records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, input)| {
+                let combination: Value = serde_json::from_str(input).unwrap();
+                format!("scenario[param={}]", combination["param"].as_str().unwrap())
+            })
+            .collect();
+        let reported: Vec<&str> = tap
+            .lines()
+            .filter_map(|line| line.split_once(" - ").map(|(_, name)| name))
+            .collect();
+        assert_eq!(reported, labels);
+        assert_ne!(labels[0], "scenario[param=a]", "runs were not shuffled");
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_color() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--color".to_string(),
+            "never".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert_eq!(cli_arguments.color, ColorMode::Never);
+
+        let cli_arguments = parse_cli_arguments(&["exe_name".to_string()]).unwrap();
+        assert_eq!(cli_arguments.color, ColorMode::Auto);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_color_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--color".to_string(),
+            "sometimes".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Invalid color mode: sometimes"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_abbreviated() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--conc".to_string(),
+            "2".to_string(),
+            "--list-s".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        assert_eq!(cli_arguments.scenario_arguments.concurrency, Some(2));
+        assert!(cli_arguments.list_scenarios);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_abbreviated_longer_option() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "example_name".to_string(),
+            "--input-t".to_string(),
+            "template.json".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+        // Exact match is not ambiguous with longer options it is a prefix of.
+        assert_eq!(
+            cli_arguments.scenario_arguments.names,
+            vec!["example_name".to_string()]
+        );
+        assert_eq!(
+            cli_arguments.input_template,
+            Some("template.json".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_abbreviated_ambiguous() {
+        let raw_arguments = ["exe_name".to_string(), "--ma".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| {
+            e == "Ambiguous argument --ma, matches: --max-failures, --max-input-bytes, --matrix, --manifest, --markers, --max-reported-input"
+        }));
+
+        let raw_arguments = ["exe_name".to_string(), "--inp".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result
+            .is_err_and(|e| e == "Ambiguous argument --inp, matches: --input, --input-template"));
+    }
+
+    #[test]
+    fn test_read_manifest() {
+        let path = write_temp_file(
+            "test_read_manifest.json",
+            r#"{ "second": {"value": 1}, "first": "first_input" }"#,
+        );
+        let entries = read_manifest(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            entries.unwrap(),
+            vec![
+                ("first".to_string(), "first_input".to_string()),
+                ("second".to_string(), r#"{"value":1}"#.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_manifest_ok() {
+        let path = write_temp_file(
+            "test_run_cli_app_manifest_ok.json",
+            r#"{ "outer_group.inner_group.inner_scenario": "inner_input", "other_scenario": "other_input" }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--manifest".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("other_scenario".to_string(), "other_input".to_string()),
+                ("inner_scenario".to_string(), "inner_input".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_manifest_ignores_env() {
+        let path = write_temp_file(
+            "test_run_cli_app_manifest_ignores_env.json",
+            r#"{ "second": "manifest_input" }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--manifest".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, scenario_env_var);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("second".to_string(), "manifest_input".to_string())]
+        );
+    }
+
+    /// Reader providing lines one at a time, recording number of scenario runs before each line.
+    struct StreamingReader {
+        lines: Vec<String>,
+        current: Vec<u8>,
+        position: usize,
+        records: Records,
+        runs_before_line: Vec<usize>,
+    }
+
+    impl Read for StreamingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let available = self.fill_buf()?;
+            let length = available.len().min(buf.len());
+            buf[..length].copy_from_slice(&available[..length]);
+            self.consume(length);
+            Ok(length)
+        }
+    }
+
+    impl BufRead for StreamingReader {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            if self.position == self.current.len() && !self.lines.is_empty() {
+                self.runs_before_line
+                    .push(self.records.lock().unwrap().len());
+                self.current = self.lines.remove(0).into_bytes();
+                self.position = 0;
+            }
+            Ok(&self.current[self.position..])
+        }
+
+        fn consume(&mut self, amount: usize) {
+            self.position += amount;
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_stdin_ndjson() {
+        let raw_arguments = ["exe_name".to_string(), "--stdin-ndjson".to_string()];
+        let (test_context, records) = init_nested_recording_context();
+        let mut input = StreamingReader {
+            lines: vec![
+                "{\"name\": \"other_scenario\", \"input\": \"other_input\"}\n".to_string(),
+                "\n".to_string(),
+                "{\"name\": \"outer_group.inner_group.inner_scenario\", \"input\": {\"count\": 1}}\n"
+                    .to_string(),
+            ],
+            current: Vec::new(),
+            position: 0,
+            records: Arc::clone(&records),
+            runs_before_line: Vec::new(),
+        };
+
+        let (result, _, _) =
+            run_cli_app_captured_with_input(&raw_arguments, &test_context, &mut input);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("other_scenario".to_string(), "other_input".to_string()),
+                ("inner_scenario".to_string(), "{\"count\":1}".to_string()),
+            ]
+        );
+        // Each line is read after the scenario of the previous line was run.
+        assert_eq!(input.runs_before_line, vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_run_cli_app_stdin_ndjson_ignores_env() {
+        let raw_arguments = ["exe_name".to_string(), "--stdin-ndjson".to_string()];
+        let (test_context, records) = init_nested_recording_context();
+        let config = CliConfig {
+            env_var: scenario_env_var,
+            ..Default::default()
+        };
+        let mut input =
+            std::io::Cursor::new("{\"name\": \"other_scenario\", \"input\": \"line_input\"}\n");
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+
+        let result = run_cli_app_with_io(
+            &raw_arguments,
+            &test_context,
+            &config,
+            &mut input,
+            &mut out,
+            &mut err,
+        );
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("other_scenario".to_string(), "line_input".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_stdin_ndjson_malformed_line() {
+        let raw_arguments = ["exe_name".to_string(), "--stdin-ndjson".to_string()];
+        let (test_context, records) = init_nested_recording_context();
+        let mut input = std::io::Cursor::new(
+            "{\"name\": \"other_scenario\", \"input\": \"first\"}\n\
+             {\"name\": \"other_scenario\", \"input\": \n\
+             {\"name\": \"other_scenario\", \"input\": \"third\"}\n",
+        );
+
+        let (result, _, _) =
+            run_cli_app_captured_with_input(&raw_arguments, &test_context, &mut input);
+        assert!(result.is_err_and(|e| matches!(e, CliError::Usage(message)
+            if message.starts_with("Invalid NDJSON line 2: "))));
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("other_scenario".to_string(), "first".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_stdin_ndjson_invalid_entries() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--stdin-ndjson".to_string(),
+            "--max-input-bytes".to_string(),
+            "4".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+        let long_line = format!(
+            "{{\"name\": \"other_scenario\", \"input\": \"{}\"}}\n",
+            "x".repeat(5000)
+        );
+        for (line, expected) in [
+            (
+                "{\"input\": \"ok\"}\n",
+                "Missing scenario name on NDJSON line 1",
+            ),
+            (
+                "{\"name\": \"other_scenario\"}\n",
+                "Missing input on NDJSON line 1",
+            ),
+            (
+                "{\"name\": \"missing\", \"input\": \"ok\"}\n",
+                "Scenario missing from NDJSON line 1 not found",
+            ),
+            (
+                "{\"name\": \"other_scenario\", \"input\": \"large\"}\n",
+                "Test scenario input of 5 bytes exceeds limit of 4 bytes",
+            ),
+            (&long_line, "NDJSON line 1 exceeds limit of 4100 bytes"),
+        ] {
+            let mut input = std::io::Cursor::new(line);
+
+            let (result, _, _) =
+                run_cli_app_captured_with_input(&raw_arguments, &test_context, &mut input);
+            assert!(
+                result
+                    .as_ref()
+                    .is_err_and(|e| *e == CliError::Usage(expected.to_string())),
+                "{result:?}"
+            );
+        }
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_stdin_ndjson_conflicts() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--stdin-ndjson".to_string(),
+            "--list-scenarios".to_string(),
+        ];
+        let (test_context, _) = init_nested_recording_context();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Conflicting arguments: --list-scenarios and --stdin-ndjson".to_string()
+            )));
+    }
+
+    /// Scenario stub with input schema requiring integer `count` field.
+    struct SchemaScenarioStub {
+        name: String,
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl Scenario for SchemaScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn input_schema(&self) -> Option<&str> {
+            Some(
+                r#"{"type": "object", "required": ["count"], "properties": {"count": {"type": "integer"}}}"#,
+            )
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_validate_inputs_manifest() {
+        let path = write_temp_file(
+            "test_run_cli_app_validate_inputs_manifest.json",
+            r#"{ "first": {"count": 1}, "second": {"count": "many"}, "third": {"count": 3} }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--manifest".to_string(),
+            path.to_string_lossy().to_string(),
+            "--validate-inputs".to_string(),
+        ];
+        let runs = Arc::new(AtomicUsize::new(0));
+        let scenarios: Vec<Box<dyn Scenario>> = ["first", "second", "third"]
+            .into_iter()
+            .map(|name| {
+                Box::new(SchemaScenarioStub {
+                    name: name.to_string(),
+                    runs: runs.clone(),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let root_group = ScenarioGroupImpl::new("root", scenarios, vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, _, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        let expected_message = "1 of 3 inputs are invalid: second".to_string();
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["second".to_string()],
+                expected_message
+            ))));
+        assert_eq!(
+            err,
+            "Input of scenario second is invalid: Invalid input: field 'count' expected integer, got string\n"
+        );
+        // Scenarios are not run.
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_run_cli_app_validate_inputs_valid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            r#"{"count": 1}"#.to_string(),
+            "--validate-inputs".to_string(),
+        ];
+        let runs = Arc::new(AtomicUsize::new(0));
+        let scenario = SchemaScenarioStub {
+            name: "first".to_string(),
+            runs: runs.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let test_context = TestContext::new(Box::new(root_group));
+
+        let (result, _, err) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(err, "");
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_run_cli_app_manifest_missing_scenario() {
+        let path = write_temp_file(
+            "test_run_cli_app_manifest_missing_scenario.json",
+            r#"{ "first": "ok", "missing": "ok" }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--manifest".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Scenarios from manifest not found: missing".to_string())
+        ));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generate_run_id_unique() {
+        assert_ne!(generate_run_id(), generate_run_id());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_run_id() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--run-id".to_string(),
+            "external_id".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert_eq!(cli_arguments.run_id, Some("external_id".to_string()));
+
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--run-id".to_string(),
+            "".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Invalid run-id parameter: "));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_run_cli_app_run_id_in_events() {
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_run_id_in_events.txt",
+            "first\tok\nsecond\terror\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+            "--run-id".to_string(),
+            "external_id".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::FmtSubscriber::builder()
+            .with_writer(move || writer.clone())
+            .json()
+            .finish();
+
+        let result = tracing::subscriber::with_default(subscriber, || {
+            run_cli_app(&raw_arguments, &test_context)
+        });
+        std::fs::remove_file(&playlist_path).unwrap();
+
+        assert!(result.is_err());
+        let logs = buffer.contents();
+        let lines: Vec<&str> = logs.lines().collect();
+        assert!(!lines.is_empty());
+        for line in lines {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["span"]["run_id"], "external_id", "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_error_annotation() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--error-annotation".to_string(),
+            "github".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert_eq!(cli_arguments.error_annotation, ErrorAnnotation::Github);
+
+        let cli_arguments = parse_cli_arguments(&["exe_name".to_string()]).unwrap();
+        assert_eq!(cli_arguments.error_annotation, ErrorAnnotation::Plain);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_combined_short_flags() {
+        for arg in ["-lh", "-hl"] {
+            let raw_arguments = ["exe_name".to_string(), arg.to_string()];
+            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+
+            assert!(cli_arguments.list_scenarios);
+            assert!(cli_arguments.help);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_combined_short_flags_unknown() {
+        let raw_arguments = ["exe_name".to_string(), "-lx".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Unknown flag -x in combined flags: -lx"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_combined_short_flags_with_value() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "-ln".to_string(),
+            "scenario".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Flag -n takes a value and cannot be combined: -ln"));
+    }
+
+    #[test]
+    fn test_run_cli_app_combined_short_flags_usage_error() {
+        let raw_arguments = ["exe_name".to_string(), "-lx".to_string()];
+        let (test_context, _) = init_recording_context(&["first"]);
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(matches!(result, Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_json_errors() {
+        let raw_arguments = ["exe_name".to_string(), "--json-errors".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.json_errors);
+    }
+
+    #[test]
+    fn test_run_cli_app_json_errors_usage() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--json-errors".to_string(),
+            "--invalid-arg".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let error = run_cli_app(&raw_arguments, &test_context).unwrap_err();
+        assert_eq!(
+            error.to_json(),
+            json!({
+                "kind": "usage",
+                "message": "Unknown argument provided: --invalid-arg",
+                "scenario": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_exit_json_errors_detection() {
+        let (test_context, _) = init_recording_context(&["first"]);
+        let response_path = write_temp_file(
+            "test_run_cli_app_exit_json_errors_detection.txt",
+            "--json-errors",
+        );
+        let run = |arguments: &[&str]| {
+            let raw_arguments: Vec<String> = std::iter::once("exe_name")
+                .chain(arguments.iter().copied())
+                .map(str::to_string)
+                .collect();
+            run_cli_app_exit_captured(&raw_arguments, &test_context, |_| None).1
+        };
+        let response_arg = format!("@{}", response_path.display());
+
+        let abbreviated = run(&["--json-err", "--invalid-arg"]);
+        let response_file = run(&[&response_arg, "--invalid-arg"]);
+        let input_value = run(&["--input", "--json-errors", "--invalid-arg"]);
+        std::fs::remove_file(&response_path).unwrap();
+
+        let json_error = "{\"kind\":\"usage\",\"message\":\"Unknown argument provided: --invalid-arg\",\"scenario\":null}\n";
+        assert_eq!(abbreviated, json_error);
+        assert_eq!(response_file, json_error);
+        assert_eq!(input_value, "Unknown argument provided: --invalid-arg\n");
+    }
+
+    #[test]
+    fn test_run_cli_app_json_errors_scenario() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--json-errors".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let error = run_cli_app(&raw_arguments, &test_context).unwrap_err();
+        assert_eq!(
+            error.to_json(),
+            json!({"kind": "scenario", "message": "Requested error", "scenario": "first"})
+        );
+    }
+
+    /// Lock held by tests changing or reading process working directory, which is shared by tests.
+    fn lock_current_dir() -> MutexGuard<'static, ()> {
+        static CURRENT_DIR_LOCK: Mutex<()> = Mutex::new(());
+        CURRENT_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Scenario stub recording working directory of its run.
+    struct WorkdirScenarioStub {
+        workdir: Arc<Mutex<Option<PathBuf>>>,
+    }
+
+    impl Scenario for WorkdirScenarioStub {
+        fn name(&self) -> &str {
+            "workdir_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            *self.workdir.lock().unwrap() = Some(std::env::current_dir().unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_workdir() {
+        let _lock = lock_current_dir();
+        let workdir_path = std::env::temp_dir().canonicalize().unwrap();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "workdir_scenario".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--workdir".to_string(),
+            workdir_path.to_string_lossy().to_string(),
+        ];
+        let workdir = Arc::new(Mutex::new(None));
+        let scenario = Box::new(WorkdirScenarioStub {
+            workdir: Arc::clone(&workdir),
+        });
+        let root_group = Box::new(ScenarioGroupImpl::new("root", vec![scenario], vec![]));
+        let test_context = TestContext::new(root_group);
+        let previous = std::env::current_dir().unwrap();
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(workdir.lock().unwrap().clone(), Some(workdir_path));
+        assert_eq!(std::env::current_dir().unwrap(), previous);
+    }
+
+    #[test]
+    fn test_run_cli_app_workdir_missing() {
+        let _lock = lock_current_dir();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--workdir".to_string(),
+            "/nonexistent/workdir".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Working directory /nonexistent/workdir does not exist".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_workdir_multi_scenario() {
+        let _lock = lock_current_dir();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+            "--workdir".to_string(),
+            std::env::temp_dir().to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Working directory is only supported for single-scenario runs".to_string()
+            )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_list_tree_counts() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--list-tree".to_string(),
+            "--counts".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.list_tree);
+        assert!(cli_arguments.counts);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_counts_without_list_tree() {
+        let raw_arguments = ["exe_name".to_string(), "--counts".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--counts requires --list-tree"));
+    }
+
+    #[test]
+    fn test_run_cli_app_list_tree_counts() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--list-tree".to_string(),
+            "--counts".to_string(),
+        ];
+        let (test_context, records) = init_nested_recording_context();
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().is_empty());
+        assert_eq!(
+            out.lines().collect::<Vec<_>>(),
+            vec![
+                "outer_group/ (2)",
+                "  inner_group/ (1)",
+                "    inner_scenario",
+                "  outer_scenario",
+                "ambiguous/ (0)",
+                "other_scenario",
+                "ambiguous",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let values = json!({"name": "example", "count": 3})
+            .as_object()
+            .unwrap()
+            .clone();
+        let rendered = render_template(r#"{"name": "{{ name }}", "count": {{count}}}"#, &values);
+        assert_eq!(rendered.unwrap(), r#"{"name": "example", "count": 3}"#);
+    }
+
+    #[test]
+    fn test_render_template_missing_placeholder() {
+        let values = json!({"name": "example"}).as_object().unwrap().clone();
+        let result = render_template("{{ name }} {{ missing }}", &values);
+        assert!(result.is_err_and(|e| e == "Missing value for template placeholder: missing"));
+    }
+
+    #[test]
+    fn test_run_cli_app_input_template() {
+        let template_path = write_temp_file(
+            "test_run_cli_app_input_template.txt",
+            "{{ first }}-{{second}}",
+        );
+        let values_path = write_temp_file(
+            "test_run_cli_app_input_template.json",
+            r#"{"first": "value", "second": 2}"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input-template".to_string(),
+            template_path.to_string_lossy().to_string(),
+            "--values".to_string(),
+            values_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&template_path).unwrap();
+        std::fs::remove_file(&values_path).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("first".to_string(), "value-2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_input_template_missing_value() {
+        let template_path = write_temp_file(
+            "test_run_cli_app_input_template_missing_value.txt",
+            "{{ first }}-{{ missing }}",
+        );
+        let values_path = write_temp_file(
+            "test_run_cli_app_input_template_missing_value.json",
+            r#"{"first": "value"}"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input-template".to_string(),
+            template_path.to_string_lossy().to_string(),
+            "--values".to_string(),
+            values_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&template_path).unwrap();
+        std::fs::remove_file(&values_path).unwrap();
+
+        assert!(result
+            .is_err_and(|e| e
+                == CliError::Usage("Missing value for template placeholder: missing".to_string())));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_values_without_template() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--values".to_string(),
+            "values.json".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--values requires --input-template"));
+    }
+
+    #[test]
+    fn test_run_cli_app_playlist_missing_input() {
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_playlist_missing_input.txt",
+            "first\tok\nsecond\nfirst\tok\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&playlist_path).unwrap();
+
+        // Playlist is validated before any scenario is run.
+        assert!(result
+            .is_err_and(|e| e
+                == CliError::Usage("Test scenario input must be provided for second".to_string())));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_aborted_report() {
+        let report_path = std::env::temp_dir().join(format!(
+            "{}_test_run_cli_app_aborted_report.tap",
+            std::process::id()
+        ));
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--stdin-ndjson".to_string(),
+            "--report-tap".to_string(),
+            report_path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+        let mut input = std::io::Cursor::new(
+            "{\"name\": \"first\", \"input\": \"ok\"}\n\
+             {\"name\": \"second\"}\n\
+             {\"name\": \"first\", \"input\": \"ok\"}\n",
+        );
+
+        let (result, _, _) =
+            run_cli_app_captured_with_input(&raw_arguments, &test_context, &mut input);
+        let report = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+
+        // Lines are read while running, so entries before the invalid one are run and reported.
+        assert!(result
+            .is_err_and(|e| e == CliError::Usage("Missing input on NDJSON line 2".to_string())));
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("first".to_string(), "ok".to_string())]
+        );
+        assert_eq!(
+            report,
+            "TAP version 13\n1..1\nok 1 - first\nBail out! Missing input on NDJSON line 2\n"
+        );
+    }
+
+    #[test]
+    fn test_run_until_duration() {
+        let budget = Duration::from_millis(50);
+        let start = Instant::now();
+        let mut calls = 0;
+        let (iterations, failures) = run_until_duration(budget, || {
+            calls += 1;
+            std::thread::sleep(Duration::from_millis(1));
+            match calls % 3 {
+                0 => Outcome::Failed("Requested error".to_string()),
+                _ => Outcome::Passed,
             }
+        });
+        let elapsed = start.elapsed();
+
+        assert!(iterations > 1);
+        assert_eq!(iterations, calls);
+        assert_eq!(failures, iterations / 3);
+        assert!(elapsed >= budget);
+        assert!(elapsed < budget + Duration::from_millis(500), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_run_cli_app_until_duration() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--until-duration".to_string(),
+            "0.05".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let start = Instant::now();
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok());
+        assert!(records.lock().unwrap().len() > 1);
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_millis(550), "{elapsed:?}");
+    }
+
+    #[test]
+    fn test_run_cli_app_until_duration_failed() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--until-duration".to_string(),
+            "0.01".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        let iterations = records.lock().unwrap().len();
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Scenario(_))
+            && e.to_string() == format!("{iterations} of {iterations} iterations failed")));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_until_duration_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--until-duration".to_string(),
+            "0".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result
+            .is_err_and(|e| e == "--until-duration must be a positive number of seconds, got '0'"));
+    }
+
+    #[test]
+    fn test_stream_observer_parallel() {
+        let (test_context, _) = init_recording_context(&["first", "second", "third"]);
+        let buffer = SharedBuffer::default();
+        let observer = StreamObserver::new(Box::new(NoopObserver), Box::new(buffer.clone()));
+        let runs: Vec<(String, String)> = [("first", "ok"), ("second", "error"), ("third", "ok")]
+            .iter()
+            .map(|(name, input)| (name.to_string(), input.to_string()))
+            .collect();
+        let options = RunOptions {
+            parallelism: Some(3),
+            ..Default::default()
+        };
+
+        test_context.run_many(&runs, &options, &observer);
+
+        let content = buffer.contents();
+        let mut lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        lines.sort_by_key(|line| line["name"].as_str().unwrap().to_string());
+        assert_eq!(lines.len(), 3);
+        for (line, (name, status)) in lines.iter().zip([
+            ("first", "passed"),
+            ("second", "failed"),
+            ("third", "passed"),
+        ]) {
+            assert_eq!(line["name"], name);
+            assert_eq!(line["status"], status);
+            assert!(line["duration_us"].is_u64());
+        }
+    }
+
+    #[test]
+    fn test_stream_observer_poisoned() {
+        let (test_context, _) = init_recording_context(&["first"]);
+        let buffer = SharedBuffer::default();
+        let observer = StreamObserver::new(Box::new(NoopObserver), Box::new(buffer.clone()));
+        std::thread::scope(|scope| {
+            let result = scope
+                .spawn(|| {
+                    let _writer = observer.writer.lock().unwrap();
+                    panic!("poison writer");
+                })
+                .join();
+            assert!(result.is_err());
+        });
+        assert!(observer.writer.is_poisoned());
+
+        let runs = [("first".to_string(), "ok".to_string())];
+        test_context.run_many(&runs, &RunOptions::default(), &observer);
+
+        let line: Value = serde_json::from_str(buffer.contents().trim_end()).unwrap();
+        assert_eq!(line["name"], "first");
+        assert_eq!(line["status"], "passed");
+    }
+
+    #[test]
+    fn test_run_cli_app_stream_results() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--stream-results".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2, "{out}");
+        for (line, name) in lines.iter().zip(["first", "second"]) {
+            assert_eq!(line["name"], name);
+            assert_eq!(line["status"], "passed");
+        }
+    }
+
+    #[test]
+    fn test_run_cli_app_stream_results_matrix() {
+        let matrix_path = write_temp_file(
+            "test_run_cli_app_stream_results_matrix.json",
+            r#"{ "param": ["a", "b"] }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--matrix".to_string(),
+            matrix_path.to_string_lossy().to_string(),
+            "--seed".to_string(),
+            "1".to_string(),
+            "--stream-results".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let (result, out, _) = run_cli_app_captured(&raw_arguments, &test_context);
+        std::fs::remove_file(&matrix_path).unwrap();
+
+        // Streamed results are labeled with the combination of the run.
+        assert!(result.is_ok());
+        let streamed: Vec<String> = out
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["name"].to_string())
+            .collect();
+        let labels: Vec<String> = records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, input)| {
+                let combination: Value = serde_json::from_str(input).unwrap();
+                format!(
+                    "\"first[param={}]\"",
+                    combination["param"].as_str().unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(streamed, labels);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_stream_results() {
+        let raw_arguments = ["exe_name".to_string(), "--stream-results".to_string()];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.stream_results);
+    }
+
+    #[test]
+    fn test_run_cli_builder() {
+        let cli_arguments = CliArguments::builder()
+            .name("first")
+            .input("builder_input")
+            .repeat(2)
+            .build();
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli(cli_arguments, &test_context);
+        assert!(result.is_ok());
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("first".to_string(), "builder_input".to_string()),
+                ("first".to_string(), "builder_input".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_builder_error() {
+        let cli_arguments = CliArguments::builder()
+            .name("first")
+            .name("second")
+            .input("error")
+            .build();
+        let (test_context, records) = init_recording_context(&["first", "second"]);
+
+        let result = run_cli(cli_arguments, &test_context);
+        assert!(result.is_err_and(|e| matches!(&e, CliError::Scenario(_))));
+        assert_eq!(records.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_run_cli_builder_invalid() {
+        let (test_context, records) = init_recording_context(&["first"]);
+        let cases = [
+            (
+                CliArguments::builder().name("first").input("ok").repeat(0),
+                "--repeat must be at least 1, got '0'",
+            ),
+            (
+                CliArguments::builder()
+                    .name("first")
+                    .input("ok")
+                    .concurrency(0),
+                "--concurrency must be at least 1, got '0'",
+            ),
+            (
+                CliArguments::builder()
+                    .name("first")
+                    .input("ok")
+                    .timeout(Duration::ZERO),
+                "--timeout must be a positive number of seconds, got '0'",
+            ),
+        ];
+
+        for (builder, expected) in cases {
+            let result = run_cli(builder.build(), &test_context);
+            assert!(
+                result
+                    .as_ref()
+                    .is_err_and(|e| *e == CliError::Usage(expected.to_string())),
+                "{result:?}"
+            );
+        }
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_builder_conflict() {
+        let cli_arguments = CliArguments::builder()
+            .list_scenarios()
+            .name("first")
+            .input("ok")
+            .build();
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let result = run_cli_with_io(
+            cli_arguments,
+            &test_context,
+            &CliConfig::default(),
+            &mut std::io::empty(),
+            &mut out,
+            &mut err,
+            &mut None,
+        );
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage("Conflicting arguments: --list-scenarios and --name".to_string())));
+        assert!(out.is_empty());
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_conflict_list_and_name() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--list-scenarios".to_string(),
+            "--name".to_string(),
+            "foo".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Conflicting arguments: --list-scenarios and --name"));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_conflict_listing_modes() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--count".to_string(),
+            "--list-tree".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "Conflicting arguments: --list-tree and --count"));
+    }
+
+    #[test]
+    fn test_run_cli_app_conflict_is_usage_error() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--list-scenarios".to_string(),
+            "--playlist".to_string(),
+            "playlist.txt".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Usage(
+                "Conflicting arguments: --list-scenarios and --playlist".to_string()
+            )));
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_help_precedence() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--help".to_string(),
+            "--list-scenarios".to_string(),
+            "--name".to_string(),
+            "foo".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.help);
+    }
+
+    /// Test context with scenarios of different priorities in `group`, recording their runs.
+    fn init_priority_context() -> (TestContext, Records) {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let priority_stub = |name: &str, priority: i32| -> Box<dyn Scenario> {
+            Box::new(PriorityScenarioStub {
+                name: name.to_string(),
+                priority,
+                records: records.clone(),
+            })
+        };
+        let group = ScenarioGroupImpl::new(
+            "group",
+            vec![
+                priority_stub("low", -1),
+                priority_stub("default", 0),
+                priority_stub("high", 10),
+                priority_stub("other_default", 0),
+            ],
+            vec![],
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        (TestContext::new(Box::new(root_group)), records)
+    }
+
+    #[test]
+    fn test_run_cli_app_priority_order() {
+        let (test_context, records) = init_priority_context();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "group".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+        ];
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
+        let names: Vec<String> = records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        assert_eq!(names, vec!["high", "default", "other_default", "low"]);
+    }
+
+    #[test]
+    fn test_run_cli_app_priority_explicit_order() {
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_priority_explicit_order.txt",
+            "group.low\t\ngroup.high\t\n",
+        );
+        let names_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "group.low".to_string(),
+            "--name".to_string(),
+            "group.high".to_string(),
+            "--input".to_string(),
+            "".to_string(),
+        ];
+        let playlist_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+        ];
+
+        for raw_arguments in [&names_arguments[..], &playlist_arguments[..]] {
+            let (test_context, records) = init_priority_context();
+            let result = run_cli_app(raw_arguments, &test_context);
+            assert!(result.is_ok());
+            let names: Vec<String> = records
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect();
+            assert_eq!(names, vec!["low", "high"]);
         }
+        std::fs::remove_file(&playlist_path).unwrap();
+    }
+
+    #[test]
+    fn test_run_cli_app_rerun_failed() {
+        let path = write_temp_file(
+            "test_run_cli_app_rerun_failed.json",
+            r#"{
+                "stats": {"total": 3, "passed": 1, "failed": 1, "skipped": 1},
+                "results": [
+                    {"name": "first", "status": "passed", "error": null},
+                    {"name": "second", "status": "failed", "error": "Requested error"},
+                    {"name": "third", "status": "skipped", "error": null, "not_run": false}
+                ]
+            }"#,
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--rerun-failed".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second", "third"]);
+
+        // Name from environment does not conflict with names from report.
+        let result = run_cli_app_with_env(&raw_arguments, &test_context, scenario_env_var);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![("second".to_string(), "ok".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_rerun_failed_not_run_and_inputs() {
+        let playlist_path = write_temp_file(
+            "test_run_cli_app_rerun_failed_not_run_and_inputs.txt",
+            "first\terror\nsecond\tsecond input\nthird\tthird input\n",
+        );
+        let output_dir = std::env::temp_dir().join(format!(
+            "test_run_cli_app_rerun_failed_not_run_and_inputs_{}",
+            std::process::id()
+        ));
+        let (test_context, records) = init_recording_context(&["first", "second", "third"]);
+
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            playlist_path.to_string_lossy().to_string(),
+            "--max-failures".to_string(),
+            "1".to_string(),
+            "--output-dir".to_string(),
+            output_dir.to_string_lossy().to_string(),
+        ];
+        let _ = run_cli_app(&raw_arguments, &test_context);
+        let summary_path = output_dir.join("summary.json");
+        let summary: Value =
+            serde_json::from_str(&std::fs::read_to_string(&summary_path).unwrap()).unwrap();
+        records.lock().unwrap().clear();
+
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--rerun-failed".to_string(),
+            summary_path.to_string_lossy().to_string(),
+        ];
+        let _ = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&playlist_path).unwrap();
+        std::fs::remove_dir_all(&output_dir).unwrap();
+
+        assert_eq!(summary["results"][1]["status"], "skipped");
+        assert_eq!(summary["results"][1]["not_run"], true);
+        assert_eq!(summary["results"][1]["input"], "second input");
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("first".to_string(), "error".to_string()),
+                ("second".to_string(), "second input".to_string()),
+                ("third".to_string(), "third input".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_rerun_failed_matrix_label() {
+        let path = write_temp_file(
+            "test_run_cli_app_rerun_failed_matrix_label.ndjson",
+            concat!(
+                r#"{"name": "first[param=a]", "outcome": {"status": "failed", "message": "Requested error"}, "input": "{\"param\":\"a\"}"}"#,
+                "\n",
+                r#"{"name": "first[param=b]", "outcome": {"status": "timed_out", "message": null}}"#,
+                "\n",
+                r#"{"name": "first[param=c]", "outcome": {"status": "passed", "message": null}, "input": "{\"param\":\"c\"}"}"#,
+                "\n",
+            ),
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--rerun-failed".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                ("first".to_string(), r#"{"param":"a"}"#.to_string()),
+                ("first".to_string(), "ok".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_cli_app_rerun_failed_missing_scenario() {
+        let path = write_temp_file(
+            "test_run_cli_app_rerun_failed_missing_scenario.ndjson",
+            concat!(
+                r#"{"name": "first", "outcome": {"status": "failed", "message": "Requested error"}}"#,
+                "\n",
+                r#"{"name": "removed", "outcome": {"status": "panicked", "message": "Boom"}}"#,
+                "\n",
+            ),
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--rerun-failed".to_string(),
+            path.to_string_lossy().to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Scenarios from report not found: removed".to_string())
+        ));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_rerun_failed_invalid_report() {
+        let path = write_temp_file(
+            "test_run_cli_app_rerun_failed_invalid_report.tap",
+            "TAP version 13\n1..1\nnot ok 1 - first\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--rerun-failed".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err_and(|e| matches!(
+            e,
+            CliError::Usage(ref message) if message.starts_with("Invalid report line: ")
+        )));
+        assert!(records.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_cli_app_global_timeout() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--name".to_string(),
+            "third".to_string(),
+            "--name".to_string(),
+            "fourth".to_string(),
+            "--input".to_string(),
+            "sleep".to_string(),
+            "--global-timeout".to_string(),
+            "0.1".to_string(),
+        ];
+        let (test_context, records) =
+            init_recording_context(&["first", "second", "third", "fourth"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+
+        // First scenario sleeps past the timeout, remaining ones are not started.
+        let expected_names = vec![
+            "second".to_string(),
+            "third".to_string(),
+            "fourth".to_string(),
+        ];
+        assert!(result.is_err_and(|e| e
+            == CliError::GlobalTimeout(ScenarioError::new(
+                expected_names,
+                "Global timeout exceeded, 3 of 4 scenarios not run"
+            ))));
+        assert_eq!(records.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_run_cli_app_global_timeout_after_failure() {
+        let path = write_temp_file(
+            "test_run_cli_app_global_timeout_after_failure.txt",
+            "first\terror\nsecond\tsleep\nthird\tok\n",
+        );
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--playlist".to_string(),
+            path.to_string_lossy().to_string(),
+            "--global-timeout".to_string(),
+            "0.1".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first", "second", "third"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        std::fs::remove_file(&path).unwrap();
+
+        // Failure is not masked by the global timeout.
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["first".to_string()],
+                "1 of 3 scenarios failed: first"
+            ))));
+        assert_eq!(records.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_cli_arguments_global_timeout_invalid() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--global-timeout".to_string(),
+            "0".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result
+            .is_err_and(|e| e == "--global-timeout must be a positive number of seconds, got '0'"));
     }
 
     #[test]
-    fn test_parse_cli_arguments_empty() {
-        let raw_arguments = vec![];
+    fn test_parse_cli_arguments_trace_args() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--trace-args".to_string(),
+            "-lh".to_string(),
+            "--log-level".to_string(),
+            "info".to_string(),
+        ];
         let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
 
-        // Default values are expected.
-        assert!(cli_arguments.scenario_arguments.name.is_none());
-        assert!(cli_arguments.scenario_arguments.input.is_none());
-        assert!(!cli_arguments.list_scenarios);
-        assert!(!cli_arguments.help);
+        assert!(cli_arguments.trace_args);
+        assert_eq!(cli_arguments.tracing_config.level, Level::DEBUG);
+        assert_eq!(
+            cli_arguments.parsed_arguments,
+            vec![
+                ("--trace-args".to_string(), vec![]),
+                ("-lh".to_string(), vec![]),
+                ("--log-level".to_string(), vec!["info".to_string()]),
+            ]
+        );
     }
 
+    #[cfg(feature = "tracing")]
     #[test]
-    fn test_parse_cli_arguments_executable_name_only() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = vec![exe_name];
-        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+    fn test_run_cli_app_trace_args_events() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--trace-args".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--max-fail".to_string(),
+            "2".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::FmtSubscriber::builder()
+            .with_writer(move || writer.clone())
+            .with_max_level(Level::DEBUG)
+            .json()
+            .finish();
 
-        // Default values are expected.
-        assert!(cli_arguments.scenario_arguments.name.is_none());
-        assert!(cli_arguments.scenario_arguments.input.is_none());
-        assert!(!cli_arguments.list_scenarios);
-        assert!(!cli_arguments.help);
+        let result = tracing::subscriber::with_default(subscriber, || {
+            run_cli_app(&raw_arguments, &test_context)
+        });
+
+        assert!(result.is_ok());
+        let logs = buffer.contents();
+        let messages: Vec<String> = logs
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .filter(|event| event["level"] == "DEBUG")
+            .map(|event| event["fields"]["message"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(messages[0], "Parsed argument --trace-args");
+        assert_eq!(messages[1], "Parsed argument --name: first");
+        assert_eq!(messages[2], "Parsed argument --input: ok");
+        // Abbreviated arguments are traced by their full name.
+        assert_eq!(messages[3], "Parsed argument --max-failures: 2");
+        assert!(messages[4].starts_with("Parsed configuration: {"));
+        assert!(messages[4].contains(r#""max_failures":2"#));
     }
 
     #[test]
-    fn test_parse_cli_arguments_name_ok() {
-        let exe_name = "exe_name".to_string();
-        for arg in ["-n", "--name"] {
-            let example_name = "example_name".to_string();
-            let raw_arguments = vec![exe_name.clone(), arg.to_string(), example_name.clone()];
-            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+    fn test_run_cli_app_expect_fail_failed() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--expect".to_string(),
+            "fail".to_string(),
+            "--expect-error-contains".to_string(),
+            "Requested".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
 
-            assert!(cli_arguments
-                .scenario_arguments
-                .name
-                .is_some_and(|n| n == example_name));
-            assert!(cli_arguments.scenario_arguments.input.is_none());
-            assert!(!cli_arguments.list_scenarios);
-            assert!(!cli_arguments.help);
-        }
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_ok());
     }
 
     #[test]
-    fn test_parse_cli_arguments_name_missing() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = [exe_name, "--name".to_string()];
-        let result = parse_cli_arguments(&raw_arguments);
-        assert!(result.is_err_and(|e| e == "Failed to read name parameter"))
+    fn test_run_cli_app_expect_fail_passed() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--expect".to_string(),
+            "fail".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first"]);
+
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["first".to_string()],
+                "Scenario first was expected to fail, but passed"
+            ))));
     }
 
     #[test]
-    fn test_parse_cli_arguments_input_ok() {
-        for arg in ["-i", "--input"] {
-            let exe_name = "exe_name".to_string();
-            let example_input = "example_input".to_string();
-            let raw_arguments = [exe_name.clone(), arg.to_string(), example_input.clone()];
-            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+    fn test_run_cli_app_expect_fail_wrong_error() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--expect".to_string(),
+            "fail".to_string(),
+            "--expect-error-contains".to_string(),
+            "timeout".to_string(),
+        ];
+        let (test_context, _) = init_recording_context(&["first", "second"]);
 
-            assert!(cli_arguments.scenario_arguments.name.is_none());
-            assert!(cli_arguments
-                .scenario_arguments
-                .input
-                .is_some_and(|n| n == example_input));
-            assert!(!cli_arguments.list_scenarios);
-            assert!(!cli_arguments.help);
-        }
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e
+            == CliError::Scenario(ScenarioError::new(
+                vec!["first".to_string(), "second".to_string()],
+                "2 of 2 scenarios did not fail as expected: first, second"
+            ))));
     }
 
     #[test]
-    fn test_parse_cli_arguments_input_missing() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = [exe_name, "--input".to_string()];
+    fn test_parse_cli_arguments_expect_error_contains_without_expect_fail() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--expect-error-contains".to_string(),
+            "error".to_string(),
+        ];
         let result = parse_cli_arguments(&raw_arguments);
-        assert!(result.is_err_and(|e| e == "Failed to read input parameter"))
+        assert!(result.is_err_and(|e| e == "--expect-error-contains requires --expect fail"));
     }
 
     #[test]
-    fn test_parse_cli_arguments_list_scenarios() {
-        let exe_name = "exe_name".to_string();
-        for arg in ["-l", "--list-scenarios"] {
-            let raw_arguments = [exe_name.clone(), arg.to_string()];
-            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+    fn test_parse_cli_arguments_event_log() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--event-log".to_string(),
+            "events.json".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
 
-            assert!(cli_arguments.scenario_arguments.name.is_none());
-            assert!(cli_arguments.scenario_arguments.input.is_none());
-            assert!(cli_arguments.list_scenarios);
-            assert!(!cli_arguments.help);
-        }
+        assert_eq!(cli_arguments.event_log, Some("events.json".to_string()));
+        assert_eq!(
+            config_json(&cli_arguments)["log"]["event_log"],
+            "events.json"
+        );
     }
 
     #[test]
-    fn test_parse_cli_arguments_help() {
-        let exe_name = "exe_name".to_string();
-        for arg in ["-h", "--help"] {
-            let raw_arguments = [exe_name.clone(), arg.to_string()];
-            let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
-
-            assert!(cli_arguments.scenario_arguments.name.is_none());
-            assert!(cli_arguments.scenario_arguments.input.is_none());
-            assert!(!cli_arguments.list_scenarios);
-            assert!(cli_arguments.help);
-        }
-    }
+    fn test_run_cli_app_retry_if_contains_matching() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--retries".to_string(),
+            "2".to_string(),
+            "--retry-if-contains".to_string(),
+            "Requested".to_string(),
+        ];
+        let (test_context, records) = init_recording_context(&["first"]);
 
-    #[test]
-    fn test_parse_cli_arguments_unknown_argument() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = [exe_name, "--invalid-arg".to_string()];
-        let result = parse_cli_arguments(&raw_arguments);
-        assert!(result.is_err_and(|e| e == "Unknown argument provided: --invalid-arg"));
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err_and(|e| e.to_string() == "Requested error"));
+        // Initial run and two retries.
+        assert_eq!(records.lock().unwrap().len(), 3);
     }
 
     #[test]
-    fn test_parse_cli_arguments_all() {
-        let exe_name = "exe_name".to_string();
-        let example_name = "example_name".to_string();
-        let example_input = "example_input".to_string();
+    fn test_run_cli_app_retry_if_contains_not_matching() {
         let raw_arguments = [
-            exe_name,
-            "--help".to_string(),
-            "--list-scenarios".to_string(),
-            "--input".to_string(),
-            example_input.clone(),
+            "exe_name".to_string(),
             "--name".to_string(),
-            example_name.clone(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--retries".to_string(),
+            "2".to_string(),
+            "--retry-if-contains".to_string(),
+            "Connection reset".to_string(),
         ];
-        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        let (test_context, records) = init_recording_context(&["first", "second"]);
 
-        assert!(cli_arguments
-            .scenario_arguments
-            .name
-            .is_some_and(|n| n == example_name));
-        assert!(cli_arguments
-            .scenario_arguments
-            .input
-            .is_some_and(|n| n == example_input));
-        assert!(cli_arguments.list_scenarios);
-        assert!(cli_arguments.help);
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err());
+        // Each scenario is run once, its error is not retryable.
+        assert_eq!(records.lock().unwrap().len(), 2);
     }
 
     #[test]
-    fn test_run_cli_app_show_help() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = vec![exe_name, "--help".to_string()];
-        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
-
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_ok());
-        // It's not possible to check stderr without unstable feature.
+    fn test_parse_cli_arguments_retry_if_contains_without_retries() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--retry-if-contains".to_string(),
+            "error".to_string(),
+        ];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--retry-if-contains requires --retries"));
     }
 
     #[test]
-    fn test_run_cli_app_list_scenarios() {
-        let exe_name = "exe_name".to_string();
-        let raw_arguments = vec![exe_name, "--list-scenarios".to_string()];
-        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+    fn test_parse_cli_arguments_isolate() {
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--isolate".to_string(),
+            "--process-group".to_string(),
+        ];
+        let cli_arguments = parse_cli_arguments(&raw_arguments).unwrap();
+        assert!(cli_arguments.scenario_arguments.isolate);
+        assert!(cli_arguments.scenario_arguments.process_group);
+    }
 
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_ok());
-        // It's not possible to check stdout without unstable feature.
+    #[test]
+    fn test_parse_cli_arguments_process_group_without_isolate() {
+        let raw_arguments = ["exe_name".to_string(), "--process-group".to_string()];
+        let result = parse_cli_arguments(&raw_arguments);
+        assert!(result.is_err_and(|e| e == "--process-group requires --isolate"));
     }
 
     #[test]
-    fn test_run_cli_app_ok() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
+    fn test_run_cli_app_isolate_emit_output() {
         let raw_arguments = [
-            exe_name,
+            "exe_name".to_string(),
             "--name".to_string(),
-            scenario_name.to_string(),
+            "first".to_string(),
             "--input".to_string(),
             "ok".to_string(),
+            "--isolate".to_string(),
+            "--emit-output".to_string(),
         ];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+        let (test_context, records) = init_recording_context(&["first"]);
 
         let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_ok());
+        assert!(result.is_err_and(
+            |e| e == CliError::Usage("Emit output is not supported with isolation".to_string())
+        ));
+        assert!(records.lock().unwrap().is_empty());
     }
 
     #[test]
-    fn test_run_cli_app_error() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
+    fn test_run_cli_app_result_file() {
+        let path = write_temp_file("synth_398_results.ndjson", "");
         let raw_arguments = [
-            exe_name,
+            "exe_name".to_string(),
             "--name".to_string(),
-            scenario_name.to_string(),
+            "first".to_string(),
             "--input".to_string(),
             "error".to_string(),
+            "--result-file".to_string(),
+            path.display().to_string(),
         ];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+        let (test_context, _) = init_recording_context(&["first"]);
 
-        // It's expected that test will fail due to error from `ScenarioStub`, not from `run_cli_app`.
         let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_err_and(|e| e == "Requested error"));
+        assert!(result.is_err());
+        let line: Value =
+            serde_json::from_str(std::fs::read_to_string(&path).unwrap().trim()).unwrap();
+        assert_eq!(line["name"], "first");
+        assert_eq!(line["outcome"]["status"], "failed");
+        assert_eq!(line["input"], "error");
+
+        // Runner error is reported if scenario was not run.
+        std::fs::write(&path, "").unwrap();
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "unknown".to_string(),
+            "--input".to_string(),
+            "ok".to_string(),
+            "--result-file".to_string(),
+            path.display().to_string(),
+        ];
+        let result = run_cli_app(&raw_arguments, &test_context);
+        assert!(result.is_err());
+        let line: Value =
+            serde_json::from_str(std::fs::read_to_string(&path).unwrap().trim()).unwrap();
+        assert!(line["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("unknown")));
+        std::fs::remove_file(path).unwrap();
     }
 
     #[test]
-    fn test_run_cli_app_missing_input() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
-        let raw_arguments = [exe_name, "--name".to_string(), scenario_name.to_string()];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+    fn test_isolation_arguments() {
+        let tracing_config = TracingConfig {
+            nonblocking: true,
+            ..Default::default()
+        };
+        let arguments = isolation_arguments(&tracing_config, "run_1");
+        let mut cli_arguments = CliArguments::default();
+        let raw_arguments: Vec<String> = ["exe_name".to_string()]
+            .into_iter()
+            .chain(arguments)
+            .collect();
+        parse_cli_arguments_into(&raw_arguments, &mut cli_arguments).unwrap();
+        assert_eq!(cli_arguments.tracing_config, tracing_config);
+        assert_eq!(cli_arguments.run_id, Some("run_1".to_string()));
+    }
 
-        // It's expected that test will fail due to error from `ScenarioStub`, not from `run_cli_app`.
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_err_and(|e| e == "Test scenario input must be provided"));
+    /// Exit code mapping returning 75 if only timeouts occurred, to request retry of the run.
+    fn retry_on_timeout(stats: &RunStats) -> Option<ExitCode> {
+        (stats.failed == 0 && stats.timed_out > 0).then(|| ExitCode::from(75))
+    }
+
+    /// Run CLI application capturing stderr, returning debug representation of exit code.
+    fn run_cli_app_exit_captured(
+        raw_arguments: &[String],
+        test_context: &TestContext,
+        exit_code: impl Fn(&RunStats) -> Option<ExitCode>,
+    ) -> (String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run_cli_app_exit_with_io(
+            raw_arguments,
+            test_context,
+            &CliConfig::default(),
+            &mut std::io::empty(),
+            &mut out,
+            &mut err,
+            exit_code,
+        );
+        (format!("{code:?}"), String::from_utf8(err).unwrap())
     }
 
     #[test]
-    fn test_run_cli_app_missing_name() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
-        let raw_arguments = vec![exe_name];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+    fn test_run_cli_app_exit_custom_mapping() {
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+        let run = |input: &str, timeout: &str| {
+            let raw_arguments = [
+                "exe_name".to_string(),
+                "--name".to_string(),
+                "first".to_string(),
+                "--name".to_string(),
+                "second".to_string(),
+                "--input".to_string(),
+                input.to_string(),
+                "--hard-timeout-ms".to_string(),
+                timeout.to_string(),
+            ];
+            run_cli_app_exit_captured(&raw_arguments, &test_context, retry_on_timeout)
+        };
 
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_err_and(|e| e == "Test scenario name must be provided"));
+        let (code, err) = run("ok", "5000");
+        assert_eq!(code, format!("{:?}", ExitCode::SUCCESS));
+        assert_eq!(err, "");
+
+        let (code, err) = run("error", "5000");
+        assert_eq!(code, format!("{:?}", ExitCode::FAILURE));
+        assert!(err.contains("Requested error"), "{err}");
+
+        let (code, _) = run("sleep", "10");
+        assert_eq!(code, format!("{:?}", ExitCode::from(75)));
     }
 
     #[test]
-    fn test_run_cli_app_empty_name() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
-        let raw_arguments = vec![exe_name, "--name".to_string(), String::new()];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
+    fn test_run_cli_app_exit_usage_error_and_listing() {
+        let (test_context, _) = init_recording_context(&["first"]);
+        let mapping_called = AtomicBool::new(false);
+        let exit_code = |_: &RunStats| {
+            mapping_called.store(true, Ordering::SeqCst);
+            Some(ExitCode::from(75))
+        };
 
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_err_and(|e| e == "Test scenario name must not be empty"));
+        let raw_arguments = ["exe_name".to_string(), "--unknown".to_string()];
+        let (code, err) = run_cli_app_exit_captured(&raw_arguments, &test_context, exit_code);
+        assert_eq!(code, format!("{:?}", ExitCode::from(2)));
+        assert!(err.contains("--unknown"), "{err}");
+
+        let raw_arguments = ["exe_name".to_string(), "--list-scenarios".to_string()];
+        let (code, _) = run_cli_app_exit_captured(&raw_arguments, &test_context, exit_code);
+        assert_eq!(code, format!("{:?}", ExitCode::SUCCESS));
+        assert!(!mapping_called.load(Ordering::SeqCst));
     }
 
     #[test]
-    fn test_run_cli_app_invalid_name() {
-        let exe_name = "exe_name".to_string();
-        let scenario_name = "example_scenario";
+    fn test_run_cli_app_exit_expect_fail() {
+        let (test_context, _) = init_recording_context(&["first"]);
         let raw_arguments = [
-            exe_name,
+            "exe_name".to_string(),
             "--name".to_string(),
-            "invalid_scenario".to_string(),
+            "first".to_string(),
             "--input".to_string(),
-            "".to_string(),
+            "error".to_string(),
+            "--expect".to_string(),
+            "fail".to_string(),
         ];
-        let scenario = ScenarioStub::new(scenario_name);
-        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
-        let test_context = TestContext::new(Box::new(root_group));
 
-        // It's expected that test will fail due to error from `TestContext`, not from `run_cli_app`.
-        let result = run_cli_app(&raw_arguments, &test_context);
-        assert!(result.is_err_and(|e| e == "Scenario invalid_scenario not found"));
+        let (code, _) = run_cli_app_exit_captured(&raw_arguments, &test_context, |_| None);
+        assert_eq!(code, format!("{:?}", ExitCode::SUCCESS));
+    }
+
+    #[test]
+    fn test_run_cli_app_exit_quarantined_failure() {
+        let quarantine_path = write_temp_file(
+            "test_run_cli_app_exit_quarantined_failure_q.txt",
+            "second\n",
+        );
+        let (test_context, _) = init_recording_context(&["first", "second"]);
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--input".to_string(),
+            "error".to_string(),
+            "--quarantine-file".to_string(),
+            quarantine_path.to_string_lossy().to_string(),
+        ];
+
+        let (default_code, _) = run_cli_app_exit_captured(&raw_arguments, &test_context, |_| None);
+        let (mapped_code, _) = run_cli_app_exit_captured(&raw_arguments, &test_context, |stats| {
+            (stats.failed == stats.quarantined).then(|| ExitCode::from(75))
+        });
+        std::fs::remove_file(&quarantine_path).unwrap();
+
+        assert_eq!(default_code, format!("{:?}", ExitCode::SUCCESS));
+        assert_eq!(mapped_code, format!("{:?}", ExitCode::from(75)));
+    }
+
+    #[test]
+    fn test_run_cli_app_exit_global_timeout() {
+        let (test_context, _) = init_recording_context(&["first", "second", "third"]);
+        let raw_arguments = [
+            "exe_name".to_string(),
+            "--name".to_string(),
+            "first".to_string(),
+            "--name".to_string(),
+            "second".to_string(),
+            "--name".to_string(),
+            "third".to_string(),
+            "--input".to_string(),
+            "sleep".to_string(),
+            "--global-timeout".to_string(),
+            "0.1".to_string(),
+        ];
+
+        let (code, _) =
+            run_cli_app_exit_captured(&raw_arguments, &test_context, |_| Some(ExitCode::from(75)));
+        assert_eq!(code, format!("{:?}", ExitCode::from(124)));
+    }
+
+    #[test]
+    fn test_default_exit_code() {
+        assert_eq!(
+            format!("{:?}", default_exit_code(&Ok(()))),
+            format!("{:?}", ExitCode::SUCCESS)
+        );
+        let failed = Err(CliError::Scenario(ScenarioError::new(vec![], "failed")));
+        assert_eq!(
+            format!("{:?}", default_exit_code(&failed)),
+            format!("{:?}", ExitCode::FAILURE)
+        );
+        let usage = Err(CliError::Usage("usage".to_string()));
+        assert_eq!(
+            format!("{:?}", default_exit_code(&usage)),
+            format!("{:?}", ExitCode::from(2))
+        );
     }
 }
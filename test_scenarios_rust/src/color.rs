@@ -0,0 +1,123 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Terminal color selection.
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+/// ANSI color usage mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Use colors only if stderr is a terminal.
+    #[default]
+    Auto,
+
+    /// Always use colors.
+    Always,
+
+    /// Never use colors.
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!("Invalid color mode: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorMode::Auto => f.write_str("auto"),
+            ColorMode::Always => f.write_str("always"),
+            ColorMode::Never => f.write_str("never"),
+        }
+    }
+}
+
+impl ColorMode {
+    /// Decide whether colors are used.
+    ///
+    /// * `is_tty` - Whether output is a terminal.
+    pub fn use_color(self, is_tty: bool) -> bool {
+        match self {
+            ColorMode::Auto => is_tty,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+
+    /// Decide whether colors are used, detecting whether stderr is a terminal.
+    pub fn use_color_stderr(self) -> bool {
+        self.use_color(std::io::stderr().is_terminal())
+    }
+}
+
+/// Color scenario status, e.g. `Outcome::status()`.
+/// Passed is green, skipped is yellow, others are red.
+///
+/// * `status` - Scenario status.
+/// * `color` - Whether colors are used.
+pub fn paint_status(status: &str, color: bool) -> String {
+    if !color {
+        return status.to_string();
+    }
+    let code = match status {
+        "passed" => "32",
+        "skipped" => "33",
+        _ => "31",
+    };
+    format!("\x1b[{code}m{status}\x1b[0m")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::{paint_status, ColorMode};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_color_mode_from_str() {
+        assert_eq!(ColorMode::from_str("auto"), Ok(ColorMode::Auto));
+        assert_eq!(ColorMode::from_str("always"), Ok(ColorMode::Always));
+        assert_eq!(ColorMode::from_str("never"), Ok(ColorMode::Never));
+        assert_eq!(
+            ColorMode::from_str("sometimes"),
+            Err("Invalid color mode: sometimes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_color_mode_use_color() {
+        assert!(ColorMode::Auto.use_color(true));
+        assert!(!ColorMode::Auto.use_color(false));
+        assert!(ColorMode::Always.use_color(true));
+        assert!(ColorMode::Always.use_color(false));
+        assert!(!ColorMode::Never.use_color(true));
+        assert!(!ColorMode::Never.use_color(false));
+    }
+
+    #[test]
+    fn test_paint_status() {
+        assert_eq!(paint_status("passed", false), "passed");
+        assert_eq!(paint_status("passed", true), "\x1b[32mpassed\x1b[0m");
+        assert_eq!(paint_status("skipped", true), "\x1b[33mskipped\x1b[0m");
+        assert_eq!(paint_status("timed_out", true), "\x1b[31mtimed_out\x1b[0m");
+    }
+}
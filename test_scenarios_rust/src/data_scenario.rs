@@ -0,0 +1,323 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Declarative scenarios defined by JSON files.
+//!
+//! Each file contains a JSON object with fields:
+//! - `name` - scenario name, required.
+//! - `description` - human-readable description, optional.
+//! - `input` - test scenario input, optional. Strings are used as-is, other values are serialized.
+//! - `expected` - expected result, `pass` (default) or `fail`.
+//!
+//! Run passes if provided input matches declared input, JSON inputs are compared by value.
+//! Result of the run is then compared with expected result, so scenario expected to fail
+//! passes only if provided input does not match.
+use crate::scenario::{Scenario, ScenarioGroupImpl};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Expected result of a data scenario.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expected {
+    /// Scenario is expected to pass.
+    Pass,
+
+    /// Scenario is expected to fail.
+    Fail,
+}
+
+impl FromStr for Expected {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pass" => Ok(Expected::Pass),
+            "fail" => Ok(Expected::Fail),
+            _ => Err(format!("Invalid expected result: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Pass => write!(f, "pass"),
+            Expected::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Scenario with behavior read from JSON definition.
+/// Provided input is evaluated against declared input, result is compared with expected result.
+#[derive(Clone, Debug)]
+pub struct DataScenario {
+    name: String,
+    description: String,
+    input: Option<String>,
+    expected: Expected,
+}
+
+impl DataScenario {
+    /// Create scenario from JSON definition.
+    ///
+    /// * `definition` - JSON definition of the scenario.
+    pub fn from_json(definition: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(definition)
+            .map_err(|e| format!("Failed to parse scenario definition: {e}"))?;
+        let Value::Object(object) = value else {
+            return Err("Scenario definition must be a JSON object".to_string());
+        };
+
+        let name = match object.get("name") {
+            Some(Value::String(name)) if !name.is_empty() => name.clone(),
+            _ => return Err("Scenario definition must contain non-empty name".to_string()),
+        };
+        let description = match object.get("description") {
+            Some(Value::String(description)) => description.clone(),
+            Some(_) => return Err(format!("Description of scenario {name} must be a string")),
+            None => String::new(),
+        };
+        let input = match object.get("input") {
+            Some(Value::String(input)) => Some(input.clone()),
+            Some(input) => Some(input.to_string()),
+            None => None,
+        };
+        let expected = match object.get("expected").map(Value::as_str) {
+            None | Some(Some("pass")) => Expected::Pass,
+            Some(Some("fail")) => Expected::Fail,
+            Some(_) => {
+                return Err(format!(
+                    "Expected result of scenario {name} must be 'pass' or 'fail'"
+                ))
+            }
+        };
+
+        Ok(DataScenario {
+            name,
+            description,
+            input,
+            expected,
+        })
+    }
+
+    /// Test scenario input declared by definition.
+    pub fn input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    /// Expected result declared by definition.
+    pub fn expected(&self) -> Expected {
+        self.expected
+    }
+
+    /// Evaluate provided input against declared input.
+    /// Any input matches if no input is declared.
+    ///
+    /// * `input` - Test scenario input.
+    fn evaluate(&self, input: &str) -> Result<(), String> {
+        let Some(declared) = &self.input else {
+            return Ok(());
+        };
+        let json_equal = || match (
+            serde_json::from_str::<Value>(declared),
+            serde_json::from_str::<Value>(input),
+        ) {
+            (Ok(declared), Ok(input)) => declared == input,
+            _ => false,
+        };
+        if input == declared || json_equal() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Input of scenario {} does not match declared input: expected {declared}, got {input}",
+                self.name
+            ))
+        }
+    }
+}
+
+impl Scenario for DataScenario {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, input: &str) -> Result<(), String> {
+        match (self.expected, self.evaluate(input)) {
+            (Expected::Pass, result) => result,
+            (Expected::Fail, Err(_)) => Ok(()),
+            (Expected::Fail, Ok(())) => Err(format!(
+                "Scenario {} is expected to fail, but input matches declared input",
+                self.name
+            )),
+        }
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// Load scenarios from all `.json` files in a directory, nested directories are not searched.
+/// Group is named after the directory, scenarios are ordered by file name.
+///
+/// * `path` - Path to directory with scenario definitions.
+pub fn load_scenarios_from_dir(path: &Path) -> Result<ScenarioGroupImpl, String> {
+    let entries = std::fs::read_dir(path)
+        .map_err(|e| format!("Failed to read directory {}: {e}", path.display()))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry_path = entry
+            .map_err(|e| format!("Failed to read directory {}: {e}", path.display()))?
+            .path();
+        if entry_path.is_file() && entry_path.extension().is_some_and(|ext| ext == "json") {
+            files.push(entry_path);
+        }
+    }
+    files.sort();
+
+    let mut scenarios: Vec<Box<dyn Scenario>> = Vec::new();
+    for file in files {
+        let definition = std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+        let scenario = DataScenario::from_json(&definition)
+            .map_err(|e| format!("Invalid scenario definition {}: {e}", file.display()))?;
+        scenarios.push(Box::new(scenario));
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(ScenarioGroupImpl::new(&name, scenarios, vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_scenario::{load_scenarios_from_dir, DataScenario, Expected};
+    use crate::scenario::{Scenario, ScenarioGroup, ScenarioGroupImpl};
+    use crate::test_context::TestContext;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    /// Create directory with unique name in temporary directory.
+    fn create_temp_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{name}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_data_scenario_from_json() {
+        let scenario = DataScenario::from_json(
+            r#"{"name": "check", "description": "Check", "input": {"a": 1}, "expected": "fail"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(scenario.name(), "check");
+        assert_eq!(scenario.description(), "Check");
+        assert_eq!(scenario.input(), Some(r#"{"a":1}"#));
+        assert_eq!(scenario.expected(), Expected::Fail);
+    }
+
+    #[test]
+    fn test_data_scenario_run() {
+        let scenario =
+            DataScenario::from_json(r#"{"name": "check", "input": {"a": 1, "b": 2}}"#).unwrap();
+
+        assert!(scenario.run(r#"{"b": 2, "a": 1}"#).is_ok());
+        assert!(scenario.run(r#"{"a":1,"b":2}"#).is_ok());
+        assert!(scenario.run(r#"{"a": 2}"#).is_err_and(|e| e
+            == r#"Input of scenario check does not match declared input: expected {"a":1,"b":2}, got {"a": 2}"#));
+        assert!(scenario.run("not json").is_err());
+    }
+
+    #[test]
+    fn test_data_scenario_run_expected_fail() {
+        let scenario =
+            DataScenario::from_json(r#"{"name": "check", "input": "x", "expected": "fail"}"#)
+                .unwrap();
+
+        assert!(scenario.run("y").is_ok());
+        assert!(scenario.run("x").is_err_and(
+            |e| e == "Scenario check is expected to fail, but input matches declared input"
+        ));
+    }
+
+    #[test]
+    fn test_data_scenario_from_json_defaults() {
+        let scenario = DataScenario::from_json(r#"{"name": "check"}"#).unwrap();
+
+        assert_eq!(scenario.input(), None);
+        assert_eq!(scenario.expected(), Expected::Pass);
+        assert!(scenario.run("").is_ok());
+        assert!(scenario.run("any").is_ok());
+    }
+
+    #[test]
+    fn test_data_scenario_from_json_invalid() {
+        assert!(DataScenario::from_json(r#"{"input": "x"}"#)
+            .is_err_and(|e| e == "Scenario definition must contain non-empty name"));
+        assert!(
+            DataScenario::from_json(r#"{"name": "check", "expected": "maybe"}"#)
+                .is_err_and(|e| e == "Expected result of scenario check must be 'pass' or 'fail'")
+        );
+    }
+
+    #[test]
+    fn test_expected_from_str() {
+        assert_eq!(Expected::from_str("pass"), Ok(Expected::Pass));
+        assert_eq!(Expected::from_str("fail"), Ok(Expected::Fail));
+        assert_eq!(Expected::Fail.to_string(), "fail");
+        assert!(Expected::from_str("maybe").is_err_and(|e| e == "Invalid expected result: maybe"));
+    }
+
+    #[test]
+    fn test_load_scenarios_from_dir() {
+        let path = create_temp_dir("test_load_scenarios_from_dir");
+        std::fs::write(
+            path.join("b.json"),
+            r#"{"name": "second", "expected": "fail"}"#,
+        )
+        .unwrap();
+        std::fs::write(path.join("a.json"), r#"{"name": "first", "input": "x"}"#).unwrap();
+        std::fs::write(path.join("notes.txt"), "not a scenario").unwrap();
+
+        let result = load_scenarios_from_dir(&path);
+        std::fs::remove_dir_all(&path).unwrap();
+
+        let group = result.unwrap();
+        assert_eq!(group.name(), path.file_name().unwrap().to_string_lossy());
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let test_context = TestContext::new(Box::new(root_group));
+        let group_name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(
+            test_context.list_scenarios(),
+            vec![
+                format!("{group_name}.first"),
+                format!("{group_name}.second")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_scenarios_from_dir_invalid_file() {
+        let path = create_temp_dir("test_load_scenarios_from_dir_invalid_file");
+        std::fs::write(path.join("broken.json"), "{").unwrap();
+
+        let result = load_scenarios_from_dir(&path);
+        std::fs::remove_dir_all(&path).unwrap();
+
+        assert!(result.is_err_and(|e| e.starts_with("Invalid scenario definition")));
+    }
+}
@@ -0,0 +1,227 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Errors returned by the test scenario runner.
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Failure of one or more executed scenarios.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioError {
+    /// Names of the failed scenarios.
+    pub names: Vec<String>,
+
+    /// Error message.
+    pub message: String,
+}
+
+impl ScenarioError {
+    /// Create scenario error.
+    ///
+    /// * `names` - Names of the failed scenarios.
+    /// * `message` - Error message.
+    pub fn new(names: Vec<String>, message: impl Into<String>) -> Self {
+        ScenarioError {
+            names,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// Error returned by [`crate::cli::run_cli_app`].
+/// New kinds of errors might be added, use [`CliError::exit_code`] to classify unknown ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CliError {
+    /// Invalid invocation, e.g., unknown argument or scenario name.
+    Usage(String),
+
+    /// Scenarios were run and failed.
+    Scenario(ScenarioError),
+
+    /// Global timeout was exceeded, scenarios listed in error were not run.
+    GlobalTimeout(ScenarioError),
+
+    /// Writing output or reports failed.
+    Io(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Io(message) => write!(f, "{message}"),
+            CliError::Scenario(error) | CliError::GlobalTimeout(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl CliError {
+    /// Process exit code for the error.
+    /// Scenario and I/O failures exit with 1, usage errors with 2 and exceeded global timeout
+    /// with 124, as the `timeout` command does.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Scenario(_) | CliError::Io(_) => 1,
+            CliError::GlobalTimeout(_) => 124,
+        }
+    }
+
+    /// Machine-readable representation of the error.
+    /// Contains `kind` (`usage`, `scenario`, `global_timeout` or `io`), `message` and `scenario`.
+    /// `scenario` contains comma-separated names of the failed or not run scenarios,
+    /// `null` for usage and I/O errors.
+    pub fn to_json(&self) -> Value {
+        match self {
+            CliError::Usage(message) => json!({
+                "kind": "usage",
+                "message": message,
+                "scenario": Value::Null,
+            }),
+            CliError::Scenario(error) => json!({
+                "kind": "scenario",
+                "message": error.message,
+                "scenario": error.names.join(","),
+            }),
+            CliError::GlobalTimeout(error) => json!({
+                "kind": "global_timeout",
+                "message": error.message,
+                "scenario": error.names.join(","),
+            }),
+            CliError::Io(message) => json!({
+                "kind": "io",
+                "message": message,
+                "scenario": Value::Null,
+            }),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Format error with its chain of sources, separated by `: `.
+///
+/// * `error` - Outermost error.
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{error_chain, CliError, ScenarioError};
+    use serde_json::json;
+    use std::fmt;
+
+    #[test]
+    fn test_cli_error_display() {
+        let usage = CliError::Usage("Unknown argument provided: --x".to_string());
+        let scenario = CliError::Scenario(ScenarioError::new(
+            vec!["scenario".to_string()],
+            "Requested error",
+        ));
+
+        assert_eq!(usage.to_string(), "Unknown argument provided: --x");
+        assert_eq!(scenario.to_string(), "Requested error");
+    }
+
+    #[test]
+    fn test_cli_error_to_json() {
+        let usage = CliError::Usage("Unknown argument provided: --x".to_string());
+        let scenario = CliError::Scenario(ScenarioError::new(
+            vec!["first".to_string(), "second".to_string()],
+            "Requested error",
+        ));
+
+        assert_eq!(
+            usage.to_json(),
+            json!({"kind": "usage", "message": "Unknown argument provided: --x", "scenario": null})
+        );
+        assert_eq!(
+            scenario.to_json(),
+            json!({"kind": "scenario", "message": "Requested error", "scenario": "first,second"})
+        );
+    }
+
+    #[test]
+    fn test_cli_error_global_timeout() {
+        let error = CliError::GlobalTimeout(ScenarioError::new(
+            vec!["second".to_string()],
+            "Global timeout exceeded, 1 of 2 scenarios not run",
+        ));
+
+        assert_eq!(
+            error.to_json(),
+            json!({
+                "kind": "global_timeout",
+                "message": "Global timeout exceeded, 1 of 2 scenarios not run",
+                "scenario": "second"
+            })
+        );
+        assert_eq!(error.exit_code(), 124);
+        assert_eq!(CliError::Usage(String::new()).exit_code(), 2);
+        assert_eq!(CliError::Io(String::new()).exit_code(), 1);
+        assert_eq!(
+            CliError::Scenario(ScenarioError::new(vec![], "")).exit_code(),
+            1
+        );
+    }
+
+    #[derive(Debug)]
+    struct ChainedError {
+        message: &'static str,
+        source: Option<Box<ChainedError>>,
+    }
+
+    impl fmt::Display for ChainedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.message)
+        }
+    }
+
+    impl std::error::Error for ChainedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.source
+                .as_deref()
+                .map(|source| source as &(dyn std::error::Error + 'static))
+        }
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let error = ChainedError {
+            message: "Failed to connect",
+            source: Some(Box::new(ChainedError {
+                message: "Connection refused",
+                source: None,
+            })),
+        };
+        assert_eq!(error_chain(&error), "Failed to connect: Connection refused");
+        assert_eq!(
+            error_chain(error.source.as_deref().unwrap()),
+            "Connection refused"
+        );
+    }
+}
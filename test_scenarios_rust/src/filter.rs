@@ -0,0 +1,336 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+use crate::hash::stable_hash;
+use crate::scenario::Scenario;
+use std::fmt;
+use std::str::FromStr;
+
+/// Check whether text matches wildcard pattern, `*` matches any sequence of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in pattern and matching position in text.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Extend sequence matched by the last `*` by one character.
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Check whether scenario name or any of its groups matches pattern.
+/// Pattern can contain `*` wildcards, e.g., `group.*_slow`.
+///
+/// * `pattern` - Pattern to match.
+/// * `name` - Fully-qualified scenario name.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    name.match_indices('.')
+        .map(|(index, _)| &name[..index])
+        .chain([name])
+        .any(|prefix| wildcard_match(pattern, prefix))
+}
+
+/// Scenario toggles, used to enable or disable scenarios without changing selection.
+/// Disabled scenarios are reported as skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScenarioToggles {
+    /// Patterns of enabled scenarios or groups, see [`matches_pattern`].
+    /// All scenarios are enabled if empty.
+    pub enable: Vec<String>,
+
+    /// Patterns of disabled scenarios or groups, see [`matches_pattern`].
+    /// Take precedence over `enable`.
+    pub disable: Vec<String>,
+}
+
+impl ScenarioToggles {
+    /// Check if scenario is enabled.
+    ///
+    /// * `name` - Fully-qualified scenario name.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, name))
+        };
+        !matches_any(&self.disable) && (self.enable.is_empty() || matches_any(&self.enable))
+    }
+}
+
+/// Shard of scenarios, used to split scenarios between multiple runners.
+/// Scenario belongs to the shard selected by stable hash of its fully-qualified name,
+/// so assignment does not change between runs, Rust versions or platforms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// One-based index of the shard, at most `count`.
+    pub index: u64,
+
+    /// Number of shards, at least 1.
+    pub count: u64,
+}
+
+impl Shard {
+    /// Check if scenario belongs to the shard.
+    ///
+    /// * `name` - Fully-qualified scenario name.
+    pub fn contains(&self, name: &str) -> bool {
+        stable_hash(name) % self.count == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid shard parameter: {s}, expected <index>/<count>");
+        let (index, count) = s.split_once('/').ok_or_else(invalid)?;
+        let index: u64 = index.parse().map_err(|_| invalid())?;
+        let count: u64 = count.parse().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+        Ok(Shard { index, count })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Scenario selection filter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScenarioFilter {
+    /// Tags to select.
+    /// Scenario is selected if it has any of the tags. All scenarios are selected if empty.
+    pub tags: Vec<String>,
+
+    /// Scenarios or groups to skip, as fully-qualified names.
+    pub skip: Vec<String>,
+
+    /// Shard to select, all scenarios are selected if `None`.
+    pub shard: Option<Shard>,
+}
+
+impl ScenarioFilter {
+    /// Check if scenario is selected by the filter.
+    ///
+    /// * `name` - Fully-qualified scenario name.
+    /// * `scenario` - Scenario to check.
+    pub fn matches(&self, name: &str, scenario: &dyn Scenario) -> bool {
+        self.matches_with_group_tags(name, scenario, &[])
+    }
+
+    /// Check if scenario is selected by the filter, considering tags inherited from its groups.
+    ///
+    /// * `name` - Fully-qualified scenario name.
+    /// * `scenario` - Scenario to check.
+    /// * `group_tags` - Tags of all groups containing the scenario.
+    pub fn matches_with_group_tags(
+        &self,
+        name: &str,
+        scenario: &dyn Scenario,
+        group_tags: &[String],
+    ) -> bool {
+        let skipped = self
+            .skip
+            .iter()
+            .any(|skip| name == skip || name.starts_with(&format!("{skip}.")));
+        if skipped || self.shard.is_some_and(|shard| !shard.contains(name)) {
+            return false;
+        }
+
+        self.tags.is_empty()
+            || scenario
+                .tags()
+                .iter()
+                .chain(group_tags)
+                .any(|tag| self.tags.contains(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::{matches_pattern, wildcard_match, ScenarioFilter, ScenarioToggles, Shard};
+    use crate::scenario::Scenario;
+    use std::str::FromStr;
+
+    struct ScenarioStub {
+        tags: Vec<String>,
+    }
+
+    impl Scenario for ScenarioStub {
+        fn name(&self) -> &str {
+            "scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn tags(&self) -> &[String] {
+            &self.tags
+        }
+    }
+
+    #[test]
+    fn test_matches_empty_filter() {
+        let filter = ScenarioFilter::default();
+        let scenario = ScenarioStub { tags: vec![] };
+        assert!(filter.matches("group.scenario", &scenario));
+    }
+
+    #[test]
+    fn test_matches_tags() {
+        let filter = ScenarioFilter {
+            tags: vec!["fast".to_string(), "smoke".to_string()],
+            skip: vec![],
+            shard: None,
+        };
+        let tagged = ScenarioStub {
+            tags: vec!["smoke".to_string()],
+        };
+        let untagged = ScenarioStub { tags: vec![] };
+        assert!(filter.matches("scenario", &tagged));
+        assert!(!filter.matches("scenario", &untagged));
+    }
+
+    #[test]
+    fn test_matches_skip() {
+        let filter = ScenarioFilter {
+            tags: vec![],
+            skip: vec!["group".to_string(), "other.scenario".to_string()],
+            shard: None,
+        };
+        let scenario = ScenarioStub { tags: vec![] };
+        assert!(!filter.matches("group.scenario", &scenario));
+        assert!(!filter.matches("group.inner.scenario", &scenario));
+        assert!(!filter.matches("other.scenario", &scenario));
+        assert!(filter.matches("other.scenario_2", &scenario));
+        assert!(filter.matches("group_2.scenario", &scenario));
+    }
+
+    #[test]
+    fn test_matches_group_tags() {
+        let filter = ScenarioFilter {
+            tags: vec!["integration".to_string()],
+            skip: vec![],
+            shard: None,
+        };
+        let scenario = ScenarioStub { tags: vec![] };
+        let group_tags = vec!["integration".to_string()];
+        assert!(filter.matches_with_group_tags("group.scenario", &scenario, &group_tags));
+        assert!(!filter.matches_with_group_tags("group.scenario", &scenario, &[]));
+    }
+
+    #[test]
+    fn test_matches_shard() {
+        let scenario = ScenarioStub { tags: vec![] };
+        let names = ["group.first", "group.second", "group.third"];
+        // Known assignment, changing it would move scenarios between shards of existing pipelines.
+        let shards: Vec<u64> = names
+            .iter()
+            .map(|name| {
+                (1..=4)
+                    .find(|index| {
+                        let filter = ScenarioFilter {
+                            shard: Some(Shard {
+                                index: *index,
+                                count: 4,
+                            }),
+                            ..Default::default()
+                        };
+                        filter.matches(name, &scenario)
+                    })
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(shards, [1, 3, 4]);
+
+        // Each scenario belongs to exactly one shard.
+        for name in names {
+            let count = (1..=3)
+                .filter(|index| {
+                    Shard {
+                        index: *index,
+                        count: 3,
+                    }
+                    .contains(name)
+                })
+                .count();
+            assert_eq!(count, 1, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_shard_from_str() {
+        assert_eq!(Shard::from_str("2/3"), Ok(Shard { index: 2, count: 3 }));
+        assert_eq!(Shard::from_str("1/1").unwrap().to_string(), "1/1");
+        for invalid in ["0/3", "4/3", "1/0", "1", "a/3", "1/3/5"] {
+            assert_eq!(
+                Shard::from_str(invalid),
+                Err(format!(
+                    "Invalid shard parameter: {invalid}, expected <index>/<count>"
+                )),
+            );
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("scenario", "scenario"));
+        assert!(wildcard_match("*", "scenario"));
+        assert!(wildcard_match("sc*io", "scenario"));
+        assert!(wildcard_match("*a*a*", "banana"));
+        assert!(!wildcard_match("sc*x", "scenario"));
+        assert!(!wildcard_match("scenario", "scenario_2"));
+    }
+
+    #[test]
+    fn test_matches_pattern_groups() {
+        assert!(matches_pattern("group", "group.inner.scenario"));
+        assert!(matches_pattern("group.inner", "group.inner.scenario"));
+        assert!(matches_pattern("group.*_slow", "group.scenario_slow"));
+        assert!(!matches_pattern("group", "group_2.scenario"));
+        assert!(!matches_pattern("inner", "group.inner.scenario"));
+    }
+
+    #[test]
+    fn test_toggles_overlapping() {
+        let toggles = ScenarioToggles {
+            enable: vec!["group".to_string(), "other.first".to_string()],
+            disable: vec!["group.*_slow".to_string(), "other".to_string()],
+        };
+        assert!(toggles.is_enabled("group.scenario"));
+        assert!(!toggles.is_enabled("group.scenario_slow"));
+        // Disable wins over enable.
+        assert!(!toggles.is_enabled("other.first"));
+        // Not enabled.
+        assert!(!toggles.is_enabled("third.scenario"));
+        assert!(ScenarioToggles::default().is_enabled("third.scenario"));
+    }
+}
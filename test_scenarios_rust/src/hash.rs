@@ -0,0 +1,52 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Stable hashing of scenario names.
+//! Unlike [`std::hash::DefaultHasher`], hash values do not change between Rust versions or platforms,
+//! so they can be used for assignment of scenarios to shards, see `--shard`,
+//! or in persistent names, e.g., of scenario log files.
+
+/// FNV-1a 64-bit offset basis.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a 64-bit prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Compute stable 64-bit FNV-1a hash of a name.
+///
+/// * `name` - Name to hash, e.g., fully-qualified scenario name.
+pub(crate) fn stable_hash(name: &str) -> u64 {
+    name.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::stable_hash;
+
+    #[test]
+    fn test_stable_hash_known_values() {
+        assert_eq!(stable_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_stable_hash_bucket_assignment() {
+        let buckets: Vec<u64> = ["group.first", "group.second", "group.third"]
+            .iter()
+            .map(|name| stable_hash(name) % 4)
+            .collect();
+        assert_eq!(buckets, [0, 2, 3]);
+    }
+}
@@ -12,7 +12,43 @@
 // *******************************************************************************
 //! Common implementation of test scenario runner for Rust.
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "tokio")]
+pub mod async_scenario;
+pub mod cancellation;
 pub mod cli;
-mod monotonic_clock;
+pub mod color;
+pub mod data_scenario;
+pub mod error;
+pub mod filter;
+pub(crate) mod hash;
+pub mod logging;
+pub mod metrics;
+pub mod monotonic_clock;
+pub mod outcome;
+pub mod panic_capture;
+pub mod prelude;
+pub(crate) mod regex;
+pub mod report;
+pub mod resource_usage;
 pub mod scenario;
+pub mod scenario_env;
+pub mod schema;
+pub mod sub_results;
+pub mod subprocess;
 pub mod test_context;
+#[cfg(test)]
+mod test_utils;
+
+pub use cli::{
+    default_exit_code, run_cli, run_cli_app, run_cli_app_exit, run_cli_app_with_config,
+    run_cli_app_with_io, CliArguments, CliConfig,
+};
+pub use error::{CliError, ScenarioError};
+pub use outcome::Outcome;
+pub use scenario::{
+    FnScenario, GroupPolicy, PlaceholderScenario, Scenario, ScenarioGroup, ScenarioGroupImpl,
+};
+pub use test_context::{TestContext, TestContextBuilder};
@@ -0,0 +1,764 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Tracing subscriber configuration.
+//!
+//! Without `tracing` feature, subscriber is never created and logs are not emitted.
+#[cfg(feature = "tracing")]
+use crate::monotonic_clock::{shared_start, MonotonicClock};
+#[cfg(feature = "tracing")]
+use serde_json::Map;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "tracing")]
+use tracing::field::{Field, Visit};
+#[cfg(feature = "tracing")]
+use tracing::{Event, Subscriber};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::filter::LevelFilter;
+#[cfg(feature = "tracing")]
+use tracing_subscriber::fmt::format::{JsonFields, Writer};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+#[cfg(feature = "tracing")]
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::registry::LookupSpan;
+#[cfg(feature = "tracing")]
+use tracing_subscriber::Registry;
+
+/// Layer boxed to unify types of differently configured layers.
+#[cfg(feature = "tracing")]
+type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+#[cfg(feature = "tracing")]
+pub use tracing::Level;
+
+/// Guard of the non-blocking log writer, flushes pending logs when dropped.
+#[cfg(feature = "tracing")]
+pub type LogGuard = tracing_appender::non_blocking::WorkerGuard;
+
+/// Log level, replaces `tracing::Level` when `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Level(u8);
+
+#[cfg(not(feature = "tracing"))]
+impl Level {
+    /// Trace level.
+    pub const TRACE: Level = Level(0);
+    /// Debug level.
+    pub const DEBUG: Level = Level(1);
+    /// Info level.
+    pub const INFO: Level = Level(2);
+    /// Warn level.
+    pub const WARN: Level = Level(3);
+    /// Error level.
+    pub const ERROR: Level = Level(4);
+}
+
+#[cfg(not(feature = "tracing"))]
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" | "5" => Ok(Level::TRACE),
+            "debug" | "4" => Ok(Level::DEBUG),
+            "info" | "3" => Ok(Level::INFO),
+            "warn" | "2" => Ok(Level::WARN),
+            "error" | "1" => Ok(Level::ERROR),
+            _ => Err(format!("Invalid log level: {s}")),
+        }
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"][self.0 as usize];
+        f.write_str(name)
+    }
+}
+
+/// Guard of the non-blocking log writer, no-op when `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug)]
+pub struct LogGuard;
+
+/// Log record format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// JSON object per record.
+    #[default]
+    Json,
+
+    /// Human-readable text.
+    Text,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(LogFormat::Json),
+            "text" => Ok(LogFormat::Text),
+            _ => Err(format!("Invalid log format: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Json => f.write_str("json"),
+            LogFormat::Text => f.write_str("text"),
+        }
+    }
+}
+
+/// Log record timestamp mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimestampMode {
+    /// Microseconds elapsed since subscriber creation.
+    #[default]
+    Monotonic,
+
+    /// RFC 3339 wall-clock time.
+    WallClock,
+
+    /// No timestamp.
+    None,
+
+    /// RFC 3339 wall-clock time, with microseconds elapsed since subscriber creation
+    /// in a separate `monotonic_us` field.
+    Both,
+}
+
+impl FromStr for TimestampMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "monotonic" => Ok(TimestampMode::Monotonic),
+            "wall-clock" => Ok(TimestampMode::WallClock),
+            "none" => Ok(TimestampMode::None),
+            "both" => Ok(TimestampMode::Both),
+            _ => Err(format!("Invalid timestamp mode: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for TimestampMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampMode::Monotonic => f.write_str("monotonic"),
+            TimestampMode::WallClock => f.write_str("wall-clock"),
+            TimestampMode::None => f.write_str("none"),
+            TimestampMode::Both => f.write_str("both"),
+        }
+    }
+}
+
+/// Log output stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogOutput {
+    /// Standard output.
+    #[default]
+    Stdout,
+
+    /// Standard error.
+    Stderr,
+}
+
+impl FromStr for LogOutput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdout" => Ok(LogOutput::Stdout),
+            "stderr" => Ok(LogOutput::Stderr),
+            _ => Err(format!("Invalid log output: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for LogOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogOutput::Stdout => f.write_str("stdout"),
+            LogOutput::Stderr => f.write_str("stderr"),
+        }
+    }
+}
+
+/// Tracing subscriber configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TracingConfig {
+    /// Maximum log level.
+    pub level: Level,
+
+    /// Log record format.
+    pub format: LogFormat,
+
+    /// Log record timestamp mode.
+    pub timestamp_mode: TimestampMode,
+
+    /// Log output stream.
+    pub output: LogOutput,
+
+    /// Write logs from a background thread, without blocking the logging thread.
+    pub nonblocking: bool,
+
+    /// Include thread ids in log records.
+    pub thread_ids: bool,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        TracingConfig {
+            level: Level::TRACE,
+            format: LogFormat::default(),
+            timestamp_mode: TimestampMode::default(),
+            output: LogOutput::default(),
+            nonblocking: false,
+            thread_ids: true,
+        }
+    }
+}
+
+/// Layer formatting records with provided timer.
+#[cfg(feature = "tracing")]
+fn build_layer<S, T>(config: &TracingConfig, writer: BoxMakeWriter, timer: T) -> BoxedLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    T: FormatTime + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_thread_ids(config.thread_ids)
+        .with_writer(writer)
+        .with_timer(timer);
+
+    match config.format {
+        LogFormat::Json => layer.json().boxed(),
+        LogFormat::Text => layer.boxed(),
+    }
+}
+
+/// Event format adding monotonic timestamp to records produced by inner format.
+/// JSON records get a `monotonic_us` field, text records are prefixed with the timestamp.
+#[cfg(feature = "tracing")]
+struct MonotonicField<F> {
+    inner: F,
+    clock: MonotonicClock,
+}
+
+#[cfg(feature = "tracing")]
+impl<S, N, F> FormatEvent<S, N> for MonotonicField<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut record = String::new();
+        self.inner
+            .format_event(ctx, Writer::new(&mut record), event)?;
+        let micros = self.clock.elapsed_micros();
+        match record.strip_prefix('{') {
+            Some(rest) => write!(writer, "{{\"monotonic_us\":{micros},{rest}"),
+            None => write!(writer, "{micros} {record}"),
+        }
+    }
+}
+
+/// Layer logging both wall-clock and monotonic timestamps.
+#[cfg(feature = "tracing")]
+fn build_layer_both<S>(config: &TracingConfig, writer: BoxMakeWriter) -> BoxedLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    let format = tracing_subscriber::fmt::format()
+        .with_thread_ids(config.thread_ids)
+        .with_timer(SystemTime);
+    let clock = MonotonicClock::from_start(shared_start());
+
+    match config.format {
+        LogFormat::Json => layer
+            .fmt_fields(JsonFields::new())
+            .event_format(MonotonicField {
+                inner: format.json(),
+                clock,
+            })
+            .boxed(),
+        LogFormat::Text => layer
+            .event_format(MonotonicField {
+                inner: format.with_ansi(false),
+                clock,
+            })
+            .boxed(),
+    }
+}
+
+/// Create a layer formatting records to provided writer, not filtered by configured level.
+#[cfg(feature = "tracing")]
+fn create_layer<S>(config: &TracingConfig, writer: BoxMakeWriter) -> BoxedLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    match config.timestamp_mode {
+        TimestampMode::Monotonic => {
+            build_layer(config, writer, MonotonicClock::from_start(shared_start()))
+        }
+        TimestampMode::WallClock => build_layer(config, writer, SystemTime),
+        TimestampMode::None => build_layer(config, writer, ()),
+        TimestampMode::Both => build_layer_both(config, writer),
+    }
+}
+
+/// Create a tracing subscriber using provided writer.
+#[cfg(feature = "tracing")]
+fn create_subscriber_with_writer(
+    config: &TracingConfig,
+    writer: BoxMakeWriter,
+) -> Box<dyn Subscriber + Send + Sync> {
+    Box::new(
+        Registry::default()
+            .with(LevelFilter::from_level(config.level))
+            .with(create_layer(config, writer)),
+    )
+}
+
+/// Create a tracing subscriber using provided writer, capturing events into event log.
+/// Configured level filters only logged records, event log is filtered by its own maximum level.
+#[cfg(feature = "tracing")]
+fn create_subscriber_with_event_log(
+    config: &TracingConfig,
+    writer: BoxMakeWriter,
+    event_log: &EventLog,
+) -> Box<dyn Subscriber + Send + Sync> {
+    let filter = LevelFilter::from_level(config.level);
+    let event_filter = LevelFilter::from_level(event_log.max_level);
+    Box::new(
+        Registry::default()
+            .with(create_layer(config, writer).with_filter(filter))
+            .with(event_log.clone().with_filter(event_filter)),
+    )
+}
+
+/// Create a tracing subscriber based on provided configuration.
+/// Returns guard of the non-blocking writer, `None` if writer is blocking.
+/// Pending logs are flushed when guard is dropped, guard must be kept alive as long as subscriber is used.
+///
+/// * `config` - Tracing configuration.
+#[cfg(feature = "tracing")]
+pub fn create_subscriber(
+    config: &TracingConfig,
+) -> (Box<dyn Subscriber + Send + Sync>, Option<LogGuard>) {
+    let (writer, guard) = create_writer(config);
+    (create_subscriber_with_writer(config, writer), guard)
+}
+
+/// Create writer to configured output.
+/// Returns guard of the non-blocking writer, `None` if writer is blocking.
+#[cfg(feature = "tracing")]
+fn create_writer(config: &TracingConfig) -> (BoxMakeWriter, Option<LogGuard>) {
+    match (config.output, config.nonblocking) {
+        (LogOutput::Stdout, false) => (BoxMakeWriter::new(std::io::stdout), None),
+        (LogOutput::Stderr, false) => (BoxMakeWriter::new(std::io::stderr), None),
+        (LogOutput::Stdout, true) => {
+            let (writer, guard) = tracing_appender::non_blocking(std::io::stdout());
+            (BoxMakeWriter::new(writer), Some(guard))
+        }
+        (LogOutput::Stderr, true) => {
+            let (writer, guard) = tracing_appender::non_blocking(std::io::stderr());
+            (BoxMakeWriter::new(writer), Some(guard))
+        }
+    }
+}
+
+/// Buffer of all events logged during a run, written as a single JSON array.
+/// Events are captured when buffer is installed as a tracing layer,
+/// see [`init_tracing_with_event_log`]. Nothing is captured when `tracing` feature is disabled.
+/// Events up to [`Level::TRACE`] are captured by default, regardless of the configured log level.
+#[derive(Clone, Debug)]
+pub struct EventLog {
+    events: Arc<Mutex<Vec<Value>>>,
+    max_level: Level,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        EventLog {
+            events: Arc::default(),
+            max_level: Level::TRACE,
+        }
+    }
+}
+
+impl EventLog {
+    /// Capture only events with level up to `level`.
+    ///
+    /// * `level` - Maximum level of captured events.
+    pub fn with_max_level(mut self, level: Level) -> Self {
+        self.max_level = level;
+        self
+    }
+
+    /// Captured events, in order of logging.
+    /// Each event is an object with `level`, `target` and `fields`.
+    pub fn events(&self) -> Vec<Value> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Write captured events to file as JSON array.
+    ///
+    /// * `path` - Path to event log file.
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, Value::Array(self.events()).to_string())
+            .map_err(|e| format!("Failed to write event log: {e}"))
+    }
+
+    /// Write captured events to file as NDJSON, one event per line.
+    ///
+    /// * `path` - Path to event log file.
+    pub fn write_ndjson(&self, path: &str) -> Result<(), String> {
+        let ndjson: String = self
+            .events()
+            .iter()
+            .map(|event| format!("{event}\n"))
+            .collect();
+        std::fs::write(path, ndjson).map_err(|e| format!("Failed to write event log: {e}"))
+    }
+}
+
+/// Visitor collecting event fields into JSON object.
+#[cfg(feature = "tracing")]
+struct JsonVisitor(Map<String, Value>);
+
+#[cfg(feature = "tracing")]
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<S: Subscriber> Layer<S> for EventLog {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor(Map::new());
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push(serde_json::json!({
+            "level": metadata.level().to_string(),
+            "target": metadata.target(),
+            "fields": visitor.0,
+        }));
+    }
+}
+
+/// Create a tracing subscriber and set it as global default.
+/// Returns guard of the non-blocking writer, which must be kept alive until the process exits.
+/// Returns an error if global default is already set.
+///
+/// * `config` - Tracing configuration.
+#[cfg(feature = "tracing")]
+pub fn init_tracing(config: &TracingConfig) -> Result<Option<LogGuard>, String> {
+    let (subscriber, guard) = create_subscriber(config);
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Failed to set tracing subscriber: {e}"))?;
+    Ok(guard)
+}
+
+/// No-op, logs are not emitted when `tracing` feature is disabled.
+///
+/// * `config` - Tracing configuration.
+#[cfg(not(feature = "tracing"))]
+pub fn init_tracing(_config: &TracingConfig) -> Result<Option<LogGuard>, String> {
+    Ok(None)
+}
+
+/// Create a tracing subscriber capturing all events into event log and set it as global default.
+/// Events are logged as with [`init_tracing`], event log is filtered by its own maximum level,
+/// see [`EventLog::with_max_level`].
+/// Returns an error if global default is already set.
+///
+/// * `config` - Tracing configuration.
+/// * `event_log` - Event log capturing all events.
+#[cfg(feature = "tracing")]
+pub fn init_tracing_with_event_log(
+    config: &TracingConfig,
+    event_log: &EventLog,
+) -> Result<Option<LogGuard>, String> {
+    let (writer, guard) = create_writer(config);
+    let subscriber = create_subscriber_with_event_log(config, writer, event_log);
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Failed to set tracing subscriber: {e}"))?;
+    Ok(guard)
+}
+
+/// No-op, logs are not emitted when `tracing` feature is disabled.
+///
+/// * `config` - Tracing configuration.
+/// * `event_log` - Event log, stays empty.
+#[cfg(not(feature = "tracing"))]
+pub fn init_tracing_with_event_log(
+    _config: &TracingConfig,
+    _event_log: &EventLog,
+) -> Result<Option<LogGuard>, String> {
+    Ok(None)
+}
+
+/// Check whether global tracing subscriber was already set.
+/// Always `false` when `tracing` feature is disabled.
+pub(crate) fn tracing_initialized() -> bool {
+    #[cfg(feature = "tracing")]
+    return tracing::dispatcher::has_been_set();
+    #[cfg(not(feature = "tracing"))]
+    return false;
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::logging::{
+        create_subscriber, create_subscriber_with_event_log, create_subscriber_with_writer,
+        init_tracing, EventLog, LogFormat, LogOutput, TimestampMode, TracingConfig,
+    };
+    use crate::scenario::{FnScenario, ScenarioGroupImpl};
+    use crate::test_context::{NoopObserver, RunOptions, TestContext};
+    use crate::test_utils::SharedBuffer;
+    use serde_json::Value;
+    use std::str::FromStr;
+    use tracing::Level;
+    use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+    #[test]
+    fn test_create_subscriber_configs() {
+        for format in [LogFormat::Json, LogFormat::Text] {
+            for timestamp_mode in [
+                TimestampMode::Monotonic,
+                TimestampMode::WallClock,
+                TimestampMode::None,
+                TimestampMode::Both,
+            ] {
+                for output in [LogOutput::Stdout, LogOutput::Stderr] {
+                    let config = TracingConfig {
+                        level: Level::INFO,
+                        format,
+                        timestamp_mode,
+                        output,
+                        nonblocking: false,
+                        thread_ids: true,
+                    };
+                    let (subscriber, _guard) = create_subscriber(&config);
+                    tracing::subscriber::with_default(subscriber, || {
+                        assert!(tracing::enabled!(Level::INFO));
+                        assert!(!tracing::enabled!(Level::DEBUG));
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_timestamp_mode_both_json() {
+        let config = TracingConfig {
+            timestamp_mode: TimestampMode::Both,
+            ..Default::default()
+        };
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber =
+            create_subscriber_with_writer(&config, BoxMakeWriter::new(move || writer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || tracing::info!("Example message"));
+
+        let logs = buffer.contents();
+        let record: serde_json::Value = serde_json::from_str(logs.trim_end()).unwrap();
+        assert!(record["monotonic_us"].is_u64());
+        // RFC 3339, e.g. "2025-01-01T12:00:00.000000Z".
+        let timestamp = record["timestamp"].as_str().unwrap();
+        assert!(
+            timestamp.contains('T') && timestamp.ends_with('Z'),
+            "{timestamp}"
+        );
+        assert_eq!(record["fields"]["message"], "Example message");
+    }
+
+    #[test]
+    fn test_thread_ids() {
+        for thread_ids in [true, false] {
+            let config = TracingConfig {
+                thread_ids,
+                ..Default::default()
+            };
+            let buffer = SharedBuffer::default();
+            let writer = buffer.clone();
+            let subscriber =
+                create_subscriber_with_writer(&config, BoxMakeWriter::new(move || writer.clone()));
+
+            tracing::subscriber::with_default(subscriber, || tracing::info!("Example message"));
+
+            let logs = buffer.contents();
+            let record: serde_json::Value = serde_json::from_str(logs.trim_end()).unwrap();
+            assert_eq!(record.get("threadId").is_some(), thread_ids, "{logs}");
+        }
+    }
+
+    #[test]
+    fn test_create_subscriber_nonblocking() {
+        for output in [LogOutput::Stdout, LogOutput::Stderr] {
+            let config = TracingConfig {
+                level: Level::INFO,
+                output,
+                nonblocking: true,
+                ..Default::default()
+            };
+            let (subscriber, guard) = create_subscriber(&config);
+            assert!(guard.is_some());
+            tracing::subscriber::with_default(subscriber, || {
+                assert!(tracing::enabled!(Level::INFO));
+                assert!(!tracing::enabled!(Level::DEBUG));
+            });
+        }
+
+        let (_, guard) = create_subscriber(&TracingConfig::default());
+        assert!(guard.is_none());
+    }
+
+    #[test]
+    fn test_init_tracing_already_set() {
+        let config = TracingConfig::default();
+        // First call might fail if other test already set global default.
+        let _ = init_tracing(&config);
+        let result = init_tracing(&config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(LogFormat::from_str("text"), Ok(LogFormat::Text));
+        assert!(LogFormat::from_str("xml").is_err_and(|e| e == "Invalid log format: xml"));
+        assert_eq!(
+            TimestampMode::from_str("wall-clock"),
+            Ok(TimestampMode::WallClock)
+        );
+        assert!(TimestampMode::from_str("x").is_err_and(|e| e == "Invalid timestamp mode: x"));
+        assert_eq!(LogOutput::from_str("stderr"), Ok(LogOutput::Stderr));
+        assert!(LogOutput::from_str("file").is_err_and(|e| e == "Invalid log output: file"));
+    }
+
+    #[test]
+    fn test_event_log_captures_scenario_events() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(FnScenario::new("first", |_| Ok(())))],
+            vec![],
+        );
+        let context = TestContext::new(Box::new(root_group));
+        // Debug events are captured by event log, even though they are not logged.
+        let config = TracingConfig {
+            level: Level::INFO,
+            ..Default::default()
+        };
+        let event_log = EventLog::default();
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = create_subscriber_with_event_log(
+            &config,
+            BoxMakeWriter::new(move || writer.clone()),
+            &event_log,
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            let runs = vec![("first".to_string(), String::new())];
+            context.run_many(&runs, &RunOptions::default(), &NoopObserver);
+        });
+        let path = std::env::temp_dir().join(format!(
+            "{}_test_event_log_captures_scenario_events.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+        event_log.write(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let events: Value = serde_json::from_str(&content).unwrap();
+        let messages: Vec<&str> = events
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["fields"]["message"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["Scenario first started", "Scenario first finished: passed"]
+        );
+        assert_eq!(events[0]["level"], "DEBUG");
+        assert_eq!(events[0]["target"], "test_scenarios_rust::test_context");
+        let logs = buffer.contents();
+        assert!(!logs.contains("Scenario first started"), "{logs}");
+    }
+}
+
+#[cfg(all(test, not(feature = "tracing")))]
+mod tests {
+    use crate::logging::{init_tracing, Level, TracingConfig};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_init_tracing_noop() {
+        let result = init_tracing(&TracingConfig::default());
+        assert!(result.is_ok_and(|guard| guard.is_none()));
+    }
+
+    #[test]
+    fn test_level_from_str() {
+        assert_eq!(Level::from_str("INFO"), Ok(Level::INFO));
+        assert_eq!(Level::from_str("1"), Ok(Level::ERROR));
+        assert!(Level::from_str("x").is_err_and(|e| e == "Invalid log level: x"));
+    }
+}
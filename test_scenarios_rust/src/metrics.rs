@@ -0,0 +1,68 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Numeric metrics reported by scenarios.
+use std::sync::{Arc, Mutex};
+
+/// Sink collecting metrics recorded by a single scenario run.
+/// Clones share recorded metrics.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSink {
+    metrics: Arc<Mutex<Vec<(String, f64)>>>,
+}
+
+impl MetricsSink {
+    /// Create empty metrics sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record metric value.
+    ///
+    /// * `key` - Metric name.
+    /// * `value` - Metric value.
+    pub fn record(&self, key: &str, value: f64) {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((key.to_string(), value));
+    }
+
+    /// Recorded metrics, in order of recording.
+    pub fn snapshot(&self) -> Vec<(String, f64)> {
+        self.metrics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::MetricsSink;
+
+    #[test]
+    fn test_metrics_sink_shared_by_clones() {
+        let sink = MetricsSink::new();
+        let clone = sink.clone();
+        sink.record("latency_ms", 1.5);
+        clone.record("throughput", 100.0);
+
+        assert_eq!(
+            sink.snapshot(),
+            vec![
+                ("latency_ms".to_string(), 1.5),
+                ("throughput".to_string(), 100.0)
+            ]
+        );
+    }
+}
@@ -10,26 +10,106 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 // *******************************************************************************
+//! Monotonic clock providing timestamps of log records.
+//!
+//! Without `tracing` feature, clock only measures elapsed time and is not used by any subscriber.
+#[cfg(feature = "tracing")]
 use std::fmt;
+use std::sync::OnceLock;
+use std::time::Instant;
+#[cfg(feature = "tracing")]
 use tracing_subscriber::fmt::format::Writer;
+#[cfg(feature = "tracing")]
 use tracing_subscriber::fmt::time::FormatTime;
 
+/// Start instant shared by clocks of log subscribers, set on first use.
+static SHARED_START: OnceLock<Instant> = OnceLock::new();
+
+/// Start instant shared by clocks of log subscribers, so their timestamps are aligned.
+/// Set when first requested.
+pub fn shared_start() -> Instant {
+    *SHARED_START.get_or_init(Instant::now)
+}
+
 /// Timestamp provider using monotonic clock.
 pub struct MonotonicClock {
-    start: std::time::Instant,
+    start: Instant,
 }
 
 impl MonotonicClock {
+    /// Create clock starting now.
     pub fn new() -> Self {
-        Self {
-            start: std::time::Instant::now(),
-        }
+        Self::from_start(Instant::now())
+    }
+
+    /// Create clock starting at given instant.
+    ///
+    /// * `start` - Instant timestamps are measured from.
+    pub fn from_start(start: Instant) -> Self {
+        Self { start }
+    }
+
+    /// Instant timestamps are measured from.
+    pub fn start_instant(&self) -> Instant {
+        self.start
+    }
+
+    /// Microseconds elapsed since clock start.
+    pub fn elapsed_micros(&self) -> u128 {
+        self.micros_at(Instant::now())
+    }
+
+    /// Microseconds from clock start to given instant, zero for instants before start.
+    ///
+    /// * `instant` - Measured instant.
+    pub fn micros_at(&self, instant: Instant) -> u128 {
+        instant.saturating_duration_since(self.start).as_micros()
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+#[cfg(feature = "tracing")]
 impl FormatTime for MonotonicClock {
     fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
-        let elapsed = std::time::Instant::now() - self.start;
-        write!(w, "{}", elapsed.as_micros())
+        write!(w, "{}", self.elapsed_micros())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::monotonic_clock::{shared_start, MonotonicClock};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_from_start_same_values() {
+        let start = Instant::now();
+        let first = MonotonicClock::from_start(start);
+        let second = MonotonicClock::from_start(start);
+        let instant = start + Duration::from_millis(5);
+
+        assert_eq!(first.start_instant(), second.start_instant());
+        assert_eq!(first.micros_at(instant), 5000);
+        assert_eq!(
+            first.micros_at(instant).to_string(),
+            second.micros_at(instant).to_string()
+        );
+    }
+
+    #[test]
+    fn test_micros_at_before_start() {
+        let clock = MonotonicClock::new();
+        let before = clock.start_instant() - Duration::from_millis(1);
+
+        assert_eq!(clock.micros_at(before), 0);
+    }
+
+    #[test]
+    fn test_shared_start_stable() {
+        assert_eq!(shared_start(), shared_start());
     }
 }
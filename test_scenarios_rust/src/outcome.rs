@@ -0,0 +1,182 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Test scenario run outcome.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Scenario passed.
+    Passed,
+
+    /// Scenario failed with provided error.
+    Failed(String),
+
+    /// Scenario was skipped with provided reason.
+    Skipped(String),
+
+    /// Scenario did not finish before timeout.
+    TimedOut,
+
+    /// Scenario panicked with provided message.
+    Panicked(String),
+}
+
+impl Outcome {
+    /// Check outcome is not a failure.
+    /// Skipped scenarios are not considered failed.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Passed | Outcome::Skipped(_))
+    }
+
+    /// Short status name of the outcome.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::Failed(_) => "failed",
+            Outcome::Skipped(_) => "skipped",
+            Outcome::TimedOut => "timed_out",
+            Outcome::Panicked(_) => "panicked",
+        }
+    }
+
+    /// Outcome as JSON object with `status` and `message` fields.
+    /// Message is `null` for passed and timed out scenarios.
+    pub fn to_json(&self) -> Value {
+        let message = match self {
+            Outcome::Failed(message) | Outcome::Skipped(message) | Outcome::Panicked(message) => {
+                Some(message)
+            }
+            Outcome::Passed | Outcome::TimedOut => None,
+        };
+        json!({"status": self.status(), "message": message})
+    }
+
+    /// Read outcome from JSON object, as created by [`Outcome::to_json`].
+    ///
+    /// * `value` - JSON object with outcome.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let message = || {
+            value["message"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("Outcome must contain message: {value}"))
+        };
+        match value["status"].as_str() {
+            Some("passed") => Ok(Outcome::Passed),
+            Some("failed") => Ok(Outcome::Failed(message()?)),
+            Some("skipped") => Ok(Outcome::Skipped(message()?)),
+            Some("timed_out") => Ok(Outcome::TimedOut),
+            Some("panicked") => Ok(Outcome::Panicked(message()?)),
+            _ => Err(format!("Invalid outcome: {value}")),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "Scenario passed"),
+            Outcome::Failed(error) => write!(f, "{error}"),
+            Outcome::Skipped(reason) => write!(f, "Scenario skipped: {reason}"),
+            Outcome::TimedOut => write!(f, "Scenario timed out"),
+            Outcome::Panicked(message) => write!(f, "Scenario panicked: {message}"),
+        }
+    }
+}
+
+impl From<Outcome> for Result<(), String> {
+    fn from(outcome: Outcome) -> Self {
+        if outcome.is_ok() {
+            Ok(())
+        } else {
+            Err(outcome.to_string())
+        }
+    }
+}
+
+impl From<Result<(), String>> for Outcome {
+    fn from(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Outcome::Passed,
+            Err(error) => Outcome::Failed(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outcome::Outcome;
+    use serde_json::json;
+
+    #[test]
+    fn test_into_result_ok() {
+        let result: Result<(), String> = Outcome::Passed.into();
+        assert!(result.is_ok());
+
+        let result: Result<(), String> = Outcome::Skipped("Not supported".to_string()).into();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_into_result_err() {
+        let result: Result<(), String> = Outcome::Failed("Requested error".to_string()).into();
+        assert!(result.is_err_and(|e| e == "Requested error"));
+
+        let result: Result<(), String> = Outcome::TimedOut.into();
+        assert!(result.is_err_and(|e| e == "Scenario timed out"));
+
+        let result: Result<(), String> = Outcome::Panicked("Boom".to_string()).into();
+        assert!(result.is_err_and(|e| e == "Scenario panicked: Boom"));
+    }
+
+    #[test]
+    fn test_from_result() {
+        assert_eq!(Outcome::from(Ok(())), Outcome::Passed);
+        assert_eq!(
+            Outcome::from(Err("Requested error".to_string())),
+            Outcome::Failed("Requested error".to_string())
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let outcomes = [
+            Outcome::Passed,
+            Outcome::Failed("Requested error".to_string()),
+            Outcome::Skipped("Not supported".to_string()),
+            Outcome::TimedOut,
+            Outcome::Panicked("Boom".to_string()),
+        ];
+        for outcome in outcomes {
+            assert_eq!(Outcome::from_json(&outcome.to_json()), Ok(outcome));
+        }
+
+        assert_eq!(
+            Outcome::Failed("Requested error".to_string()).to_json(),
+            json!({"status": "failed", "message": "Requested error"})
+        );
+        assert!(Outcome::from_json(&json!({"status": "failed"}))
+            .is_err_and(|e| e == r#"Outcome must contain message: {"status":"failed"}"#));
+        assert!(Outcome::from_json(&json!({"status": "unknown"})).is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(Outcome::Passed.status(), "passed");
+        assert_eq!(Outcome::Failed(String::new()).status(), "failed");
+        assert_eq!(Outcome::Skipped(String::new()).status(), "skipped");
+        assert_eq!(Outcome::TimedOut.status(), "timed_out");
+        assert_eq!(Outcome::Panicked(String::new()).status(), "panicked");
+    }
+}
@@ -0,0 +1,137 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Catching panics with location of the panic.
+//!
+//! Panic payload contains only the message, location is only known to the panic hook.
+//! Recording hook is installed once, on first capture, and chained with the prior hook.
+//! Location is recorded only for panics on threads currently running [`catch_panic`],
+//! other panics are only reported by the prior hook.
+//!
+//! Hook is deliberately not scoped to a capture and prior hook is never restored.
+//! Panic hook is process-wide, while captures run in parallel and a scenario abandoned
+//! due to timeout may still panic after its capture was reported. Swapping hooks
+//! per capture would race between threads and could remove the recording hook
+//! while another capture is active.
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+static INSTALL_HOOK: Once = Once::new();
+
+thread_local! {
+    /// Number of active captures on this thread, nested captures are counted.
+    static CAPTURES: Cell<usize> = const { Cell::new(0) };
+
+    /// Location of the last panic on this thread, as `<file>:<line>`.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Install recording hook, chained with hook installed before.
+/// Hook stays installed, a capture abandoned due to timeout does not affect other threads.
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let prior_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURES.with(Cell::get) > 0 {
+                if let Some(location) = info.location() {
+                    let location = format!("{}:{}", location.file(), location.line());
+                    PANIC_LOCATION.with(|cell| *cell.borrow_mut() = Some(location));
+                }
+            }
+            // Prior hook still reports the panic, e.g., default hook prints it to stderr.
+            prior_hook(info);
+        }));
+    });
+}
+
+/// Get message from panic payload.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic payload".to_string()
+    }
+}
+
+/// Run function, catching panic raised on the current thread.
+/// Error contains panic message with its location, `panicked at <file>:<line>: <message>`.
+/// Location is omitted if not known, e.g., panic hook was replaced after first capture.
+///
+/// Recording hook installed by first call stays installed for the rest of the process,
+/// see [module documentation](self) for the reason.
+///
+/// * `run` - Function to run.
+pub fn catch_panic<R>(run: impl FnOnce() -> R) -> Result<R, String> {
+    install_hook();
+    PANIC_LOCATION.with(|cell| cell.borrow_mut().take());
+    CAPTURES.with(|captures| captures.set(captures.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(run));
+    CAPTURES.with(|captures| captures.set(captures.get() - 1));
+
+    result.map_err(|payload| {
+        let message = panic_message(payload.as_ref());
+        match PANIC_LOCATION.with(|cell| cell.borrow_mut().take()) {
+            Some(location) => format!("panicked at {location}: {message}"),
+            None => message,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::panic_capture::{catch_panic, PANIC_LOCATION};
+
+    #[test]
+    fn test_catch_panic_ok() {
+        assert_eq!(catch_panic(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn test_catch_panic_location() {
+        let line = line!() + 1;
+        let result = catch_panic(|| panic!("Requested panic"));
+
+        let expected = format!("panicked at {}:{line}: Requested panic", file!());
+        assert_eq!(result, Err(expected));
+    }
+
+    #[test]
+    fn test_catch_panic_nested() {
+        let result = catch_panic(|| {
+            let inner = catch_panic(|| panic!("Inner panic"));
+            assert!(inner.is_err_and(|e| e.ends_with(": Inner panic")));
+            panic!("Outer panic");
+        });
+
+        assert!(
+            result.is_err_and(|e| e.starts_with("panicked at ") && e.ends_with(": Outer panic"))
+        );
+    }
+
+    #[test]
+    fn test_uncaptured_panic_not_recorded() {
+        // Hook is installed by a capture, panics outside of captures are not recorded.
+        assert_eq!(catch_panic(|| 42), Ok(42));
+        let recorded = std::thread::spawn(|| {
+            let _ = std::panic::catch_unwind(|| panic!("Uncaptured panic"));
+            PANIC_LOCATION.with(|cell| cell.borrow().clone())
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(recorded, None);
+    }
+}
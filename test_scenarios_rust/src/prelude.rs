@@ -0,0 +1,49 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Commonly used types required to define scenarios and run CLI application.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use test_scenarios_rust::prelude::*;
+//!
+//! struct ExampleScenario;
+//!
+//! impl Scenario for ExampleScenario {
+//!     fn name(&self) -> &str {
+//!         "example_scenario"
+//!     }
+//!
+//!     fn run(&self, _input: &str) -> Result<(), String> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let inner_group = ScenarioGroupImpl::new("inner_group", vec![Box::new(ExampleScenario)], vec![]);
+//! let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(inner_group)]);
+//! let test_context = TestContext::new(Box::new(root_group));
+//!
+//! assert_eq!(test_context.list_scenarios(), vec!["inner_group.example_scenario"]);
+//!
+//! let raw_arguments = vec!["example".to_string(), "--list-scenarios".to_string()];
+//! assert!(run_cli_app(&raw_arguments, &test_context).is_ok());
+//! ```
+
+pub use crate::cancellation::CancellationToken;
+pub use crate::cli::{run_cli, run_cli_app, CliArguments};
+pub use crate::outcome::Outcome;
+pub use crate::scenario::{
+    FnScenario, GroupPolicy, PlaceholderScenario, Scenario, ScenarioGroup, ScenarioGroupImpl,
+};
+pub use crate::scenario_env::ScenarioEnv;
+pub use crate::test_context::{TestContext, TestContextBuilder};
@@ -0,0 +1,486 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Minimal regular expressions, used to select scenarios by name.
+//!
+//! Supported syntax:
+//! - literals, `.` and escapes `\d`, `\w`, `\s` or escaped punctuation, e.g., `\.`,
+//! - character classes, e.g., `[a-z_]` or negated `[^0-9]`,
+//! - quantifiers `*`, `+` and `?`,
+//! - anchors `^` and `$`,
+//! - groups `(...)` and alternation `|`.
+//!
+//! Other escapes and repetition counts `{n,m}` are rejected.
+//! Patterns are matched without backtracking, in time linear in text length.
+
+/// Single element of the pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    /// Literal character.
+    Char(char),
+    /// Any character.
+    Any,
+    /// Character from inclusive ranges, or not from them if negated.
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    /// Start of text.
+    Start,
+    /// End of text.
+    End,
+    /// Alternatives of sequences.
+    Group(Vec<Vec<Node>>),
+    /// Node repeated between `min` and `max` times, unlimited if `max` is not set.
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// Ranges of `\d` escape.
+const DIGIT: &[(char, char)] = &[('0', '9')];
+/// Ranges of `\w` escape.
+const WORD: &[(char, char)] = &[('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+/// Ranges of `\s` escape.
+const SPACE: &[(char, char)] = &[(' ', ' '), ('\t', '\r')];
+
+/// Compiled regular expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regex {
+    program: Vec<Inst>,
+}
+
+/// Pattern parser, position is an index of the next character.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.peek();
+        self.position += 1;
+        next
+    }
+
+    /// Parse alternatives until end of pattern or closing parenthesis.
+    fn parse_alternatives(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut alternatives = vec![self.parse_sequence()?];
+        while self.peek() == Some('|') {
+            self.position += 1;
+            alternatives.push(self.parse_sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    /// Parse sequence until end of pattern, alternation or closing parenthesis.
+    fn parse_sequence(&mut self) -> Result<Vec<Node>, String> {
+        let mut sequence = Vec::new();
+        while let Some(next) = self.peek() {
+            let (min, max) = match next {
+                '|' | ')' => break,
+                '*' => (0, None),
+                '+' => (1, None),
+                '?' => (0, Some(1)),
+                _ => {
+                    let node = self.parse_atom()?;
+                    sequence.push(node);
+                    continue;
+                }
+            };
+            let node = match sequence.pop() {
+                Some(node @ (Node::Char(_) | Node::Any | Node::Class { .. } | Node::Group(_))) => {
+                    Box::new(node)
+                }
+                _ => return Err(format!("nothing to repeat at position {}", self.position)),
+            };
+            self.position += 1;
+            sequence.push(Node::Repeat { node, min, max });
+        }
+        Ok(sequence)
+    }
+
+    /// Parse single character, class, anchor or group.
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let start = self.position;
+        match self.next() {
+            Some('.') => Ok(Node::Any),
+            Some('^') => Ok(Node::Start),
+            Some('$') => Ok(Node::End),
+            Some('(') => {
+                let alternatives = self.parse_alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(format!("unclosed group at position {start}"));
+                }
+                Ok(Node::Group(alternatives))
+            }
+            Some('[') => self.parse_class(start),
+            Some('{') => Err(format!(
+                "repetition counts are not supported at position {start}"
+            )),
+            Some('\\') => self.parse_escape(),
+            Some(c) => Ok(Node::Char(c)),
+            None => Err(format!("unexpected end of pattern at position {start}")),
+        }
+    }
+
+    /// Parse escape sequence, backslash is already consumed.
+    fn parse_escape(&mut self) -> Result<Node, String> {
+        let class = |ranges: &[(char, char)]| Node::Class {
+            ranges: ranges.to_vec(),
+            negated: false,
+        };
+        match self.next() {
+            Some('d') => Ok(class(DIGIT)),
+            Some('w') => Ok(class(WORD)),
+            Some('s') => Ok(class(SPACE)),
+            Some(c) if c.is_ascii_punctuation() => Ok(Node::Char(c)),
+            Some(c) => Err(format!(
+                "unsupported escape \\{c} at position {}",
+                self.position - 2
+            )),
+            None => Err("trailing backslash".to_string()),
+        }
+    }
+
+    /// Parse character class, opening bracket is already consumed.
+    fn parse_class(&mut self, start: usize) -> Result<Node, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.position += 1;
+        }
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let low = match self.next() {
+                None => return Err(format!("unclosed character class at position {start}")),
+                Some(']') if !first => break,
+                Some('\\') => match self.parse_escape()? {
+                    Node::Class {
+                        ranges: escaped, ..
+                    } => {
+                        ranges.extend(escaped);
+                        first = false;
+                        continue;
+                    }
+                    Node::Char(c) => c,
+                    _ => unreachable!("escape is a class or a character"),
+                },
+                Some(c) => c,
+            };
+            first = false;
+            let is_range = self.peek() == Some('-')
+                && self.chars.get(self.position + 1).is_some_and(|c| *c != ']');
+            if !is_range {
+                ranges.push((low, low));
+                continue;
+            }
+            self.position += 1;
+            let high = match self.next() {
+                Some('\\') => match self.parse_escape()? {
+                    Node::Char(c) => c,
+                    _ => return Err(format!("invalid class range at position {start}")),
+                },
+                Some(c) => c,
+                None => return Err(format!("unclosed character class at position {start}")),
+            };
+            if high < low {
+                return Err(format!("invalid class range {low}-{high}"));
+            }
+            ranges.push((low, high));
+        }
+        Ok(Node::Class { ranges, negated })
+    }
+}
+
+/// Instruction of the compiled pattern, matched by simulating all threads at once.
+/// Matching time is linear in text length for a given pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Inst {
+    /// Literal character.
+    Char(char),
+    /// Any character.
+    Any,
+    /// Character from inclusive ranges, or not from them if negated.
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    /// Start of text.
+    Start,
+    /// End of text.
+    End,
+    /// Continue at both instructions.
+    Split(usize, usize),
+    /// Continue at instruction.
+    Jump(usize),
+    /// Pattern matched.
+    Match,
+}
+
+/// Compile alternatives of sequences, appending instructions to program.
+fn compile_alternatives(alternatives: &[Vec<Node>], program: &mut Vec<Inst>) {
+    let mut jumps = Vec::new();
+    for (index, sequence) in alternatives.iter().enumerate() {
+        let is_last = index + 1 == alternatives.len();
+        let split = program.len();
+        if !is_last {
+            program.push(Inst::Split(split + 1, 0));
+        }
+        for node in sequence {
+            compile_node(node, program);
+        }
+        if !is_last {
+            jumps.push(program.len());
+            program.push(Inst::Jump(0));
+            program[split] = Inst::Split(split + 1, program.len());
+        }
+    }
+    let end = program.len();
+    for jump in jumps {
+        program[jump] = Inst::Jump(end);
+    }
+}
+
+/// Compile single node, appending instructions to program.
+fn compile_node(node: &Node, program: &mut Vec<Inst>) {
+    match node {
+        Node::Char(c) => program.push(Inst::Char(*c)),
+        Node::Any => program.push(Inst::Any),
+        Node::Class { ranges, negated } => program.push(Inst::Class {
+            ranges: ranges.clone(),
+            negated: *negated,
+        }),
+        Node::Start => program.push(Inst::Start),
+        Node::End => program.push(Inst::End),
+        Node::Group(alternatives) => compile_alternatives(alternatives, program),
+        Node::Repeat { node, min, max } => {
+            for _ in 0..*min {
+                compile_node(node, program);
+            }
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    compile_node(node, program);
+                    program.push(Inst::Jump(split));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+                Some(max) => {
+                    for _ in *min..*max {
+                        let split = program.len();
+                        program.push(Inst::Split(split + 1, 0));
+                        compile_node(node, program);
+                        program[split] = Inst::Split(split + 1, program.len());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Add thread at instruction to the list, following jumps, splits and anchors.
+///
+/// * `program` - Compiled pattern.
+/// * `pc` - Instruction of the thread.
+/// * `position` - Position in text.
+/// * `length` - Length of text.
+/// * `threads` - Instructions of threads waiting for a character or matched.
+/// * `visited` - Instructions already added at this position.
+fn add_thread(
+    program: &[Inst],
+    pc: usize,
+    position: usize,
+    length: usize,
+    threads: &mut Vec<usize>,
+    visited: &mut [bool],
+) {
+    if visited[pc] {
+        return;
+    }
+    visited[pc] = true;
+    match program[pc] {
+        Inst::Jump(target) => add_thread(program, target, position, length, threads, visited),
+        Inst::Split(first, second) => {
+            add_thread(program, first, position, length, threads, visited);
+            add_thread(program, second, position, length, threads, visited);
+        }
+        Inst::Start if position == 0 => {
+            add_thread(program, pc + 1, position, length, threads, visited)
+        }
+        Inst::End if position == length => {
+            add_thread(program, pc + 1, position, length, threads, visited)
+        }
+        Inst::Start | Inst::End => {}
+        _ => threads.push(pc),
+    }
+}
+
+impl Regex {
+    /// Compile regular expression.
+    /// Fails with a description of the syntax error.
+    ///
+    /// * `pattern` - Regular expression to compile.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let alternatives = parser.parse_alternatives()?;
+        if parser.peek() == Some(')') {
+            return Err(format!("unmatched ) at position {}", parser.position));
+        }
+        let mut program = Vec::new();
+        compile_alternatives(&alternatives, &mut program);
+        program.push(Inst::Match);
+        Ok(Regex { program })
+    }
+
+    /// Check whether regular expression matches any part of the text.
+    /// Use anchors `^` and `$` to match whole text.
+    ///
+    /// * `text` - Text to check.
+    pub fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        let mut threads = Vec::new();
+        for position in 0..=text.len() {
+            // Match may start at any position, new thread is started at each.
+            let mut visited = vec![false; self.program.len()];
+            let mut current = Vec::new();
+            for pc in threads.drain(..).chain([0]) {
+                add_thread(
+                    &self.program,
+                    pc,
+                    position,
+                    text.len(),
+                    &mut current,
+                    &mut visited,
+                );
+            }
+            let current_char = text.get(position).copied();
+            for pc in current {
+                let matched = match &self.program[pc] {
+                    Inst::Match => return true,
+                    Inst::Char(c) => current_char == Some(*c),
+                    Inst::Any => current_char.is_some(),
+                    Inst::Class { ranges, negated } => current_char.is_some_and(|c| {
+                        let in_ranges =
+                            ranges.iter().any(|(low, high)| (*low..=*high).contains(&c));
+                        in_ranges != *negated
+                    }),
+                    _ => false,
+                };
+                if matched {
+                    threads.push(pc + 1);
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::regex::Regex;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Regex::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn test_is_match_literals_and_anchors() {
+        assert!(is_match("scenario", "group.scenario_1"));
+        assert!(is_match("^group\\.", "group.scenario"));
+        assert!(!is_match("^scenario", "group.scenario"));
+        assert!(is_match("_1$", "group.scenario_1"));
+        assert!(!is_match("_1$", "group.scenario_10"));
+        assert!(is_match("", "anything"));
+    }
+
+    #[test]
+    fn test_is_match_quantifiers() {
+        assert!(is_match("^a.*z$", "abcz"));
+        assert!(is_match("^ab+c$", "abbbc"));
+        assert!(!is_match("^ab+c$", "ac"));
+        assert!(is_match("^ab?c$", "ac"));
+        assert!(!is_match("^ab?c$", "abbc"));
+        assert!(is_match("^(ab)*$", "ababab"));
+        assert!(is_match("^(a*)*b$", "aaab"));
+    }
+
+    #[test]
+    fn test_is_match_classes_and_alternation() {
+        assert!(is_match("^scenario_\\d+$", "scenario_42"));
+        assert!(!is_match("^scenario_\\d+$", "scenario_x"));
+        assert!(is_match("^[a-c_]+$", "ab_c"));
+        assert!(!is_match("^[^0-9]+$", "abc1"));
+        assert!(is_match("^(fast|slow)\\.\\w+$", "slow.scenario"));
+        assert!(!is_match("^(fast|slow)\\.\\w+$", "other.scenario"));
+        assert!(is_match("^[]a]+$", "]a"));
+        assert!(is_match("^[a-]+$", "a-"));
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(
+            Regex::new("(ab"),
+            Err("unclosed group at position 0".to_string())
+        );
+        assert_eq!(
+            Regex::new("ab)"),
+            Err("unmatched ) at position 2".to_string())
+        );
+        assert_eq!(
+            Regex::new("*ab"),
+            Err("nothing to repeat at position 0".to_string())
+        );
+        assert_eq!(
+            Regex::new("a[bc"),
+            Err("unclosed character class at position 1".to_string())
+        );
+        assert_eq!(
+            Regex::new("[z-a]"),
+            Err("invalid class range z-a".to_string())
+        );
+        assert_eq!(Regex::new("ab\\"), Err("trailing backslash".to_string()));
+        assert_eq!(
+            Regex::new("a\\D"),
+            Err("unsupported escape \\D at position 1".to_string())
+        );
+        assert_eq!(
+            Regex::new("\\b"),
+            Err("unsupported escape \\b at position 0".to_string())
+        );
+        assert_eq!(
+            Regex::new("a{2}"),
+            Err("repetition counts are not supported at position 1".to_string())
+        );
+        assert!(is_match("^a\\{2\\}$", "a{2}"));
+    }
+
+    #[test]
+    fn test_is_match_nested_repetition_is_fast() {
+        let text = "a".repeat(1000);
+        let start = std::time::Instant::now();
+
+        assert!(!is_match("^(a|a)*b$", &text));
+        assert!(!is_match("^(a*)*b$", &text));
+        assert!(is_match("^(a|a)*$", &text));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
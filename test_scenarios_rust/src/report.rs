@@ -0,0 +1,1176 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Test scenario run reports.
+use crate::outcome::Outcome;
+use crate::resource_usage::ResourceUsage;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Quote string as YAML double-quoted scalar.
+fn yaml_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Read YAML double-quoted scalar, as created by [`yaml_quote`].
+fn yaml_unquote(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            c @ ('"' | '\\') => value.push(c),
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// Scenario entry of detailed TAP report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TapEntry {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Scenario outcome.
+    pub outcome: Outcome,
+
+    /// Scenario was skipped without being run, e.g., after reaching maximum number of failures.
+    /// Reported as `not ok` with `# SKIP` directive, as it did not pass.
+    pub not_run: bool,
+
+    /// Scenario input, reported in diagnostics of entries which are not ok.
+    pub input: Option<String>,
+}
+
+/// Create TAP (Test Anything Protocol) version 13 report.
+///
+/// * `results` - Scenario names with run results.
+pub fn to_tap(results: &[(String, Result<(), String>)]) -> String {
+    let entries: Vec<TapEntry> = results
+        .iter()
+        .map(|(name, result)| TapEntry {
+            name: name.clone(),
+            outcome: result.clone().into(),
+            not_run: false,
+            input: None,
+        })
+        .collect();
+    to_tap_entries(&entries, None)
+}
+
+/// Create TAP version 13 report with skip directives and inputs of entries which are not ok.
+///
+/// * `entries` - Scenario entries.
+/// * `error` - Error which aborted the run, reported with bail out line.
+pub fn to_tap_entries(entries: &[TapEntry], error: Option<&str>) -> String {
+    let mut tap = String::from("TAP version 13\n");
+    tap.push_str(&format!("1..{}\n", entries.len()));
+    for (index, entry) in entries.iter().enumerate() {
+        let number = index + 1;
+        let name = &entry.name;
+        let message = match &entry.outcome {
+            Outcome::Passed => {
+                tap.push_str(&format!("ok {number} - {name}\n"));
+                continue;
+            }
+            Outcome::Skipped(reason) if !entry.not_run => {
+                tap.push_str(&format!("ok {number} - {name} # SKIP {reason}\n"));
+                continue;
+            }
+            Outcome::Skipped(reason) => {
+                tap.push_str(&format!("not ok {number} - {name} # SKIP {reason}\n"));
+                None
+            }
+            outcome => {
+                tap.push_str(&format!("not ok {number} - {name}\n"));
+                Some(outcome.to_string())
+            }
+        };
+        tap.push_str("  ---\n");
+        if let Some(message) = message {
+            tap.push_str(&format!("  message: {}\n", yaml_quote(&message)));
+        }
+        if let Some(input) = &entry.input {
+            tap.push_str(&format!("  input: {}\n", yaml_quote(input)));
+        }
+        tap.push_str("  ...\n");
+    }
+    if let Some(error) = error {
+        tap.push_str(&format!("Bail out! {error}\n"));
+    }
+    tap
+}
+
+/// Read names of scenarios which did not pass from TAP report.
+/// Each name is listed once, in order of first failure.
+///
+/// * `tap` - TAP report, as created by [`to_tap`].
+pub fn tap_failures(tap: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (name, _) in tap_failed_runs(tap) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Read names and inputs of scenario runs which did not pass from TAP report.
+/// Input is `None` if it is not reported in diagnostics. Each run is listed once, in order of first failure.
+///
+/// * `tap` - TAP report, as created by [`to_tap_entries`].
+pub fn tap_failed_runs(tap: &str) -> Vec<(String, Option<String>)> {
+    let mut runs: Vec<(String, Option<String>)> = Vec::new();
+    // Diagnostics follow the test line they belong to.
+    let mut current: Option<(String, Option<String>)> = None;
+    for line in tap.lines() {
+        if let Some(quoted) = line.strip_prefix("  input: ") {
+            if let Some((_, input)) = &mut current {
+                *input = yaml_unquote(quoted);
+            }
+            continue;
+        }
+        if line.starts_with("  ") {
+            continue;
+        }
+        runs.extend(current.take().filter(|run| !runs.contains(run)));
+        let Some(test_line) = line.strip_prefix("not ok ") else {
+            continue;
+        };
+        if let Some((_, name)) = test_line.split_once(" - ") {
+            // Directive, e.g., `# SKIP`, is not part of the name.
+            let name = name.split_once(" # ").map_or(name, |(name, _)| name);
+            current = Some((name.to_string(), None));
+        }
+    }
+    runs.extend(current.filter(|run| !runs.contains(run)));
+    runs
+}
+
+/// Create TAP version 13 report of a run aborted by an error.
+/// Results of scenarios run before the abort are followed by a bail out line.
+///
+/// * `results` - Scenario names with run results.
+/// * `error` - Error which aborted the run.
+pub fn to_tap_aborted(results: &[(String, Result<(), String>)], error: &str) -> String {
+    let mut tap = to_tap(results);
+    tap.push_str(&format!("Bail out! {error}\n"));
+    tap
+}
+
+/// Quote CSV field if it contains separator, quote or line break.
+fn csv_quote(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Create CSV report with scenario timings.
+/// Columns: `name,status,duration_us,error`.
+/// Error column is empty for passed and skipped scenarios.
+///
+/// * `results` - Scenario names with outcomes and execution times.
+pub fn to_csv(results: &[(String, Outcome, Duration)]) -> String {
+    let mut csv = String::from("name,status,duration_us,error\n");
+    for (name, outcome, duration) in results {
+        csv.push_str(&csv_row(name, outcome, outcome.status(), *duration));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Scenario entry of detailed CSV report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvEntry {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Scenario outcome.
+    pub outcome: Outcome,
+
+    /// Scenario execution time.
+    pub duration: Duration,
+
+    /// Reported test scenario input, possibly truncated.
+    pub input: String,
+
+    /// Scenario is deprecated.
+    pub deprecated: bool,
+
+    /// Scenario took longer than expected.
+    pub slow: bool,
+
+    /// Scenario is quarantined, its failure is reported as `quarantined-failed`.
+    pub quarantined: bool,
+
+    /// Resource usage of the scenario, `None` if not collected.
+    pub resource_usage: Option<ResourceUsage>,
+
+    /// Metrics recorded by the scenario, see [`metrics_json`].
+    pub metrics: Vec<(String, f64)>,
+}
+
+/// Create detailed CSV report with scenario timings, inputs, deprecation and slowness flags.
+/// Columns: `name,status,duration_us,error,input,deprecated,slow`.
+/// If resource usage was collected for any entry, `max_rss_bytes,cpu_time_us` columns are added.
+/// If any entry recorded metrics, `metrics` column with JSON object of metrics is added.
+/// Error column is empty for passed and skipped scenarios, resource columns are empty if unknown.
+/// Failures of quarantined scenarios have `quarantined-failed` status.
+///
+/// * `entries` - Scenario entries.
+pub fn to_csv_entries(entries: &[CsvEntry]) -> String {
+    let resource_columns = entries.iter().any(|entry| entry.resource_usage.is_some());
+    let metrics_column = entries.iter().any(|entry| !entry.metrics.is_empty());
+    let mut csv = String::from("name,status,duration_us,error,input,deprecated,slow");
+    if resource_columns {
+        csv.push_str(",max_rss_bytes,cpu_time_us");
+    }
+    if metrics_column {
+        csv.push_str(",metrics");
+    }
+    csv.push('\n');
+    for entry in entries {
+        let status = if entry.quarantined && !entry.outcome.is_ok() {
+            "quarantined-failed"
+        } else {
+            entry.outcome.status()
+        };
+        csv.push_str(&csv_row(
+            &entry.name,
+            &entry.outcome,
+            status,
+            entry.duration,
+        ));
+        csv.push_str(&format!(
+            ",{},{},{}",
+            csv_quote(&entry.input),
+            entry.deprecated,
+            entry.slow
+        ));
+        if resource_columns {
+            let usage = entry.resource_usage.unwrap_or_default();
+            let max_rss = usage.max_rss_bytes.map(|rss| rss.to_string());
+            let cpu_time = usage.cpu_time.map(|time| time.as_micros().to_string());
+            csv.push_str(&format!(
+                ",{},{}",
+                max_rss.unwrap_or_default(),
+                cpu_time.unwrap_or_default()
+            ));
+        }
+        if metrics_column {
+            let metrics = metrics_json(&entry.metrics).to_string();
+            csv.push_str(&format!(",{}", csv_quote(&metrics)));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Create CSV row with `name,status,duration_us,error` columns, without line end.
+fn csv_row(name: &str, outcome: &Outcome, status: &str, duration: Duration) -> String {
+    let error = if outcome.is_ok() {
+        String::new()
+    } else {
+        outcome.to_string()
+    };
+    format!(
+        "{},{},{},{}",
+        csv_quote(name),
+        status,
+        duration.as_micros(),
+        csv_quote(&error)
+    )
+}
+
+/// Escape text for XML attribute values and character data.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ if c.is_control() && c != '\t' => (),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Create JUnit XML report.
+/// Scenario group is used as test case class name, timed out and panicked scenarios are failures.
+///
+/// * `results` - Scenario names with outcomes and execution times.
+pub fn to_junit(results: &[(String, Outcome, Duration)]) -> String {
+    let failures = results
+        .iter()
+        .filter(|(_, outcome, _)| !outcome.is_ok())
+        .count();
+    let skipped = results
+        .iter()
+        .filter(|(_, outcome, _)| matches!(outcome, Outcome::Skipped(_)))
+        .count();
+    let time: Duration = results.iter().map(|(_, _, duration)| *duration).sum();
+    let counts = format!(
+        "tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\" time=\"{:.6}\"",
+        results.len(),
+        time.as_secs_f64()
+    );
+
+    let mut junit = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    junit.push_str(&format!("<testsuites {counts}>\n"));
+    junit.push_str(&format!("  <testsuite name=\"test_scenarios\" {counts}>\n"));
+    for (name, outcome, duration) in results {
+        let (class_name, case_name) = name.rsplit_once('.').unwrap_or(("", name));
+        let case = format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.6}\"",
+            xml_escape(case_name),
+            xml_escape(class_name),
+            duration.as_secs_f64()
+        );
+        match outcome {
+            Outcome::Passed => junit.push_str(&format!("{case}/>\n")),
+            Outcome::Skipped(reason) => junit.push_str(&format!(
+                "{case}>\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                xml_escape(reason)
+            )),
+            _ => junit.push_str(&format!(
+                "{case}>\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                xml_escape(&outcome.to_string())
+            )),
+        }
+    }
+    junit.push_str("  </testsuite>\n</testsuites>\n");
+    junit
+}
+
+/// Create JSON object of scenario metrics, keyed by metric name.
+/// Later values override earlier values of the same metric.
+///
+/// * `metrics` - Metrics recorded by the scenario, in order of recording.
+pub fn metrics_json(metrics: &[(String, f64)]) -> Value {
+    let mut object = Map::new();
+    for (key, value) in metrics {
+        object.insert(key.clone(), json!(value));
+    }
+    Value::Object(object)
+}
+
+/// Outcome counts of scenarios with a tag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TagCounts {
+    /// Number of passed scenarios.
+    pub passed: usize,
+
+    /// Number of failed scenarios, including timed out and panicked ones.
+    pub failed: usize,
+
+    /// Number of skipped scenarios.
+    pub skipped: usize,
+}
+
+/// Count outcomes of scenarios per tag, ordered by tag.
+/// Scenario with multiple tags is counted under each, scenarios without tags are not counted.
+///
+/// * `results` - Scenario names with outcomes.
+/// * `tags` - Tags of scenario with given name.
+pub fn summary_by_tag(
+    results: &[(String, Outcome)],
+    tags: impl Fn(&str) -> Vec<String>,
+) -> BTreeMap<String, TagCounts> {
+    let mut summary: BTreeMap<String, TagCounts> = BTreeMap::new();
+    for (name, outcome) in results {
+        for tag in tags(name) {
+            let counts = summary.entry(tag).or_default();
+            match outcome {
+                Outcome::Passed => counts.passed += 1,
+                Outcome::Skipped(_) => counts.skipped += 1,
+                _ => counts.failed += 1,
+            }
+        }
+    }
+    summary
+}
+
+/// Format per-tag summary, one line per tag, e.g., `smoke: 2 passed, 1 failed, 0 skipped`.
+///
+/// * `summary` - Outcome counts per tag.
+pub fn format_summary_by_tag(summary: &BTreeMap<String, TagCounts>) -> String {
+    summary
+        .iter()
+        .map(|(tag, counts)| {
+            format!(
+                "{tag}: {} passed, {} failed, {} skipped\n",
+                counts.passed, counts.failed, counts.skipped
+            )
+        })
+        .collect()
+}
+
+/// Format of scenario failure lines printed to stderr.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorAnnotation {
+    /// Plain text.
+    #[default]
+    Plain,
+
+    /// GitHub Actions workflow command, shown as inline annotation.
+    Github,
+
+    /// JSON object per line.
+    Json,
+}
+
+impl FromStr for ErrorAnnotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(ErrorAnnotation::Plain),
+            "github" => Ok(ErrorAnnotation::Github),
+            "json" => Ok(ErrorAnnotation::Json),
+            _ => Err(format!("Invalid error annotation: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for ErrorAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorAnnotation::Plain => f.write_str("plain"),
+            ErrorAnnotation::Github => f.write_str("github"),
+            ErrorAnnotation::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// Escape GitHub Actions workflow command data.
+fn github_escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape GitHub Actions workflow command property value.
+fn github_escape_property(value: &str) -> String {
+    github_escape_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Create failure line of a scenario.
+///
+/// * `annotation` - Failure line format.
+/// * `name` - Name of the scenario.
+/// * `message` - Failure message.
+pub fn format_failure(annotation: ErrorAnnotation, name: &str, message: &str) -> String {
+    match annotation {
+        ErrorAnnotation::Plain => format!("Scenario {name} failed: {message}"),
+        ErrorAnnotation::Github => format!(
+            "::error title={}::{}",
+            github_escape_property(name),
+            github_escape_data(message)
+        ),
+        ErrorAnnotation::Json => json!({
+            "scenario": name,
+            "message": message,
+        })
+        .to_string(),
+    }
+}
+
+/// Create marker line printed before scenario is run.
+///
+/// * `name` - Name of the scenario.
+pub fn scenario_start_marker(name: &str) -> String {
+    format!("##SCENARIO_START name={name}")
+}
+
+/// Create marker line printed before scenario is run, including its input.
+/// Input is printed as JSON string.
+///
+/// * `name` - Name of the scenario.
+/// * `input` - Test scenario input, e.g. truncated with [`truncate_input`].
+pub fn scenario_start_marker_with_input(name: &str, input: &str) -> String {
+    format!(
+        "{} input={}",
+        scenario_start_marker(name),
+        Value::String(input.to_string())
+    )
+}
+
+/// Truncate test scenario input for reporting.
+/// Truncated input ends with `...`.
+///
+/// * `input` - Test scenario input.
+/// * `max_chars` - Maximum number of reported characters.
+pub fn truncate_input(input: &str, max_chars: usize) -> String {
+    match input.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}...", &input[..index]),
+        None => input.to_string(),
+    }
+}
+
+/// Create marker line printed after scenario finished.
+///
+/// * `name` - Name of the scenario.
+/// * `status` - Scenario status, e.g. `Outcome::status()`.
+/// * `duration` - Scenario execution time.
+pub fn scenario_end_marker(name: &str, status: &str, duration: Duration) -> String {
+    format!(
+        "##SCENARIO_END name={name} status={status} duration_us={}",
+        duration.as_micros()
+    )
+}
+
+/// Flakiness report aggregated from repeated runs of a single scenario.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FlakinessReport {
+    /// Number of runs.
+    pub runs: usize,
+
+    /// Number of passed runs.
+    /// Skipped runs are considered passed.
+    pub passes: usize,
+
+    /// Number of failed runs.
+    pub failures: usize,
+
+    /// Distinct error messages, in order of first occurrence.
+    pub distinct_errors: Vec<String>,
+}
+
+impl FlakinessReport {
+    /// Create flakiness report from outcomes of repeated runs.
+    ///
+    /// * `outcomes` - Outcomes of repeated runs.
+    pub fn from_outcomes(outcomes: &[Outcome]) -> Self {
+        let mut report = FlakinessReport {
+            runs: outcomes.len(),
+            ..Default::default()
+        };
+        for outcome in outcomes {
+            if outcome.is_ok() {
+                report.passes += 1;
+            } else {
+                report.failures += 1;
+                let error = outcome.to_string();
+                if !report.distinct_errors.contains(&error) {
+                    report.distinct_errors.push(error);
+                }
+            }
+        }
+        report
+    }
+
+    /// Ratio of passed runs to all runs.
+    /// Zero if there were no runs.
+    pub fn pass_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.passes as f64 / self.runs as f64
+        }
+    }
+
+    /// Serialize report to JSON.
+    pub fn to_json(&self) -> String {
+        json!({
+            "runs": self.runs,
+            "passes": self.passes,
+            "failures": self.failures,
+            "pass_rate": self.pass_rate(),
+            "distinct_errors": self.distinct_errors,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outcome::Outcome;
+    use crate::report::{
+        format_failure, format_summary_by_tag, metrics_json, scenario_end_marker,
+        scenario_start_marker, scenario_start_marker_with_input, summary_by_tag, tap_failed_runs,
+        tap_failures, to_csv, to_csv_entries, to_junit, to_tap, to_tap_aborted, to_tap_entries,
+        truncate_input, CsvEntry, ErrorAnnotation, FlakinessReport, TagCounts, TapEntry,
+    };
+    use crate::resource_usage::ResourceUsage;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_to_tap_empty() {
+        let tap = to_tap(&[]);
+        assert_eq!(tap, "TAP version 13\n1..0\n");
+    }
+
+    #[test]
+    fn test_tap_failures() {
+        let results = vec![
+            ("first".to_string(), Ok(())),
+            (
+                "group.second".to_string(),
+                Err("Failed - badly".to_string()),
+            ),
+            ("third".to_string(), Ok(())),
+            ("group.second".to_string(), Err("Failed again".to_string())),
+        ];
+        let tap = to_tap_aborted(&results, "Input missing");
+        assert_eq!(tap_failures(&tap), vec!["group.second".to_string()]);
+    }
+
+    #[test]
+    fn test_to_tap_entries() {
+        let entry = |name: &str, outcome: Outcome, not_run: bool| TapEntry {
+            name: name.to_string(),
+            outcome,
+            not_run,
+            input: Some(format!("{name} \"input\"")),
+        };
+        let entries = vec![
+            entry("passed", Outcome::Passed, false),
+            entry("disabled", Outcome::Skipped("Disabled".to_string()), false),
+            entry("not_run", Outcome::Skipped("Stopped".to_string()), true),
+            entry("failed", Outcome::Failed("Error".to_string()), false),
+        ];
+        let tap = to_tap_entries(&entries, Some("Aborted"));
+
+        let expected = "TAP version 13\n\
+                        1..4\n\
+                        ok 1 - passed\n\
+                        ok 2 - disabled # SKIP Disabled\n\
+                        not ok 3 - not_run # SKIP Stopped\n  \
+                        ---\n  \
+                        input: \"not_run \\\"input\\\"\"\n  \
+                        ...\n\
+                        not ok 4 - failed\n  \
+                        ---\n  \
+                        message: \"Error\"\n  \
+                        input: \"failed \\\"input\\\"\"\n  \
+                        ...\n\
+                        Bail out! Aborted\n";
+        assert_eq!(tap, expected);
+        assert_eq!(
+            tap_failed_runs(&tap),
+            vec![
+                ("not_run".to_string(), Some("not_run \"input\"".to_string())),
+                ("failed".to_string(), Some("failed \"input\"".to_string())),
+            ]
+        );
+        assert_eq!(tap_failures(&tap), vec!["not_run", "failed"]);
+    }
+
+    #[test]
+    fn test_to_tap_aborted() {
+        let results = vec![("first".to_string(), Ok(()))];
+        let tap = to_tap_aborted(&results, "Input missing");
+        assert_eq!(
+            tap,
+            "TAP version 13\n1..1\nok 1 - first\nBail out! Input missing\n"
+        );
+    }
+
+    #[test]
+    fn test_to_tap_plan_line() {
+        let results = vec![
+            ("group.first".to_string(), Ok(())),
+            ("group.second".to_string(), Ok(())),
+            ("third".to_string(), Ok(())),
+        ];
+        let tap = to_tap(&results);
+
+        let lines: Vec<&str> = tap.lines().collect();
+        assert_eq!(lines[0], "TAP version 13");
+        assert_eq!(lines[1], "1..3");
+        assert_eq!(lines[2], "ok 1 - group.first");
+        assert_eq!(lines[3], "ok 2 - group.second");
+        assert_eq!(lines[4], "ok 3 - third");
+    }
+
+    #[test]
+    fn test_to_tap_failure_diagnostic() {
+        let results = vec![
+            ("passing".to_string(), Ok(())),
+            (
+                "failing".to_string(),
+                Err("Requested \"error\"\nsecond line".to_string()),
+            ),
+        ];
+        let tap = to_tap(&results);
+
+        let expected = "TAP version 13\n\
+                        1..2\n\
+                        ok 1 - passing\n\
+                        not ok 2 - failing\n  \
+                        ---\n  \
+                        message: \"Requested \\\"error\\\"\\nsecond line\"\n  \
+                        ...\n";
+        assert_eq!(tap, expected);
+    }
+
+    #[test]
+    fn test_to_csv_rows() {
+        let results = vec![
+            (
+                "group.passing".to_string(),
+                Outcome::Passed,
+                Duration::from_micros(150),
+            ),
+            (
+                "skipped".to_string(),
+                Outcome::Skipped("Not supported".to_string()),
+                Duration::ZERO,
+            ),
+            (
+                "timed_out".to_string(),
+                Outcome::TimedOut,
+                Duration::from_millis(3),
+            ),
+        ];
+        let csv = to_csv(&results);
+
+        let expected = "name,status,duration_us,error\n\
+                        group.passing,passed,150,\n\
+                        skipped,skipped,0,\n\
+                        timed_out,timed_out,3000,Scenario timed out\n";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn test_to_junit() {
+        let results = vec![
+            (
+                "group.passing".to_string(),
+                Outcome::Passed,
+                Duration::from_micros(1500),
+            ),
+            (
+                "skipped".to_string(),
+                Outcome::Skipped("Not <supported>".to_string()),
+                Duration::ZERO,
+            ),
+            (
+                "group.failing".to_string(),
+                Outcome::Failed("Expected \"a\" & \"b\"".to_string()),
+                Duration::from_micros(500),
+            ),
+        ];
+        let junit = to_junit(&results);
+
+        let counts = "tests=\"3\" failures=\"1\" skipped=\"1\" time=\"0.002000\"";
+        let expected = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites {counts}>\n  <testsuite name=\"test_scenarios\" {counts}>\n    \
+             <testcase name=\"passing\" classname=\"group\" time=\"0.001500\"/>\n    \
+             <testcase name=\"skipped\" classname=\"\" time=\"0.000000\">\n      \
+             <skipped message=\"Not &lt;supported&gt;\"/>\n    </testcase>\n    \
+             <testcase name=\"failing\" classname=\"group\" time=\"0.000500\">\n      \
+             <failure message=\"Expected &quot;a&quot; &amp; &quot;b&quot;\"/>\n    </testcase>\n  \
+             </testsuite>\n</testsuites>\n"
+        );
+        assert_eq!(junit, expected);
+    }
+
+    #[test]
+    fn test_to_csv_quoting() {
+        let results = vec![
+            (
+                "commas".to_string(),
+                Outcome::Failed("expected 1, got 2".to_string()),
+                Duration::from_micros(1),
+            ),
+            (
+                "quotes".to_string(),
+                Outcome::Failed("Requested \"error\"".to_string()),
+                Duration::from_micros(2),
+            ),
+            (
+                "newlines".to_string(),
+                Outcome::Failed("first line\nsecond line".to_string()),
+                Duration::from_micros(3),
+            ),
+        ];
+        let csv = to_csv(&results);
+
+        let expected = "name,status,duration_us,error\n\
+                        commas,failed,1,\"expected 1, got 2\"\n\
+                        quotes,failed,2,\"Requested \"\"error\"\"\"\n\
+                        newlines,failed,3,\"first line\nsecond line\"\n";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn test_metrics_json() {
+        let metrics = vec![
+            ("latency_ms".to_string(), 1.5),
+            ("count".to_string(), 2.0),
+            ("latency_ms".to_string(), 3.0),
+        ];
+
+        assert_eq!(
+            metrics_json(&metrics),
+            serde_json::json!({"latency_ms": 3.0, "count": 2.0})
+        );
+        assert_eq!(metrics_json(&[]), serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_error_annotation_from_str() {
+        assert_eq!(
+            ErrorAnnotation::from_str("github"),
+            Ok(ErrorAnnotation::Github)
+        );
+        assert!(
+            ErrorAnnotation::from_str("xml").is_err_and(|e| e == "Invalid error annotation: xml")
+        );
+    }
+
+    #[test]
+    fn test_format_failure_plain() {
+        let line = format_failure(ErrorAnnotation::Plain, "group.scenario", "Requested error");
+        assert_eq!(line, "Scenario group.scenario failed: Requested error");
+    }
+
+    #[test]
+    fn test_format_failure_github() {
+        let line = format_failure(
+            ErrorAnnotation::Github,
+            "group.scenario",
+            "Expected 1, got 2\n100% wrong",
+        );
+        assert_eq!(
+            line,
+            "::error title=group.scenario::Expected 1, got 2%0A100%25 wrong"
+        );
+
+        let line = format_failure(ErrorAnnotation::Github, "a:b,c", "error");
+        assert_eq!(line, "::error title=a%3Ab%2Cc::error");
+    }
+
+    #[test]
+    fn test_format_failure_json() {
+        let line = format_failure(
+            ErrorAnnotation::Json,
+            "group.scenario",
+            "Requested \"error\"",
+        );
+        assert_eq!(
+            line,
+            r#"{"message":"Requested \"error\"","scenario":"group.scenario"}"#
+        );
+    }
+
+    #[test]
+    fn test_scenario_start_marker() {
+        assert_eq!(
+            scenario_start_marker("group.scenario"),
+            "##SCENARIO_START name=group.scenario"
+        );
+    }
+
+    #[test]
+    fn test_scenario_start_marker_with_input() {
+        assert_eq!(
+            scenario_start_marker_with_input("group.scenario", "{\"key\": \"value\"}"),
+            "##SCENARIO_START name=group.scenario input=\"{\\\"key\\\": \\\"value\\\"}\""
+        );
+    }
+
+    #[test]
+    fn test_truncate_input() {
+        assert_eq!(truncate_input("short", 5), "short");
+        assert_eq!(truncate_input("longer input", 6), "longer...");
+        // Truncated on character boundary.
+        assert_eq!(truncate_input("żółw", 2), "żó...");
+        assert_eq!(truncate_input("input", 0), "...");
+    }
+
+    #[test]
+    fn test_to_csv_entries() {
+        let entries = [CsvEntry {
+            name: "scenario".to_string(),
+            outcome: Outcome::Passed,
+            duration: Duration::from_micros(5),
+            input: "a,b".to_string(),
+            deprecated: true,
+            slow: false,
+            quarantined: false,
+            resource_usage: None,
+            metrics: Vec::new(),
+        }];
+        assert_eq!(
+            to_csv_entries(&entries),
+            "name,status,duration_us,error,input,deprecated,slow\n\
+             scenario,passed,5,,\"a,b\",true,false\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_entries_resource_usage() {
+        let entry = CsvEntry {
+            name: "scenario".to_string(),
+            outcome: Outcome::Passed,
+            duration: Duration::from_micros(5),
+            input: String::new(),
+            deprecated: false,
+            slow: true,
+            quarantined: false,
+            resource_usage: Some(ResourceUsage {
+                max_rss_bytes: Some(4096),
+                cpu_time: Some(Duration::from_micros(3)),
+            }),
+            metrics: Vec::new(),
+        };
+        let entries = [
+            entry.clone(),
+            CsvEntry {
+                name: "other".to_string(),
+                resource_usage: None,
+                ..entry
+            },
+        ];
+        assert_eq!(
+            to_csv_entries(&entries),
+            "name,status,duration_us,error,input,deprecated,slow,max_rss_bytes,cpu_time_us\n\
+             scenario,passed,5,,,false,true,4096,3\n\
+             other,passed,5,,,false,true,,\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_entries_metrics() {
+        let entry = CsvEntry {
+            name: "scenario".to_string(),
+            outcome: Outcome::Passed,
+            duration: Duration::from_micros(5),
+            input: String::new(),
+            deprecated: false,
+            slow: false,
+            quarantined: false,
+            resource_usage: None,
+            metrics: vec![("latency_ms".to_string(), 1.5), ("count".to_string(), 2.0)],
+        };
+        let entries = [
+            entry.clone(),
+            CsvEntry {
+                name: "other".to_string(),
+                metrics: Vec::new(),
+                ..entry
+            },
+        ];
+        assert_eq!(
+            to_csv_entries(&entries),
+            "name,status,duration_us,error,input,deprecated,slow,metrics\n\
+             scenario,passed,5,,,false,false,\"{\"\"count\"\":2.0,\"\"latency_ms\"\":1.5}\"\n\
+             other,passed,5,,,false,false,{}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_entries_quarantined() {
+        let entry = CsvEntry {
+            name: "scenario".to_string(),
+            outcome: Outcome::Failed("Requested error".to_string()),
+            duration: Duration::from_micros(5),
+            input: String::new(),
+            deprecated: false,
+            slow: false,
+            quarantined: true,
+            resource_usage: None,
+            metrics: Vec::new(),
+        };
+        let entries = [
+            entry.clone(),
+            CsvEntry {
+                name: "passed".to_string(),
+                outcome: Outcome::Passed,
+                ..entry
+            },
+        ];
+        assert_eq!(
+            to_csv_entries(&entries),
+            "name,status,duration_us,error,input,deprecated,slow\n\
+             scenario,quarantined-failed,5,Requested error,,false,false\n\
+             passed,passed,5,,,false,false\n"
+        );
+    }
+
+    #[test]
+    fn test_scenario_end_marker_passed() {
+        let marker = scenario_end_marker(
+            "group.scenario",
+            Outcome::Passed.status(),
+            Duration::from_micros(123),
+        );
+        assert_eq!(
+            marker,
+            "##SCENARIO_END name=group.scenario status=passed duration_us=123"
+        );
+    }
+
+    #[test]
+    fn test_scenario_end_marker_failed() {
+        let outcome = Outcome::Failed("Requested error".to_string());
+        let marker = scenario_end_marker("scenario", outcome.status(), Duration::from_millis(2));
+        assert_eq!(
+            marker,
+            "##SCENARIO_END name=scenario status=failed duration_us=2000"
+        );
+    }
+
+    #[test]
+    fn test_scenario_end_marker_timed_out() {
+        let marker = scenario_end_marker(
+            "scenario",
+            Outcome::TimedOut.status(),
+            Duration::from_secs(1),
+        );
+        assert_eq!(
+            marker,
+            "##SCENARIO_END name=scenario status=timed_out duration_us=1000000"
+        );
+    }
+
+    #[test]
+    fn test_flakiness_report_from_outcomes() {
+        let outcomes = vec![
+            Outcome::Passed,
+            Outcome::Failed("First error".to_string()),
+            Outcome::Passed,
+            Outcome::TimedOut,
+            Outcome::Failed("First error".to_string()),
+        ];
+        let report = FlakinessReport::from_outcomes(&outcomes);
+
+        assert_eq!(report.runs, 5);
+        assert_eq!(report.passes, 2);
+        assert_eq!(report.failures, 3);
+        assert_eq!(
+            report.distinct_errors,
+            vec!["First error".to_string(), "Scenario timed out".to_string()]
+        );
+        assert_eq!(report.pass_rate(), 0.4);
+    }
+
+    #[test]
+    fn test_flakiness_report_empty() {
+        let report = FlakinessReport::from_outcomes(&[]);
+
+        assert_eq!(report, FlakinessReport::default());
+        assert_eq!(report.pass_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_flakiness_report_to_json() {
+        let outcomes = vec![Outcome::Passed, Outcome::Failed("Error".to_string())];
+        let report = FlakinessReport::from_outcomes(&outcomes);
+        let value: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(value["runs"], 2);
+        assert_eq!(value["passes"], 1);
+        assert_eq!(value["failures"], 1);
+        assert_eq!(value["pass_rate"], 0.5);
+        assert_eq!(value["distinct_errors"], serde_json::json!(["Error"]));
+    }
+
+    #[test]
+    fn test_summary_by_tag() {
+        let results = [
+            ("first".to_string(), Outcome::Passed),
+            (
+                "second".to_string(),
+                Outcome::Failed("Requested error".to_string()),
+            ),
+            (
+                "third".to_string(),
+                Outcome::Skipped("Disabled".to_string()),
+            ),
+            ("untagged".to_string(), Outcome::TimedOut),
+        ];
+        let tags = |name: &str| match name {
+            "first" => vec!["smoke".to_string(), "fast".to_string()],
+            "second" => vec!["smoke".to_string()],
+            "third" => vec!["fast".to_string()],
+            _ => vec![],
+        };
+
+        let summary = summary_by_tag(&results, tags);
+
+        assert_eq!(
+            summary.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    "fast".to_string(),
+                    TagCounts {
+                        passed: 1,
+                        failed: 0,
+                        skipped: 1
+                    }
+                ),
+                (
+                    "smoke".to_string(),
+                    TagCounts {
+                        passed: 1,
+                        failed: 1,
+                        skipped: 0
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_summary_by_tag() {
+        let results = [
+            ("first".to_string(), Outcome::Passed),
+            ("second".to_string(), Outcome::Panicked("boom".to_string())),
+        ];
+        let summary = summary_by_tag(&results, |_| vec!["smoke".to_string()]);
+
+        assert_eq!(
+            format_summary_by_tag(&summary),
+            "smoke: 1 passed, 1 failed, 0 skipped\n"
+        );
+        assert_eq!(format_summary_by_tag(&Default::default()), "");
+    }
+}
@@ -0,0 +1,162 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Process resource usage, collected with `getrusage` and `wait4` on Unix.
+//!
+//! Scenarios run in-process share the runner process, so their usage is measured on the whole process:
+//! CPU time is the difference over the run, including other scenarios run concurrently,
+//! and maximum resident set size is the peak of the process so far, not of the scenario alone.
+//! Usage of isolated scenarios is measured on their runner subprocess.
+use std::time::Duration;
+
+/// Resource usage of a process.
+/// Fields are `None` on platforms where they cannot be collected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Peak resident set size of the process in bytes, since the process started.
+    pub max_rss_bytes: Option<u64>,
+
+    /// CPU time spent in user and system mode.
+    pub cpu_time: Option<Duration>,
+}
+
+impl ResourceUsage {
+    /// Resource usage of the current process so far.
+    #[cfg(unix)]
+    pub fn current() -> Self {
+        // SAFETY: `rusage` is plain data, zeroed value is valid and fully written by `getrusage`.
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        // SAFETY: `usage` is a valid pointer to `rusage`.
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+            return ResourceUsage::default();
+        }
+        ResourceUsage::from_rusage(&usage)
+    }
+
+    /// Resource usage reported by `getrusage` or `wait4`.
+    ///
+    /// * `usage` - Reported resource usage.
+    #[cfg(unix)]
+    pub(crate) fn from_rusage(usage: &libc::rusage) -> Self {
+        let to_duration = |time: libc::timeval| {
+            Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+        };
+        // Maximum resident set size is reported in bytes on Apple platforms, in kilobytes otherwise.
+        let rss_unit = if cfg!(target_vendor = "apple") {
+            1
+        } else {
+            1024
+        };
+        ResourceUsage {
+            max_rss_bytes: Some(usage.ru_maxrss as u64 * rss_unit),
+            cpu_time: Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime)),
+        }
+    }
+
+    /// Resource usage of the current process so far.
+    #[cfg(not(unix))]
+    pub fn current() -> Self {
+        ResourceUsage::default()
+    }
+
+    /// Resource usage of two consecutive runs, e.g., of repeated isolated runs.
+    /// CPU times are summed, maximum resident set size is the higher of both.
+    ///
+    /// * `other` - Resource usage of the other run.
+    pub fn combine(&self, other: &ResourceUsage) -> ResourceUsage {
+        let cpu_time = match (self.cpu_time, other.cpu_time) {
+            (Some(first), Some(second)) => Some(first + second),
+            (first, second) => first.or(second),
+        };
+        ResourceUsage {
+            max_rss_bytes: self.max_rss_bytes.max(other.max_rss_bytes),
+            cpu_time,
+        }
+    }
+
+    /// Resource usage between `start` and this usage.
+    /// CPU time is the difference, maximum resident set size is the process-wide peak.
+    ///
+    /// * `start` - Resource usage at the start of the measured period.
+    pub fn since(&self, start: &ResourceUsage) -> ResourceUsage {
+        let cpu_time = match (self.cpu_time, start.cpu_time) {
+            (Some(end), Some(start)) => Some(end.saturating_sub(start)),
+            _ => None,
+        };
+        ResourceUsage {
+            max_rss_bytes: self.max_rss_bytes,
+            cpu_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resource_usage::ResourceUsage;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_since() {
+        let start = ResourceUsage {
+            max_rss_bytes: Some(1000),
+            cpu_time: Some(Duration::from_millis(10)),
+        };
+        let end = ResourceUsage {
+            max_rss_bytes: Some(2000),
+            cpu_time: Some(Duration::from_millis(25)),
+        };
+
+        let usage = end.since(&start);
+
+        assert_eq!(usage.max_rss_bytes, Some(2000));
+        assert_eq!(usage.cpu_time, Some(Duration::from_millis(15)));
+        assert_eq!(end.since(&ResourceUsage::default()).cpu_time, None);
+    }
+
+    #[test]
+    fn test_combine() {
+        let first = ResourceUsage {
+            max_rss_bytes: Some(2000),
+            cpu_time: Some(Duration::from_millis(10)),
+        };
+        let second = ResourceUsage {
+            max_rss_bytes: Some(1000),
+            cpu_time: Some(Duration::from_millis(25)),
+        };
+
+        let usage = first.combine(&second);
+
+        assert_eq!(usage.max_rss_bytes, Some(2000));
+        assert_eq!(usage.cpu_time, Some(Duration::from_millis(35)));
+        assert_eq!(ResourceUsage::default().combine(&first), first);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_current_populated() {
+        let start = ResourceUsage::current();
+        // Allocate and touch memory, then busy-loop briefly.
+        let buffer = vec![1u8; 16 * 1024 * 1024];
+        let busy_start = Instant::now();
+        let mut sum = 0u64;
+        while busy_start.elapsed() < Duration::from_millis(50) {
+            sum =
+                sum.wrapping_add(std::hint::black_box(buffer[sum as usize % buffer.len()]) as u64);
+        }
+        std::hint::black_box(sum);
+
+        let usage = ResourceUsage::current().since(&start);
+
+        assert!(usage.max_rss_bytes.is_some_and(|rss| rss > 0));
+        assert!(usage.cpu_time.is_some_and(|cpu_time| !cpu_time.is_zero()));
+    }
+}
@@ -10,8 +10,17 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 // *******************************************************************************
+use crate::cancellation::CancellationToken;
+use crate::error::error_chain;
+use crate::scenario_env::ScenarioEnv;
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
 /// Scenario definition.
-pub trait Scenario {
+///
+/// Scenarios must be `Send + Sync`, as they might be run from multiple threads.
+pub trait Scenario: Send + Sync {
     /// Get scenario name.
     fn name(&self) -> &str;
 
@@ -19,10 +28,229 @@ pub trait Scenario {
     ///
     /// * `input` - Test scenario input.
     fn run(&self, input: &str) -> Result<(), String>;
+
+    /// Run test scenario returning error with sources.
+    /// Error is reported with its full chain of sources.
+    /// Calls [`Scenario::run`] by default.
+    ///
+    /// * `input` - Test scenario input.
+    fn run_err(&self, input: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.run(input).map_err(Into::into)
+    }
+
+    /// Run test scenario producing structured output.
+    /// Calls [`Scenario::run`] and returns `Value::Null` on success by default.
+    ///
+    /// * `input` - Test scenario input.
+    fn run_output(&self, input: &str) -> Result<Value, String> {
+        self.run(input).map(|_| Value::Null)
+    }
+
+    /// Run test scenario producing structured output, with access to execution environment.
+    /// Used when output is requested, e.g., with `--emit-output`, instead of [`Scenario::run_with_env`].
+    /// Calls [`Scenario::run_output`] by default.
+    ///
+    /// * `env` - Scenario execution environment.
+    fn run_output_with_env(&self, env: &ScenarioEnv) -> Result<Value, String> {
+        self.run_output(env.input())
+    }
+
+    /// Run test scenario with cooperative cancellation.
+    /// Token is cancelled when scenario timeout is exceeded.
+    /// Long-running scenarios should override this method and return early once
+    /// [`CancellationToken::is_cancelled`] is set. Calls [`Scenario::run_err`] by default.
+    ///
+    /// * `input` - Test scenario input.
+    /// * `token` - Cancellation token.
+    fn run_with_cancel(&self, input: &str, _token: &CancellationToken) -> Result<(), String> {
+        self.run_err(input)
+            .map_err(|error| error_chain(error.as_ref()))
+    }
+
+    /// Run test scenario with access to execution environment.
+    /// Environment provides input, cancellation token and a temporary directory
+    /// removed after the run. Calls [`Scenario::run_with_cancel`] by default.
+    ///
+    /// * `env` - Scenario execution environment.
+    fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+        self.run_with_cancel(env.input(), env.cancellation_token())
+    }
+
+    /// Check whether test scenario should be skipped.
+    /// Returns skip reason, or `None` if scenario should be run.
+    ///
+    /// * `input` - Test scenario input.
+    fn skip_reason(&self, _input: &str) -> Option<String> {
+        None
+    }
+
+    /// Default scenario execution timeout.
+    /// Overridden by timeout provided on run (e.g., `--timeout` CLI argument).
+    /// `None` means unlimited execution time.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Expected maximum execution time.
+    /// Slower runs still pass, but a warning is logged and result is reported as slow.
+    /// `None` means no expectation.
+    fn slow_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Scenario tags, used for selection.
+    fn tags(&self) -> &[String] {
+        &[]
+    }
+
+    /// Deprecation hint, e.g., name of the replacing scenario.
+    /// Deprecated scenarios are run normally, but a warning is logged.
+    /// `None` if scenario is not deprecated.
+    fn deprecated(&self) -> Option<&str> {
+        None
+    }
+
+    /// Execution priority, scenarios with higher priority are run first.
+    /// Used only when scenarios of a group or matching a pattern are run,
+    /// explicit lists, e.g., names or playlists, keep their order.
+    /// Order of equal priorities is kept.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Alternative scenario names, e.g., names used before rename.
+    /// Scenario can be found by alias, but is listed only by its name.
+    fn aliases(&self) -> &[String] {
+        &[]
+    }
+
+    /// Human-readable scenario description.
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// JSON schema of expected test scenario input.
+    /// Input is validated before run if schema is provided, see [`crate::schema`].
+    fn input_schema(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl fmt::Debug for dyn Scenario + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scenario")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+/// Scenario defined by a name and a function running it.
+pub struct FnScenario<F> {
+    name: String,
+    run: F,
+}
+
+impl<F> FnScenario<F>
+where
+    F: Fn(&str) -> Result<(), String> + Send + Sync,
+{
+    /// Create scenario running provided function.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `run` - Function running the scenario with provided input.
+    pub fn new(name: &str, run: F) -> Self {
+        FnScenario {
+            name: name.to_string(),
+            run,
+        }
+    }
+}
+
+impl<F> Scenario for FnScenario<F>
+where
+    F: Fn(&str) -> Result<(), String> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, input: &str) -> Result<(), String> {
+        (self.run)(input)
+    }
+}
+
+/// Scenario registered before it is implemented.
+/// Listed as any other scenario, fails when run.
+pub struct PlaceholderScenario {
+    name: String,
+}
+
+impl PlaceholderScenario {
+    /// Create placeholder scenario.
+    ///
+    /// * `name` - Name of the scenario.
+    pub fn new(name: &str) -> Self {
+        PlaceholderScenario {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Scenario for PlaceholderScenario {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, _input: &str) -> Result<(), String> {
+        Err("not implemented".to_string())
+    }
+
+    fn description(&self) -> &str {
+        "Placeholder, not implemented yet"
+    }
+}
+
+/// Policy shared by all scenarios of a group, including scenarios of nested groups.
+///
+/// Precedence, from highest to lowest:
+/// 1. Run options, e.g., `--timeout` and `--retries` CLI arguments.
+/// 2. Scenario-level settings, e.g., [`Scenario::timeout`].
+/// 3. Policy of the innermost group, then policies of its ancestors.
+/// 4. Defaults - unlimited timeout, no retries.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupPolicy {
+    /// Maximum execution time of each scenario.
+    pub timeout: Option<Duration>,
+
+    /// Number of times a failed scenario is run again.
+    pub retries: Option<usize>,
+
+    /// Tags added to the group tags.
+    pub tags: Vec<String>,
+}
+
+impl GroupPolicy {
+    /// Combine this policy with policy of a parent group.
+    /// Fields set in this policy take precedence, tags are combined.
+    ///
+    /// * `parent` - Policy of the parent group.
+    pub fn inherit(&self, parent: &GroupPolicy) -> GroupPolicy {
+        let mut tags = parent.tags.clone();
+        for tag in &self.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        GroupPolicy {
+            timeout: self.timeout.or(parent.timeout),
+            retries: self.retries.or(parent.retries),
+            tags,
+        }
+    }
 }
 
 /// Scenario group definition.
-pub trait ScenarioGroup {
+pub trait ScenarioGroup: Send + Sync {
     /// Get scenario group name.
     fn name(&self) -> &str;
 
@@ -32,35 +260,366 @@ pub trait ScenarioGroup {
     /// List scenarios from this group.
     fn scenarios(&self) -> &Vec<Box<dyn Scenario>>;
 
+    /// Group tags, inherited by all scenarios and groups from this group.
+    fn tags(&self) -> &[String] {
+        &[]
+    }
+
+    /// Group policy, applied to all scenarios and groups from this group.
+    /// See [`GroupPolicy`] for precedence.
+    fn policy(&self) -> Option<&GroupPolicy> {
+        None
+    }
+
+    /// Check whether group has no scenarios and no groups.
+    fn is_empty(&self) -> bool {
+        self.scenarios().is_empty() && self.groups().is_empty()
+    }
+
+    /// Number of scenarios directly in this group, scenarios of nested groups are not counted.
+    fn len(&self) -> usize {
+        self.scenarios().len()
+    }
+
     /// Find scenario by name.
     ///
     /// * `name` - Name of the scenario to find.
     fn find_scenario(&self, name: &str) -> Option<&dyn Scenario>;
+
+    /// Find group by name.
+    /// Groups with empty name are transparent, see [`ScenarioGroupImpl`].
+    ///
+    /// * `name` - Name of the group to find.
+    fn find_group(&self, name: &str) -> Option<&dyn ScenarioGroup> {
+        let (first, rest) = match name.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        let named = self
+            .groups()
+            .iter()
+            .find(|g| !g.name().is_empty() && g.name() == first);
+        match (named, rest) {
+            (Some(group), Some(rest)) => group.find_group(rest),
+            (Some(group), None) => Some(group.as_ref()),
+            (None, _) => self
+                .groups()
+                .iter()
+                .filter(|g| g.name().is_empty())
+                .find_map(|g| g.find_group(name)),
+        }
+    }
+
+    /// Get common group definition, if this group is one.
+    /// Used by [`ScenarioGroupImpl::merge`], other groups cannot be merged.
+    fn as_group_impl_mut(&mut self) -> Option<&mut ScenarioGroupImpl> {
+        None
+    }
+}
+
+/// Scenario groups are formatted as hierarchy of group and scenario names.
+impl fmt::Debug for dyn ScenarioGroup + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scenarios: Vec<&str> = self.scenarios().iter().map(|s| s.name()).collect();
+        f.debug_struct("ScenarioGroup")
+            .field("name", &self.name())
+            .field("scenarios", &scenarios)
+            .field("groups", self.groups())
+            .finish()
+    }
+}
+
+/// Check that `other` can be merged into `group`.
+fn check_merge(
+    group: &mut ScenarioGroupImpl,
+    other: &mut ScenarioGroupImpl,
+    prefix: &str,
+) -> Result<(), String> {
+    for scenario in &other.scenarios {
+        if group.scenarios.iter().any(|s| s.name() == scenario.name()) {
+            return Err(format!(
+                "Conflicting scenario {prefix}{} found during merge",
+                scenario.name()
+            ));
+        }
+    }
+
+    for other_group in other.groups.iter_mut() {
+        let Some(existing) = group
+            .groups
+            .iter_mut()
+            .find(|g| g.name() == other_group.name())
+        else {
+            continue;
+        };
+        let name = format!("{prefix}{}", existing.name());
+        match (
+            existing.as_group_impl_mut(),
+            other_group.as_group_impl_mut(),
+        ) {
+            (Some(existing), Some(other_group)) => {
+                check_merge(existing, other_group, &format!("{name}."))?
+            }
+            _ => return Err(format!("Group {name} cannot be merged")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge `other` into `group`, must be checked with `check_merge` first.
+fn merge_checked(group: &mut ScenarioGroupImpl, other: ScenarioGroupImpl) {
+    group.scenarios.extend(other.scenarios);
+    group.policy = match (group.policy.take(), other.policy) {
+        (Some(policy), Some(other_policy)) => Some(policy.inherit(&other_policy)),
+        (policy, other_policy) => policy.or(other_policy),
+    };
+    for tag in other.tags {
+        if !group.tags.contains(&tag) {
+            group.tags.push(tag);
+        }
+    }
+
+    for mut other_group in other.groups {
+        let existing = group
+            .groups
+            .iter_mut()
+            .find(|g| g.name() == other_group.name());
+        match (existing, other_group.as_group_impl_mut()) {
+            (Some(existing), Some(other_impl)) => {
+                let taken = ScenarioGroupImpl::new(
+                    &other_impl.name,
+                    std::mem::take(&mut other_impl.scenarios),
+                    std::mem::take(&mut other_impl.groups),
+                )
+                .with_tags(std::mem::take(&mut other_impl.tags));
+                let taken = ScenarioGroupImpl {
+                    policy: other_impl.policy.take(),
+                    ..taken
+                };
+                if let Some(existing) = existing.as_group_impl_mut() {
+                    merge_checked(existing, taken);
+                }
+            }
+            _ => group.groups.push(other_group),
+        }
+    }
+}
+
+/// Normalize scenario or group name.
+/// Surrounding whitespace is trimmed, names containing control characters are rejected.
+///
+/// * `name` - Name to normalize.
+fn normalize_name(name: &str) -> Result<&str, String> {
+    let trimmed = name.trim();
+    if trimmed.chars().any(char::is_control) {
+        return Err(format!(
+            "Invalid name {name:?}, control characters are not allowed"
+        ));
+    }
+    Ok(trimmed)
+}
+
+/// Scenario registered under normalized name, all other calls are delegated.
+struct RenamedScenario {
+    name: String,
+    inner: Box<dyn Scenario>,
+}
+
+impl Scenario for RenamedScenario {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, input: &str) -> Result<(), String> {
+        self.inner.run(input)
+    }
+
+    fn run_err(&self, input: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.run_err(input)
+    }
+
+    fn run_output(&self, input: &str) -> Result<Value, String> {
+        self.inner.run_output(input)
+    }
+
+    fn run_output_with_env(&self, env: &ScenarioEnv) -> Result<Value, String> {
+        self.inner.run_output_with_env(env)
+    }
+
+    fn run_with_cancel(&self, input: &str, token: &CancellationToken) -> Result<(), String> {
+        self.inner.run_with_cancel(input, token)
+    }
+
+    fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+        self.inner.run_with_env(env)
+    }
+
+    fn skip_reason(&self, input: &str) -> Option<String> {
+        self.inner.skip_reason(input)
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout()
+    }
+
+    fn slow_after(&self) -> Option<Duration> {
+        self.inner.slow_after()
+    }
+
+    fn tags(&self) -> &[String] {
+        self.inner.tags()
+    }
+
+    fn deprecated(&self) -> Option<&str> {
+        self.inner.deprecated()
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+
+    fn aliases(&self) -> &[String] {
+        self.inner.aliases()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> Option<&str> {
+        self.inner.input_schema()
+    }
+}
+
+/// Register scenario under normalized name.
+///
+/// * `scenario` - Scenario to register.
+fn normalize_scenario(scenario: Box<dyn Scenario>) -> Result<Box<dyn Scenario>, String> {
+    let name = normalize_name(scenario.name())?;
+    if name == scenario.name() {
+        return Ok(scenario);
+    }
+    Ok(Box::new(RenamedScenario {
+        name: name.to_string(),
+        inner: scenario,
+    }))
 }
 
 /// Common scenario group definition.
+///
+/// Group and scenario names are normalized on registration, see [`ScenarioGroupImpl::new`].
+///
+/// Nested groups with empty name are transparent: their name is skipped when joining paths,
+/// so their scenarios and groups are listed and found as if they belonged to the parent group.
 pub struct ScenarioGroupImpl {
     name: String,
     scenarios: Vec<Box<dyn Scenario>>,
     groups: Vec<Box<dyn ScenarioGroup>>,
+    tags: Vec<String>,
+    policy: Option<GroupPolicy>,
 }
 
 impl ScenarioGroupImpl {
     /// Create common scenario group definition.
+    /// Surrounding whitespace is trimmed from group and scenario names.
     ///
     /// * `name` - Name of the scenario group.
     /// * `scenario` - Scenarios in this group.
     /// * `groups` - Groups in this group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if group or scenario name contains control characters.
     pub fn new(
         name: &str,
         scenarios: Vec<Box<dyn Scenario>>,
         groups: Vec<Box<dyn ScenarioGroup>>,
     ) -> Self {
+        let name = normalize_name(name).unwrap_or_else(|e| panic!("{e}"));
+        let scenarios = scenarios
+            .into_iter()
+            .map(|scenario| normalize_scenario(scenario).unwrap_or_else(|e| panic!("{e}")))
+            .collect();
         ScenarioGroupImpl {
             name: name.to_string(),
             scenarios,
             groups,
+            tags: Vec::new(),
+            policy: None,
+        }
+    }
+
+    /// Add scenario to this group.
+    /// Surrounding whitespace is trimmed from scenario name.
+    /// Fails if name contains control characters or scenario with the same name exists.
+    ///
+    /// * `scenario` - Scenario to add.
+    pub fn add_scenario(&mut self, scenario: Box<dyn Scenario>) -> Result<(), String> {
+        let scenario = normalize_scenario(scenario)?;
+        if self.scenarios.iter().any(|s| s.name() == scenario.name()) {
+            return Err(format!("Scenario {} already exists", scenario.name()));
         }
+        self.scenarios.push(scenario);
+        Ok(())
+    }
+
+    /// Set group tags, inherited by all scenarios from this group.
+    ///
+    /// * `tags` - Group tags.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Set group policy, applied to all scenarios from this group unless overridden.
+    /// Policy tags are added to the group tags.
+    ///
+    /// * `policy` - Group policy.
+    pub fn with_policy(mut self, policy: GroupPolicy) -> Self {
+        for tag in &policy.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Create scenario group from scenarios, without nested groups.
+    ///
+    /// * `name` - Name of the scenario group.
+    /// * `scenarios` - Scenarios in this group.
+    pub fn from_scenarios(
+        name: &str,
+        scenarios: impl IntoIterator<Item = Box<dyn Scenario>>,
+    ) -> Self {
+        Self::new(name, scenarios.into_iter().collect(), Vec::new())
+    }
+
+    /// Merge scenarios and groups of other group into this group.
+    /// Groups with the same name are merged recursively, their tags are combined.
+    /// Policy fields of this group take precedence over policy fields of the merged group.
+    /// Fails without modifying this group if scenario names conflict.
+    ///
+    /// * `other` - Group to merge, its name is ignored.
+    pub fn merge(&mut self, mut other: ScenarioGroupImpl) -> Result<(), String> {
+        check_merge(self, &mut other, "")?;
+        merge_checked(self, other);
+        Ok(())
+    }
+}
+
+/// Collects scenarios into group with empty name, which is transparent when nested.
+impl FromIterator<Box<dyn Scenario>> for ScenarioGroupImpl {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Scenario>>>(iter: I) -> Self {
+        Self::from_scenarios("", iter)
+    }
+}
+
+impl fmt::Debug for ScenarioGroupImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self as &dyn ScenarioGroup).fmt(f)
     }
 }
 
@@ -77,6 +636,18 @@ impl ScenarioGroup for ScenarioGroupImpl {
         &self.scenarios
     }
 
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    fn policy(&self) -> Option<&GroupPolicy> {
+        self.policy.as_ref()
+    }
+
+    fn as_group_impl_mut(&mut self) -> Option<&mut ScenarioGroupImpl> {
+        Some(self)
+    }
+
     fn find_scenario(&self, name: &str) -> Option<&dyn Scenario> {
         let split: Vec<&str> = name.split('.').collect();
         if split.len() == 1 {
@@ -85,21 +656,35 @@ impl ScenarioGroup for ScenarioGroupImpl {
                     return Some(scenario.as_ref());
                 }
             }
+            for scenario in &self.scenarios {
+                if scenario.aliases().iter().any(|alias| alias == name) {
+                    return Some(scenario.as_ref());
+                }
+            }
         } else {
             for group in &self.groups {
-                if group.name() == split[0] {
+                if !group.name().is_empty() && group.name() == split[0] {
                     return group.find_scenario(split[1..].join(".").as_str());
                 }
             }
         }
 
-        None
+        // Groups with empty name are transparent.
+        self.groups
+            .iter()
+            .filter(|group| group.name().is_empty())
+            .find_map(|group| group.find_scenario(name))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::scenario::{Scenario, ScenarioGroup, ScenarioGroupImpl};
+    use crate::scenario::{
+        FnScenario, GroupPolicy, PlaceholderScenario, Scenario, ScenarioGroup, ScenarioGroupImpl,
+    };
+    use crate::test_context::TestContext;
+    use serde_json::Value;
+    use std::time::Duration;
 
     struct ScenarioStub {
         name: String,
@@ -178,10 +763,334 @@ mod tests {
         assert!(scenario.is_none());
     }
 
+    #[test]
+    fn test_find_group_ok() {
+        let group = init_group();
+        let inner_group = group.find_group("inner_group");
+        assert!(inner_group.is_some_and(|g| g.name() == "inner_group"));
+    }
+
+    #[test]
+    fn test_find_group_nested() {
+        let group_nested = ScenarioGroupImpl::new("nested_group", vec![], vec![]);
+        let group_inner =
+            ScenarioGroupImpl::new("inner_group", vec![], vec![Box::new(group_nested)]);
+        let group_outer =
+            ScenarioGroupImpl::new("outer_group", vec![], vec![Box::new(group_inner)]);
+
+        let group = group_outer.find_group("inner_group.nested_group");
+        assert!(group.is_some_and(|g| g.name() == "nested_group"));
+    }
+
+    #[test]
+    fn test_find_group_invalid_name() {
+        let group = init_group();
+        assert!(group.find_group("").is_none());
+        assert!(group.find_group("outer_scenario").is_none());
+        assert!(group.find_group("inner_group.invalid_group").is_none());
+    }
+
     #[test]
     fn test_find_scenario_invalid_name() {
         let group = init_group();
         let scenario = group.find_scenario("invalid_group.invalid_scenario");
         assert!(scenario.is_none());
     }
+
+    fn stub(name: &str) -> Box<dyn Scenario> {
+        Box::new(ScenarioStub {
+            name: name.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_merge_ok() {
+        let mut first = ScenarioGroupImpl::new(
+            "root",
+            vec![stub("first_scenario")],
+            vec![Box::new(ScenarioGroupImpl::new(
+                "shared_group",
+                vec![stub("first_shared")],
+                vec![],
+            ))],
+        );
+        let second = ScenarioGroupImpl::new(
+            "root",
+            vec![stub("second_scenario")],
+            vec![
+                Box::new(ScenarioGroupImpl::new(
+                    "shared_group",
+                    vec![stub("second_shared")],
+                    vec![],
+                )),
+                Box::new(ScenarioGroupImpl::new(
+                    "second_group",
+                    vec![stub("second_only")],
+                    vec![],
+                )),
+            ],
+        );
+
+        assert!(first.merge(second).is_ok());
+        assert_eq!(first.scenarios().len(), 2);
+        assert_eq!(first.groups().len(), 2);
+        assert!(first.find_scenario("second_scenario").is_some());
+        assert!(first.find_scenario("shared_group.first_shared").is_some());
+        assert!(first.find_scenario("shared_group.second_shared").is_some());
+        assert!(first.find_scenario("second_group.second_only").is_some());
+    }
+
+    #[test]
+    fn test_merge_conflicting_scenario() {
+        let mut first = ScenarioGroupImpl::new(
+            "root",
+            vec![stub("first_scenario")],
+            vec![Box::new(ScenarioGroupImpl::new(
+                "shared_group",
+                vec![stub("shared_scenario")],
+                vec![],
+            ))],
+        );
+        let second = ScenarioGroupImpl::new(
+            "root",
+            vec![stub("second_scenario")],
+            vec![Box::new(ScenarioGroupImpl::new(
+                "shared_group",
+                vec![stub("shared_scenario")],
+                vec![],
+            ))],
+        );
+
+        let result = first.merge(second);
+        assert!(result.is_err_and(
+            |e| e == "Conflicting scenario shared_group.shared_scenario found during merge"
+        ));
+        // Group is left unchanged.
+        assert_eq!(first.scenarios().len(), 1);
+        assert_eq!(first.groups()[0].scenarios().len(), 1);
+    }
+
+    #[test]
+    fn test_find_empty_group_name() {
+        let group_unnamed = ScenarioGroupImpl::new(
+            "",
+            vec![stub("unnamed_scenario")],
+            vec![Box::new(ScenarioGroupImpl::new(
+                "nested_group",
+                vec![stub("nested_scenario")],
+                vec![],
+            ))],
+        );
+        let group_outer =
+            ScenarioGroupImpl::new("outer_group", vec![], vec![Box::new(group_unnamed)]);
+        let root = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group_outer)]);
+
+        assert!(root
+            .find_scenario("outer_group.unnamed_scenario")
+            .is_some_and(|s| s.name() == "unnamed_scenario"));
+        assert!(root
+            .find_scenario("outer_group.nested_group.nested_scenario")
+            .is_some_and(|s| s.name() == "nested_scenario"));
+        assert!(root
+            .find_scenario("outer_group..unnamed_scenario")
+            .is_none());
+        assert!(root
+            .find_group("outer_group.nested_group")
+            .is_some_and(|g| g.name() == "nested_group"));
+        assert!(root.find_group("outer_group.").is_none());
+    }
+
+    #[test]
+    fn test_group_debug() {
+        let group = init_group();
+        assert_eq!(
+            format!("{group:?}"),
+            "ScenarioGroup { name: \"outer_group\", scenarios: [\"outer_scenario\"], \
+             groups: [ScenarioGroup { name: \"inner_group\", scenarios: [\"inner_scenario\"], \
+             groups: [] }] }"
+        );
+        assert_eq!(
+            format!("{:?}", group.scenarios()[0]),
+            "Scenario { name: \"outer_scenario\" }"
+        );
+    }
+
+    #[test]
+    fn test_from_scenarios() {
+        let scenarios = ["first", "second", "third"].map(|name| {
+            Box::new(FnScenario::new(name, |input| match input {
+                "ok" => Ok(()),
+                _ => Err("Invalid input".to_string()),
+            })) as Box<dyn Scenario>
+        });
+        let group = ScenarioGroupImpl::from_scenarios("generated", scenarios);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(
+            context.list_scenarios(),
+            vec!["generated.first", "generated.second", "generated.third"]
+        );
+        assert!(context.run("generated.second", "ok").is_ok());
+        assert!(context.run("generated.third", "").is_err());
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let group: ScenarioGroupImpl = (1..=3)
+            .map(|index| {
+                Box::new(FnScenario::new(&format!("scenario_{index}"), |_| Ok(())))
+                    as Box<dyn Scenario>
+            })
+            .collect();
+        assert_eq!(group.name(), "");
+
+        let context = TestContext::new(Box::new(group));
+        assert_eq!(
+            context.list_scenarios(),
+            vec!["scenario_1", "scenario_2", "scenario_3"]
+        );
+    }
+
+    #[test]
+    fn test_group_is_empty_and_len_empty() {
+        let group = ScenarioGroupImpl::new("group", vec![], vec![]);
+        assert!(group.is_empty());
+        assert_eq!(group.len(), 0);
+    }
+
+    #[test]
+    fn test_group_is_empty_and_len_only_groups() {
+        let nested = ScenarioGroupImpl::new(
+            "nested",
+            vec![Box::new(ScenarioStub {
+                name: "nested_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let group = ScenarioGroupImpl::new("group", vec![], vec![Box::new(nested)]);
+        assert!(!group.is_empty());
+        assert_eq!(group.len(), 0);
+    }
+
+    #[test]
+    fn test_group_is_empty_and_len_scenarios() {
+        let scenarios: Vec<Box<dyn Scenario>> = vec![
+            Box::new(ScenarioStub {
+                name: "first".to_string(),
+            }),
+            Box::new(ScenarioStub {
+                name: "second".to_string(),
+            }),
+        ];
+        let group = ScenarioGroupImpl::new("group", scenarios, vec![]);
+        assert!(!group.is_empty());
+        assert_eq!(group.len(), 2);
+    }
+
+    #[test]
+    fn test_placeholder_scenario() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(PlaceholderScenario::new("todo"))],
+            vec![],
+        );
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(context.list_scenarios(), vec!["todo".to_string()]);
+        let catalog: Value = serde_json::from_str(&context.catalog_json()).unwrap();
+        assert_eq!(
+            catalog["scenarios"][0]["description"],
+            "Placeholder, not implemented yet"
+        );
+        assert!(context
+            .run("todo", "")
+            .is_err_and(|e| e == "not implemented"));
+    }
+
+    #[test]
+    fn test_group_new_trims_names() {
+        let group = ScenarioGroupImpl::new(
+            " group ",
+            vec![Box::new(ScenarioStub {
+                name: "padded  ".to_string(),
+            })],
+            vec![],
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(context.list_scenarios(), vec!["group.padded".to_string()]);
+        assert!(context.run("group.padded", "").is_ok());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid name \"first\\tsecond\", control characters are not allowed"
+    )]
+    fn test_group_new_rejects_control_characters() {
+        ScenarioGroupImpl::new(
+            "group",
+            vec![Box::new(ScenarioStub {
+                name: "first\tsecond".to_string(),
+            })],
+            vec![],
+        );
+    }
+
+    #[test]
+    fn test_group_add_scenario() {
+        let mut group = ScenarioGroupImpl::new("group", vec![], vec![]);
+        let stub = |name: &str| -> Box<dyn Scenario> {
+            Box::new(ScenarioStub {
+                name: name.to_string(),
+            })
+        };
+
+        assert!(group.add_scenario(stub(" first")).is_ok());
+        assert!(group.find_scenario("first").is_some());
+        assert!(group
+            .add_scenario(stub("first "))
+            .is_err_and(|e| e == "Scenario first already exists"));
+        assert!(group.add_scenario(stub("first\tsecond")).is_err_and(
+            |e| e == "Invalid name \"first\\tsecond\", control characters are not allowed"
+        ));
+        assert_eq!(group.len(), 1);
+    }
+
+    #[test]
+    fn test_group_policy_inherit() {
+        let parent = GroupPolicy {
+            timeout: Some(Duration::from_secs(10)),
+            retries: Some(2),
+            tags: vec!["slow".to_string()],
+        };
+        let child = GroupPolicy {
+            timeout: Some(Duration::from_secs(1)),
+            retries: None,
+            tags: vec!["network".to_string(), "slow".to_string()],
+        };
+
+        let policy = child.inherit(&parent);
+
+        assert_eq!(policy.timeout, Some(Duration::from_secs(1)));
+        assert_eq!(policy.retries, Some(2));
+        assert_eq!(policy.tags, vec!["slow", "network"]);
+    }
+
+    #[test]
+    fn test_with_policy_adds_tags() {
+        let group = ScenarioGroupImpl::new("group", vec![stub("scenario")], vec![])
+            .with_tags(vec!["slow".to_string()])
+            .with_policy(GroupPolicy {
+                retries: Some(1),
+                tags: vec!["slow".to_string(), "network".to_string()],
+                ..Default::default()
+            });
+
+        assert_eq!(group.tags(), ["slow", "network"]);
+        assert!(group
+            .policy()
+            .is_some_and(|policy| policy.retries == Some(1)));
+    }
 }
@@ -0,0 +1,180 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+use crate::cancellation::CancellationToken;
+use crate::metrics::MetricsSink;
+use crate::sub_results::SubResults;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Counter used to create unique temporary directory names.
+static TEMP_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Scenario execution environment.
+/// Temporary directory is created on first use and removed when environment is dropped.
+pub struct ScenarioEnv {
+    name: String,
+    input: String,
+    token: CancellationToken,
+    metrics: MetricsSink,
+    sub_results: SubResults,
+    deadline: Option<Instant>,
+    temp_dir: OnceLock<PathBuf>,
+}
+
+impl ScenarioEnv {
+    /// Create scenario execution environment.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `input` - Test scenario input.
+    /// * `token` - Cancellation token.
+    pub fn new(name: &str, input: &str, token: CancellationToken) -> Self {
+        ScenarioEnv {
+            name: name.to_string(),
+            input: input.to_string(),
+            token,
+            metrics: MetricsSink::new(),
+            sub_results: SubResults::new(),
+            deadline: None,
+            temp_dir: OnceLock::new(),
+        }
+    }
+
+    /// Use provided metrics sink, e.g., to collect metrics after run.
+    ///
+    /// * `metrics` - Metrics sink.
+    pub fn with_metrics(mut self, metrics: MetricsSink) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Set absolute deadline of this scenario run.
+    ///
+    /// * `deadline` - Point in time at which scenario times out, `None` if there is no timeout.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Test scenario input.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Cancellation token, cancelled when scenario timeout is exceeded.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Sub-check results of this scenario run.
+    /// Scenario fails if any sub-check failed, all sub-checks are listed in the error.
+    pub fn sub_results(&self) -> &SubResults {
+        &self.sub_results
+    }
+
+    /// Point in time at which scenario times out, `None` if there is no timeout.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Time left until deadline, zero if deadline passed, `None` if there is no timeout.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Metrics sink of this scenario run.
+    pub fn metrics(&self) -> &MetricsSink {
+        &self.metrics
+    }
+
+    /// Unique temporary directory of this scenario run.
+    /// Created on first call.
+    pub fn temp_dir(&self) -> Result<&Path, String> {
+        if let Some(path) = self.temp_dir.get() {
+            return Ok(path);
+        }
+
+        let sanitized_name: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = std::env::temp_dir().join(format!(
+            "test_scenarios_{}_{}_{sanitized_name}",
+            std::process::id(),
+            TEMP_DIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create temporary directory: {e}"))?;
+        Ok(self.temp_dir.get_or_init(|| path))
+    }
+}
+
+impl Drop for ScenarioEnv {
+    fn drop(&mut self) {
+        if let Some(path) = self.temp_dir.get() {
+            // Cleanup failure must not cause panic during drop.
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancellation::CancellationToken;
+    use crate::scenario_env::ScenarioEnv;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_input() {
+        let env = ScenarioEnv::new("scenario", "example_input", CancellationToken::new());
+        assert_eq!(env.input(), "example_input");
+        assert!(!env.cancellation_token().is_cancelled());
+        assert_eq!(env.deadline(), None);
+        assert_eq!(env.remaining(), None);
+    }
+
+    #[test]
+    fn test_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let env = ScenarioEnv::new("scenario", "", CancellationToken::new())
+            .with_deadline(Some(deadline));
+        assert_eq!(env.deadline(), Some(deadline));
+        assert!(env.remaining().unwrap() <= Duration::from_secs(10));
+
+        let env = ScenarioEnv::new("scenario", "", CancellationToken::new())
+            .with_deadline(Some(Instant::now()));
+        assert_eq!(env.remaining(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_temp_dir_removed_on_drop() {
+        let env = ScenarioEnv::new("group.scenario", "", CancellationToken::new());
+        let path = env.temp_dir().unwrap().to_path_buf();
+        assert!(path.is_dir());
+        assert_eq!(env.temp_dir().unwrap(), path);
+
+        std::fs::write(path.join("file.txt"), "content").unwrap();
+        drop(env);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_temp_dir_unique() {
+        let env_1 = ScenarioEnv::new("scenario", "", CancellationToken::new());
+        let env_2 = ScenarioEnv::new("scenario", "", CancellationToken::new());
+        assert_ne!(env_1.temp_dir().unwrap(), env_2.temp_dir().unwrap());
+    }
+}
@@ -0,0 +1,152 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Lightweight input validation against JSON schema.
+//!
+//! Only a subset of JSON Schema is supported: `type`, `required` and `properties`.
+//! Other keywords are ignored.
+use serde_json::Value;
+
+/// Name of JSON type of the value.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check whether value matches JSON schema type.
+/// Integers are also numbers.
+fn matches_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// Validate value against schema.
+///
+/// * `schema` - Schema of the value.
+/// * `value` - Value to validate.
+/// * `path` - Path of the value, empty for root.
+fn validate_value(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let location = if path.is_empty() {
+        "input".to_string()
+    } else {
+        format!("field '{path}'")
+    };
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        if !matches_type(value, expected) {
+            return Err(format!(
+                "{location} expected {expected}, got {}",
+                type_name(value)
+            ));
+        }
+    }
+
+    let Value::Object(object) = value else {
+        return Ok(());
+    };
+    let join = |field: &str| {
+        if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{path}.{field}")
+        }
+    };
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for field in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(field) {
+                return Err(format!("missing required field '{}'", join(field)));
+            }
+        }
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (field, field_schema) in properties {
+            if let Some(field_value) = object.get(field) {
+                validate_value(field_schema, field_value, &join(field))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate test scenario input against JSON schema.
+///
+/// * `schema` - JSON schema string.
+/// * `input` - Test scenario input.
+pub fn validate_input(schema: &str, input: &str) -> Result<(), String> {
+    let schema: Value =
+        serde_json::from_str(schema).map_err(|e| format!("Invalid input schema: {e}"))?;
+    let value: Value =
+        serde_json::from_str(input).map_err(|e| format!("Invalid input: not valid JSON: {e}"))?;
+    validate_value(&schema, &value, "").map_err(|e| format!("Invalid input: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::validate_input;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["name", "config"],
+        "properties": {
+            "name": {"type": "string"},
+            "count": {"type": "integer"},
+            "config": {
+                "type": "object",
+                "required": ["ratio"],
+                "properties": {"ratio": {"type": "number"}}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_validate_input_ok() {
+        let input = r#"{"name": "example", "count": 3, "config": {"ratio": 1}}"#;
+        assert!(validate_input(SCHEMA, input).is_ok());
+    }
+
+    #[test]
+    fn test_validate_input_missing_field() {
+        let result = validate_input(SCHEMA, r#"{"name": "example", "config": {}}"#);
+        assert!(result.is_err_and(|e| e == "Invalid input: missing required field 'config.ratio'"));
+    }
+
+    #[test]
+    fn test_validate_input_wrong_type() {
+        let input = r#"{"name": "example", "count": 1.5, "config": {"ratio": 1}}"#;
+        let result = validate_input(SCHEMA, input);
+        assert!(
+            result.is_err_and(|e| e == "Invalid input: field 'count' expected integer, got number")
+        );
+    }
+
+    #[test]
+    fn test_validate_input_not_json() {
+        let result = validate_input(SCHEMA, "not json");
+        assert!(result.is_err_and(|e| e.starts_with("Invalid input: not valid JSON: ")));
+    }
+
+    #[test]
+    fn test_validate_input_invalid_schema() {
+        let result = validate_input("{", "{}");
+        assert!(result.is_err_and(|e| e.starts_with("Invalid input schema: ")));
+    }
+}
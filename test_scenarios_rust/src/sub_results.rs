@@ -0,0 +1,103 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Sub-checks reported by scenarios.
+use std::sync::{Arc, Mutex};
+
+/// Sub-check name with its result.
+pub type SubResult = (String, Result<(), String>);
+
+/// Collector of sub-check results of a single scenario run.
+/// Scenario fails if any sub-check failed. Clones share recorded results.
+#[derive(Clone, Debug, Default)]
+pub struct SubResults {
+    results: Arc<Mutex<Vec<SubResult>>>,
+}
+
+impl SubResults {
+    /// Create empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record sub-check result.
+    /// Returns `true` if sub-check passed.
+    ///
+    /// * `name` - Sub-check name.
+    /// * `result` - Sub-check result.
+    pub fn check(&self, name: &str, result: Result<(), String>) -> bool {
+        let passed = result.is_ok();
+        self.results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name.to_string(), result));
+        passed
+    }
+
+    /// Recorded sub-check results, in order of recording.
+    pub fn snapshot(&self) -> Vec<SubResult> {
+        self.results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Summarize sub-checks if any of them failed, listing all sub-checks.
+    /// Returns `None` if all sub-checks passed.
+    pub fn failure_summary(&self) -> Option<String> {
+        let results = self.snapshot();
+        let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+        if failed == 0 {
+            return None;
+        }
+
+        let checks: Vec<String> = results
+            .iter()
+            .map(|(name, result)| match result {
+                Ok(()) => format!("[passed] {name}"),
+                Err(error) => format!("[failed] {name}: {error}"),
+            })
+            .collect();
+        Some(format!(
+            "{failed} of {} sub-checks failed: {}",
+            results.len(),
+            checks.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sub_results::SubResults;
+
+    #[test]
+    fn test_sub_results_summary() {
+        let sub_results = SubResults::new();
+        assert!(sub_results.check("first", Ok(())));
+        assert!(!sub_results
+            .clone()
+            .check("second", Err("Mismatch".to_string())));
+
+        assert_eq!(sub_results.snapshot().len(), 2);
+        assert_eq!(
+            sub_results.failure_summary(),
+            Some("1 of 2 sub-checks failed: [passed] first, [failed] second: Mismatch".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sub_results_all_passed() {
+        let sub_results = SubResults::new();
+        sub_results.check("first", Ok(()));
+        assert_eq!(sub_results.failure_summary(), None);
+    }
+}
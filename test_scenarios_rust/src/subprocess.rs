@@ -0,0 +1,679 @@
+// *******************************************************************************
+// Copyright (c) 2025 Contributors to the Eclipse Foundation
+//
+// See the NOTICE file(s) distributed with this work for additional
+// information regarding copyright ownership.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Apache License Version 2.0 which is available at
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// SPDX-License-Identifier: Apache-2.0
+// *******************************************************************************
+//! Execution of subprocesses with timeout, and isolated execution of scenarios.
+use crate::cli::INPUT_ENV_VAR;
+use crate::outcome::Outcome;
+use crate::resource_usage::ResourceUsage;
+use serde_json::Value;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+#[cfg(unix)]
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Interval between subprocess status checks.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Time given to collect remaining output of killed subprocess.
+const OUTPUT_GRACE: Duration = Duration::from_millis(100);
+
+/// Time given to runner process of isolated scenario to report timeout, before it is killed.
+const KILL_GRACE: Duration = Duration::from_secs(1);
+
+/// Counter of result files of isolated runs, makes file names unique within the process.
+static RESULT_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Process groups of running subprocesses, see [`signal_process_groups`].
+#[cfg(unix)]
+static PROCESS_GROUPS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Options of subprocess execution.
+#[derive(Clone, Debug, Default)]
+pub struct SubprocessOptions {
+    /// Time after which subprocess is killed.
+    pub timeout: Option<Duration>,
+
+    /// Place subprocess in its own process group, Unix only.
+    /// Timeout kills the whole group, including children spawned by the subprocess.
+    /// Group does not receive signals sent to the terminal's foreground group, e.g., on Ctrl-C,
+    /// see [`signal_process_groups`].
+    pub process_group: bool,
+}
+
+/// Result of subprocess execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubprocessOutput {
+    /// Exit code, `None` if subprocess was terminated by a signal.
+    pub exit_code: Option<i32>,
+
+    /// Signal terminating the subprocess, Unix only.
+    pub signal: Option<i32>,
+
+    /// Captured standard output.
+    pub stdout: String,
+
+    /// Captured standard error.
+    pub stderr: String,
+
+    /// Subprocess was killed due to timeout.
+    pub timed_out: bool,
+
+    /// Resource usage of the subprocess, collected on Unix only.
+    pub resource_usage: ResourceUsage,
+}
+
+impl SubprocessOutput {
+    /// Check if subprocess exited successfully.
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Read stream in a separate thread, result is sent over returned channel.
+fn read_stream(stream: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut stream) = stream {
+            let _ = stream.read_to_end(&mut buffer);
+        }
+        let _ = sender.send(String::from_utf8_lossy(&buffer).to_string());
+    });
+    receiver
+}
+
+/// Send signal to all processes in a process group.
+///
+/// * `pgid` - Process group ID.
+/// * `signal` - Signal number, e.g., `libc::SIGTERM`.
+#[cfg(unix)]
+pub fn signal_process_group(pgid: u32, signal: i32) -> Result<(), String> {
+    let pgid = libc::pid_t::try_from(pgid)
+        .map_err(|_| format!("Failed to signal process group {pgid}: invalid ID"))?;
+    // SAFETY: `kill` has no memory safety requirements, negative PID addresses the group.
+    if unsafe { libc::kill(-pgid, signal) } == 0 {
+        Ok(())
+    } else {
+        let error = std::io::Error::last_os_error();
+        Err(format!("Failed to signal process group {pgid}: {error}"))
+    }
+}
+
+/// Kill all processes in a process group.
+///
+/// * `pgid` - Process group ID.
+#[cfg(unix)]
+pub fn kill_process_group(pgid: u32) -> Result<(), String> {
+    signal_process_group(pgid, libc::SIGKILL)
+}
+
+/// Send signal to process groups of all running subprocesses started with
+/// [`SubprocessOptions::process_group`].
+/// Such subprocesses do not receive signals sent to the terminal's foreground group,
+/// e.g., SIGINT on Ctrl-C, and are left running when this process exits.
+/// Application should forward SIGINT and SIGTERM from its own signal handler,
+/// function must not be called from an asynchronous signal handler, as it takes a lock.
+///
+/// * `signal` - Signal number, e.g., `libc::SIGINT`.
+#[cfg(unix)]
+pub fn signal_process_groups(signal: i32) {
+    let groups = PROCESS_GROUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    for pgid in groups {
+        // Group may exit before being removed from the list.
+        let _ = signal_process_group(pgid, signal);
+    }
+}
+
+/// Process group of running subprocess, registered for [`signal_process_groups`] until dropped.
+#[cfg(unix)]
+struct ProcessGroupGuard(u32);
+
+#[cfg(unix)]
+impl ProcessGroupGuard {
+    fn new(pgid: u32) -> Self {
+        PROCESS_GROUPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(pgid);
+        ProcessGroupGuard(pgid)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        PROCESS_GROUPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|pgid| *pgid != self.0);
+    }
+}
+
+/// Kill subprocess, with its process group if requested.
+fn kill(child: &mut Child, process_group: bool) -> Result<(), String> {
+    #[cfg(unix)]
+    if process_group {
+        return kill_process_group(child.id());
+    }
+    let _ = process_group;
+    child
+        .kill()
+        .map_err(|e| format!("Failed to kill process {}: {e}", child.id()))
+}
+
+/// Wait for subprocess to exit, collecting its resource usage.
+/// Returns `None` if subprocess still runs and `block` is not set.
+///
+/// * `child` - Subprocess to wait for.
+/// * `block` - Wait until subprocess exits.
+#[cfg(unix)]
+fn wait_child(
+    child: &mut Child,
+    block: bool,
+) -> std::io::Result<Option<(ExitStatus, ResourceUsage)>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let flags = if block { 0 } else { libc::WNOHANG };
+    let mut status = 0;
+    // SAFETY: `rusage` is plain data, zeroed value is valid and fully written by `wait4`.
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: `status` and `usage` are valid pointers, `pid` is a child of this process.
+        match unsafe { libc::wait4(pid, &mut status, flags, &mut usage) } {
+            0 => return Ok(None),
+            -1 => {
+                let error = std::io::Error::last_os_error();
+                if error.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+            _ => {
+                let status = ExitStatus::from_raw(status);
+                return Ok(Some((status, ResourceUsage::from_rusage(&usage))));
+            }
+        }
+    }
+}
+
+/// Wait for subprocess to exit, resource usage is not collected.
+/// Returns `None` if subprocess still runs and `block` is not set.
+///
+/// * `child` - Subprocess to wait for.
+/// * `block` - Wait until subprocess exits.
+#[cfg(not(unix))]
+fn wait_child(
+    child: &mut Child,
+    block: bool,
+) -> std::io::Result<Option<(ExitStatus, ResourceUsage)>> {
+    let status = if block {
+        Some(child.wait()?)
+    } else {
+        child.try_wait()?
+    };
+    Ok(status.map(|status| (status, ResourceUsage::default())))
+}
+
+/// Signal terminating the process.
+fn signal(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+/// Run subprocess to completion or until timeout, capturing its output.
+///
+/// * `command` - Command to run, stdout and stderr are overridden to be captured.
+/// * `options` - Execution options.
+pub fn run_subprocess(
+    mut command: Command,
+    options: &SubprocessOptions,
+) -> Result<SubprocessOutput, String> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    if options.process_group {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to spawn {program}: {e}"))?;
+    #[cfg(unix)]
+    let _process_group = options
+        .process_group
+        .then(|| ProcessGroupGuard::new(child.id()));
+    let stdout = read_stream(child.stdout.take());
+    let stderr = read_stream(child.stderr.take());
+
+    let start = Instant::now();
+    let mut timed_out = false;
+    let wait_error = |e: std::io::Error| format!("Failed to wait for {program}: {e}");
+    let (status, resource_usage) = loop {
+        if let Some(exited) = wait_child(&mut child, false).map_err(wait_error)? {
+            break exited;
+        }
+        if options
+            .timeout
+            .is_some_and(|timeout| start.elapsed() >= timeout)
+        {
+            timed_out = true;
+            kill(&mut child, options.process_group)?;
+            match wait_child(&mut child, true).map_err(wait_error)? {
+                Some(exited) => break exited,
+                None => return Err(wait_error(std::io::ErrorKind::WouldBlock.into())),
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // Children left behind by killed subprocess may keep the pipes open.
+    let collect = |receiver: mpsc::Receiver<String>| {
+        if timed_out {
+            receiver.recv_timeout(OUTPUT_GRACE).unwrap_or_default()
+        } else {
+            receiver.recv().unwrap_or_default()
+        }
+    };
+
+    Ok(SubprocessOutput {
+        exit_code: status.code(),
+        signal: signal(&status),
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+        timed_out,
+        resource_usage,
+    })
+}
+
+/// Isolated execution of scenarios, each scenario is run by a separate runner process.
+/// Scenarios corrupting global process state or crashing do not affect other scenarios.
+/// Runner reports outcome of the scenario as JSON line written to file provided with `--result-file`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Isolation {
+    /// Runner executable.
+    pub executable: PathBuf,
+
+    /// Arguments preceding scenario arguments, e.g., log configuration, working directory and retries.
+    pub arguments: Vec<String>,
+
+    /// Place each runner process in its own process group, Unix only.
+    pub process_group: bool,
+}
+
+impl Isolation {
+    /// Isolation using executable of the current process as runner.
+    ///
+    /// * `process_group` - Place each runner process in its own process group.
+    pub fn current_exe(process_group: bool) -> Result<Self, String> {
+        let executable = std::env::current_exe()
+            .map_err(|e| format!("Failed to determine runner executable: {e}"))?;
+        Ok(Isolation {
+            executable,
+            arguments: Vec::new(),
+            process_group,
+        })
+    }
+
+    /// Run scenario in a runner process.
+    /// Runner is invoked with `--name` and `--result-file`,
+    /// input is passed in `TEST_SCENARIO_INPUT` environment variable.
+    /// Timeouts are passed to the runner, runner is killed if it does not exit shortly after timeout.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `input` - Test scenario input.
+    /// * `soft_timeout` - Time after which cancellation is requested, passed to the runner.
+    /// * `timeout` - Time after which scenario times out, passed to the runner.
+    pub fn run_scenario(
+        &self,
+        name: &str,
+        input: &str,
+        soft_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Outcome {
+        self.run_scenario_with_usage(name, input, soft_timeout, timeout)
+            .0
+    }
+
+    /// Run scenario in a runner process, see [`Isolation::run_scenario`].
+    /// Resource usage of the runner process is returned with the outcome.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `input` - Test scenario input.
+    /// * `soft_timeout` - Time after which cancellation is requested, passed to the runner.
+    /// * `timeout` - Time after which scenario times out, passed to the runner.
+    pub fn run_scenario_with_usage(
+        &self,
+        name: &str,
+        input: &str,
+        soft_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> (Outcome, ResourceUsage) {
+        let result_path = std::env::temp_dir().join(format!(
+            "test_scenarios_result_{}_{}.ndjson",
+            std::process::id(),
+            RESULT_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&result_path);
+
+        let mut command = Command::new(&self.executable);
+        command
+            .args(&self.arguments)
+            .args(["--name", name])
+            .arg("--result-file")
+            .arg(&result_path)
+            .env(INPUT_ENV_VAR, input);
+        if let Some(soft_timeout) = soft_timeout {
+            command.args(["--soft-timeout-ms", &soft_timeout.as_millis().to_string()]);
+        }
+        if let Some(timeout) = timeout {
+            command.args(["--hard-timeout-ms", &timeout.as_millis().to_string()]);
+        }
+        let options = SubprocessOptions {
+            timeout: timeout.map(|timeout| timeout + KILL_GRACE),
+            process_group: self.process_group,
+        };
+
+        let output = run_subprocess(command, &options);
+        let reported = std::fs::read_to_string(&result_path).unwrap_or_default();
+        let _ = std::fs::remove_file(&result_path);
+        let output = match output {
+            Ok(output) => output,
+            Err(error) => return (Outcome::Failed(error), ResourceUsage::default()),
+        };
+        if !output.stdout.is_empty() {
+            log_debug!("Output of isolated scenario {name}:\n{}", output.stdout);
+        }
+        if !output.stderr.is_empty() {
+            log_debug!(
+                "Error output of isolated scenario {name}:\n{}",
+                output.stderr
+            );
+        }
+        (isolated_outcome(&output, &reported), output.resource_usage)
+    }
+}
+
+/// Determine scenario outcome from results reported by runner process, or from its exit status
+/// if runner did not report any result, e.g., because it crashed.
+///
+/// * `output` - Output of runner process.
+/// * `reported` - Content of result file, JSON lines with `outcome` of the run or `error` of the runner.
+fn isolated_outcome(output: &SubprocessOutput, reported: &str) -> Outcome {
+    let lines: Vec<Value> = reported
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    // Result written before the runner hung is still valid.
+    let outcome = lines
+        .iter()
+        .rev()
+        .find_map(|line| Outcome::from_json(line.get("outcome")?).ok());
+    if let Some(outcome) = outcome {
+        return outcome;
+    }
+    // Runner reports error if it fails before running the scenario, e.g., due to invalid arguments.
+    if let Some(error) = lines.iter().rev().find_map(|line| line["error"].as_str()) {
+        return Outcome::Failed(format!("Isolated scenario failed: {error}"));
+    }
+    if output.timed_out {
+        return Outcome::TimedOut;
+    }
+    match (output.exit_code, output.signal) {
+        (Some(0), _) => {
+            Outcome::Failed("Isolated scenario exited without reporting result".to_string())
+        }
+        (Some(code), _) => Outcome::Failed(format!("Isolated scenario exited with code {code}")),
+        (None, Some(signal)) => {
+            Outcome::Failed(format!("Isolated scenario terminated by signal {signal}"))
+        }
+        (None, None) => Outcome::Failed("Isolated scenario terminated".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::outcome::Outcome;
+    use crate::subprocess::{run_subprocess, Isolation, SubprocessOptions};
+    #[cfg(unix)]
+    use crate::subprocess::{signal_process_group, PROCESS_GROUPS};
+    use std::path::PathBuf;
+    use std::process::Command;
+    use std::time::Duration;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo out; echo err >&2; exit 3"]);
+
+        let output = run_subprocess(command, &SubprocessOptions::default()).unwrap();
+
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(output.signal, None);
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert!(!output.timed_out);
+        assert!(!output.success());
+    }
+
+    #[test]
+    fn test_run_subprocess_spawn_error() {
+        let command = Command::new("nonexistent_test_scenarios_program");
+
+        let result = run_subprocess(command, &SubprocessOptions::default());
+
+        assert!(result
+            .is_err_and(|e| e.starts_with("Failed to spawn nonexistent_test_scenarios_program")));
+    }
+
+    /// Check if process no longer runs, zombie processes are considered not running.
+    #[cfg(unix)]
+    fn is_terminated(pid: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => stat
+                .rsplit_once(')')
+                .is_some_and(|(_, rest)| rest.trim_start().starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_timeout_kills_process_group() {
+        // Subprocess reports PID of its child, then waits for it.
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & echo $!; wait"]);
+        let options = SubprocessOptions {
+            timeout: Some(Duration::from_millis(200)),
+            process_group: true,
+        };
+
+        let output = run_subprocess(command, &options).unwrap();
+
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+        assert_eq!(output.signal, Some(9));
+        let child_pid: u32 = output.stdout.trim().parse().unwrap();
+        let mut terminated = false;
+        for _ in 0..100 {
+            terminated = is_terminated(child_pid);
+            if terminated {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(terminated, "Child process {child_pid} was not killed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_process_group_registered() {
+        // Subprocess writes its PID, which is also its process group ID, then waits for signal.
+        let pid_path = std::env::temp_dir().join(format!("{}_synth_397_pid", std::process::id()));
+        let _ = std::fs::remove_file(&pid_path);
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", r#"echo $$ > "$0"; sleep 30"#])
+            .arg(&pid_path);
+        let options = SubprocessOptions {
+            timeout: Some(Duration::from_secs(10)),
+            process_group: true,
+        };
+        let handle = std::thread::spawn(move || run_subprocess(command, &options).unwrap());
+
+        let mut pgid = None;
+        for _ in 0..500 {
+            pgid = std::fs::read_to_string(&pid_path)
+                .ok()
+                .and_then(|pid| pid.trim().parse::<u32>().ok());
+            if pgid.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let pgid = pgid.expect("Subprocess did not report its PID");
+        assert!(PROCESS_GROUPS.lock().unwrap().contains(&pgid));
+        signal_process_group(pgid, libc::SIGTERM).unwrap();
+
+        let output = handle.join().unwrap();
+        assert!(!output.timed_out);
+        assert_eq!(output.signal, Some(libc::SIGTERM));
+        assert!(!PROCESS_GROUPS.lock().unwrap().contains(&pgid));
+        std::fs::remove_file(pid_path).unwrap();
+    }
+
+    /// Isolation with shell script standing in for the runner executable.
+    /// Script receives `--name <name> --result-file <path>` as positional arguments.
+    #[cfg(unix)]
+    fn script_isolation(script: &str) -> Isolation {
+        Isolation {
+            executable: PathBuf::from("sh"),
+            arguments: vec!["-c".to_string(), script.to_string(), "runner".to_string()],
+            process_group: false,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_passes_name_and_input() {
+        let isolation = script_isolation(
+            r#"[ "$1 $2 $3 $TEST_SCENARIO_INPUT" = "--name check --result-file {\"a\": 1}" ] &&
+            echo '{"outcome":{"status":"passed","message":null}}' > "$4""#,
+        );
+
+        let outcome = isolation.run_scenario("check", r#"{"a": 1}"#, None, None);
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_failed() {
+        // Last line of error output is not mistaken for the error of the scenario.
+        let isolation = script_isolation(
+            r#"echo '{"name":"check","outcome":{"status":"failed","message":"Requested error"}}' > "$4";
+            echo 'Other error' >&2; exit 1"#,
+        );
+
+        let outcome = isolation.run_scenario("check", "", None, None);
+
+        assert_eq!(outcome, Outcome::Failed("Requested error".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_runner_error() {
+        let isolation =
+            script_isolation(r#"echo '{"error":"Unknown scenario: check"}' > "$4"; exit 1"#);
+
+        let outcome = isolation.run_scenario("check", "", None, None);
+
+        assert_eq!(
+            outcome,
+            Outcome::Failed("Isolated scenario failed: Unknown scenario: check".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_no_result() {
+        let isolation = script_isolation("echo 'Requested error' >&2; exit 1");
+
+        let outcome = isolation.run_scenario("check", "", None, None);
+
+        assert_eq!(
+            outcome,
+            Outcome::Failed("Isolated scenario exited with code 1".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_crashed() {
+        let isolation = script_isolation("kill -SEGV $$");
+
+        let outcome = isolation.run_scenario("check", "", None, None);
+
+        assert_eq!(
+            outcome,
+            Outcome::Failed("Isolated scenario terminated by signal 11".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_isolation_run_scenario_timed_out() {
+        let isolation = Isolation {
+            process_group: true,
+            ..script_isolation("sleep 30")
+        };
+
+        let outcome = isolation.run_scenario("check", "", None, Some(Duration::from_millis(100)));
+
+        assert_eq!(outcome, Outcome::TimedOut);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_subprocess_resource_usage() {
+        // Busy loop in the subprocess, time spent by this process is not included.
+        let mut command = Command::new("sh");
+        command.args(["-c", "i=0; while [ $i -lt 100000 ]; do i=$((i+1)); done"]);
+
+        let output = run_subprocess(command, &SubprocessOptions::default()).unwrap();
+
+        assert!(output.success());
+        let usage = output.resource_usage;
+        assert!(usage.max_rss_bytes.is_some_and(|rss| rss > 0));
+        assert!(usage.cpu_time.is_some_and(|cpu_time| !cpu_time.is_zero()));
+    }
+}
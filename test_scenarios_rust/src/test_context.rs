@@ -10,151 +10,3722 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 // *******************************************************************************
-use crate::scenario::ScenarioGroup;
+use crate::cancellation::CancellationToken;
+use crate::filter::{ScenarioFilter, ScenarioToggles};
+use crate::metrics::MetricsSink;
+use crate::outcome::Outcome;
+use crate::panic_capture::{catch_panic, panic_message};
+use crate::resource_usage::ResourceUsage;
+use crate::scenario::{GroupPolicy, Scenario, ScenarioGroup};
+use crate::scenario_env::ScenarioEnv;
+use crate::schema::validate_input;
+use crate::subprocess::Isolation;
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
+/// Join path segments, empty segments are skipped.
 fn join_name(left: &str, right: &str) -> String {
-    if !left.is_empty() {
-        format!("{left}.{right}")
-    } else {
-        right.to_string()
+    match (left.is_empty(), right.is_empty()) {
+        (true, _) => right.to_string(),
+        (false, true) => left.to_string(),
+        (false, false) => format!("{left}.{right}"),
     }
 }
 
-fn list_scenarios_recursive(group: &dyn ScenarioGroup, prefix: String) -> Vec<String> {
+fn list_scenarios_recursive(
+    group: &dyn ScenarioGroup,
+    prefix: String,
+) -> Vec<(String, &dyn Scenario)> {
+    list_tagged_scenarios_recursive(group, prefix, &[])
+        .into_iter()
+        .map(|scenario| (scenario.name, scenario.scenario))
+        .collect()
+}
+
+/// Scenario listed with tags inherited from its groups.
+struct TaggedScenario<'a> {
+    name: String,
+    scenario: &'a dyn Scenario,
+    group_tags: Vec<String>,
+}
+
+impl TaggedScenario<'_> {
+    /// Union of tags of the scenario and tags inherited from its groups.
+    fn tags(&self) -> Vec<String> {
+        let mut tags = self.scenario.tags().to_vec();
+        for tag in &self.group_tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+}
+
+/// List scenarios of the group with union of tags of the group and its ancestors.
+///
+/// * `group` - Group to list.
+/// * `prefix` - Fully-qualified name of the group.
+/// * `parent_tags` - Tags of the ancestor groups.
+fn list_tagged_scenarios_recursive<'a>(
+    group: &'a dyn ScenarioGroup,
+    prefix: String,
+    parent_tags: &[String],
+) -> Vec<TaggedScenario<'a>> {
+    let mut group_tags = parent_tags.to_vec();
+    group_tags.extend(group.tags().iter().cloned());
+
     let mut names = Vec::new();
 
     let groups = group.groups();
     for group in groups {
         let new_prefix = join_name(&prefix, group.name());
-        let result = list_scenarios_recursive(group.as_ref(), new_prefix);
+        let result = list_tagged_scenarios_recursive(group.as_ref(), new_prefix, &group_tags);
         names.extend(result);
     }
 
     let scenarios = group.scenarios();
     for scenario in scenarios {
-        let scenario_name = join_name(&prefix, scenario.name());
-        names.push(scenario_name);
+        names.push(TaggedScenario {
+            name: join_name(&prefix, scenario.name()),
+            scenario: scenario.as_ref(),
+            group_tags: group_tags.clone(),
+        });
     }
 
     names
 }
 
+/// Resolve policy applied to scenario, combining policies of all groups on its path.
+/// Name is resolved as in [`ScenarioGroup::find_scenario`], `None` is returned if scenario is not found.
+///
+/// * `group` - Group to search.
+/// * `name` - Name of the scenario, relative to the group.
+/// * `parent_policy` - Policy combined from ancestor groups.
+fn resolve_policy(
+    group: &dyn ScenarioGroup,
+    name: &str,
+    parent_policy: &GroupPolicy,
+) -> Option<GroupPolicy> {
+    let policy = match group.policy() {
+        Some(policy) => policy.inherit(parent_policy),
+        None => parent_policy.clone(),
+    };
+    match name.split_once('.') {
+        None => {
+            let found = group.scenarios().iter().any(|scenario| {
+                scenario.name() == name || scenario.aliases().iter().any(|alias| alias == name)
+            });
+            if found {
+                return Some(policy);
+            }
+        }
+        Some((first, rest)) => {
+            let named = group
+                .groups()
+                .iter()
+                .find(|g| !g.name().is_empty() && g.name() == first);
+            if let Some(named) = named {
+                return resolve_policy(named.as_ref(), rest, &policy);
+            }
+        }
+    }
+
+    // Groups with empty name are transparent.
+    group
+        .groups()
+        .iter()
+        .filter(|g| g.name().is_empty())
+        .find_map(|g| resolve_policy(g.as_ref(), name, &policy))
+}
+
+/// Collect scenarios of the group, including scenarios of transparent groups with empty name.
+fn level_scenarios(group: &dyn ScenarioGroup) -> Vec<&dyn Scenario> {
+    let mut scenarios: Vec<&dyn Scenario> = group.scenarios().iter().map(|s| s.as_ref()).collect();
+    for group in group.groups().iter().filter(|g| g.name().is_empty()) {
+        scenarios.extend(level_scenarios(group.as_ref()));
+    }
+    scenarios
+}
+
+/// Check that scenario aliases do not conflict with names or aliases of other scenarios
+/// of the same group.
+fn check_aliases(group: &dyn ScenarioGroup, prefix: &str) -> Result<(), String> {
+    let scenarios = level_scenarios(group);
+    for (index, scenario) in scenarios.iter().enumerate() {
+        for alias in scenario.aliases() {
+            for (other_index, other) in scenarios.iter().enumerate() {
+                let conflicts = other.name() == alias
+                    || (other_index != index && other.aliases().contains(alias));
+                if conflicts {
+                    return Err(format!(
+                        "Alias {alias} of scenario {} conflicts with scenario {}",
+                        join_name(prefix, scenario.name()),
+                        join_name(prefix, other.name())
+                    ));
+                }
+            }
+        }
+    }
+
+    for group in group.groups().iter().filter(|g| !g.name().is_empty()) {
+        check_aliases(group.as_ref(), &join_name(prefix, group.name()))?;
+    }
+    Ok(())
+}
+
+/// Count scenarios of the group, including scenarios of nested groups.
+fn count_group_scenarios(group: &dyn ScenarioGroup) -> usize {
+    let nested: usize = group
+        .groups()
+        .iter()
+        .map(|g| count_group_scenarios(g.as_ref()))
+        .sum();
+    group.scenarios().len() + nested
+}
+
+/// Collect fully-qualified names of nested groups with their scenario counts.
+/// Transparent groups with empty name are not listed, their groups are.
+fn group_counts_recursive(
+    group: &dyn ScenarioGroup,
+    prefix: &str,
+    counts: &mut Vec<(String, usize)>,
+) {
+    for group in group.groups() {
+        let name = join_name(prefix, group.name());
+        if !group.name().is_empty() {
+            counts.push((name.clone(), count_group_scenarios(group.as_ref())));
+        }
+        group_counts_recursive(group.as_ref(), &name, counts);
+    }
+}
+
+/// Render nested groups and scenarios of the group as indented tree lines.
+/// Transparent groups with empty name are not rendered, their contents are.
+///
+/// * `group` - Group to render.
+/// * `depth` - Nesting level, entries are indented by two spaces per level.
+/// * `counts` - Annotate group lines with number of scenarios.
+/// * `lines` - Rendered lines.
+fn tree_recursive(group: &dyn ScenarioGroup, depth: usize, counts: bool, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    for group in group.groups() {
+        if group.name().is_empty() {
+            tree_recursive(group.as_ref(), depth, counts, lines);
+            continue;
+        }
+        if counts {
+            let count = count_group_scenarios(group.as_ref());
+            lines.push(format!("{indent}{}/ ({count})", group.name()));
+        } else {
+            lines.push(format!("{indent}{}/", group.name()));
+        }
+        tree_recursive(group.as_ref(), depth + 1, counts, lines);
+    }
+    for scenario in group.scenarios() {
+        lines.push(format!("{indent}{}", scenario.name()));
+    }
+}
+
+/// Create JSON catalog of the group, including nested groups and scenarios.
+fn catalog_recursive(group: &dyn ScenarioGroup) -> Value {
+    let groups: Vec<Value> = group
+        .groups()
+        .iter()
+        .map(|g| catalog_recursive(g.as_ref()))
+        .collect();
+    let scenarios: Vec<Value> = group
+        .scenarios()
+        .iter()
+        .map(|s| {
+            json!({
+                "name": s.name(),
+                "tags": s.tags(),
+                "description": s.description(),
+            })
+        })
+        .collect();
+
+    json!({
+        "name": group.name(),
+        "groups": groups,
+        "scenarios": scenarios,
+    })
+}
+
+/// Slot receiving structured output of scenario run with [`Scenario::run_output_with_env`].
+type OutputSlot = Arc<Mutex<Option<Value>>>;
+
+/// Run scenario and classify the result.
+fn run_scenario_outcome(
+    scenario: &dyn Scenario,
+    input: &str,
+    token: &CancellationToken,
+    metrics: &MetricsSink,
+    deadline: Option<Instant>,
+) -> Outcome {
+    run_scenario_output(scenario, input, token, metrics, deadline, None)
+}
+
+/// Run scenario and classify the result, see [`run_scenario_outcome`].
+/// If output slot is provided, scenario is run with [`Scenario::run_output_with_env`]
+/// and its output is stored in the slot.
+fn run_scenario_output(
+    scenario: &dyn Scenario,
+    input: &str,
+    token: &CancellationToken,
+    metrics: &MetricsSink,
+    deadline: Option<Instant>,
+    output: Option<&OutputSlot>,
+) -> Outcome {
+    if let Some(reason) = scenario.skip_reason(input) {
+        return Outcome::Skipped(reason);
+    }
+    if let Some(schema) = scenario.input_schema() {
+        if let Err(error) = validate_input(schema, input) {
+            return Outcome::Failed(error);
+        }
+    }
+
+    if let Some(hint) = scenario.deprecated() {
+        log_warn!("Scenario {} is deprecated: {hint}", scenario.name());
+    }
+
+    // Environment is dropped after run, removing its temporary directory.
+    let env = ScenarioEnv::new(scenario.name(), input, token.clone())
+        .with_metrics(metrics.clone())
+        .with_deadline(deadline);
+    let run = || match output {
+        Some(output) => scenario.run_output_with_env(&env).map(|value| {
+            *output.lock().unwrap_or_else(|e| e.into_inner()) = Some(value);
+        }),
+        None => scenario.run_with_env(&env),
+    };
+    let outcome = match catch_panic(run) {
+        Ok(Ok(())) => match env.sub_results().failure_summary() {
+            Some(summary) => Outcome::Failed(summary),
+            None => Outcome::Passed,
+        },
+        Ok(Err(error)) => Outcome::Failed(error),
+        Err(message) => Outcome::Panicked(message),
+    };
+
+    outcome
+}
+
+/// Check whether run of scenario exceeded [`Scenario::slow_after`], warning if it did.
+/// Slowness is judged by execution time of the run, as reported in [`RunResult::duration`].
+///
+/// * `scenario` - Scenario which was run.
+/// * `name` - Name of the run, e.g., fully-qualified scenario name.
+/// * `duration` - Execution time of the run.
+fn check_slow(scenario: &dyn Scenario, name: &str, duration: Duration) -> bool {
+    let Some(slow_after) = scenario.slow_after().filter(|limit| duration > *limit) else {
+        return false;
+    };
+    log_warn!(
+        "Scenario {name} is slow, took {} ms, expected at most {} ms",
+        duration.as_millis(),
+        slow_after.as_millis()
+    );
+    true
+}
+
+/// Find scenario and run it, storing its output in the slot if provided.
+fn find_and_run_outcome(
+    root_group: &dyn ScenarioGroup,
+    name: &str,
+    input: &str,
+    token: &CancellationToken,
+    metrics: &MetricsSink,
+    deadline: Option<Instant>,
+    output: Option<&OutputSlot>,
+) -> Outcome {
+    match root_group.find_scenario(name) {
+        Some(scenario) => run_scenario_output(scenario, input, token, metrics, deadline, output),
+        None => Outcome::Failed(format!("Scenario {name} not found")),
+    }
+}
+
+/// Run scenario, measuring its execution time and notifying observer.
+///
+/// * `name` - Name of the scenario.
+/// * `input` - Test scenario input.
+/// * `observer` - Observer notified about start and finish of the scenario.
+/// * `run` - Runs the scenario, recording its metrics into provided sink.
+fn run_observed(
+    name: &str,
+    input: &str,
+    observer: &dyn RunObserver,
+    run: impl FnOnce(&MetricsSink) -> Outcome,
+) -> RunResult {
+    observer.on_start_with_input(name, input);
+    log_debug!("Scenario {name} started");
+    // Each run has its own sink, metrics are not shared between runs.
+    let metrics = MetricsSink::new();
+    let start = Instant::now();
+    let outcome = run(&metrics);
+    let duration = start.elapsed();
+    log_debug!("Scenario {name} finished: {}", outcome.status());
+    observer.on_finish(name, &outcome, duration);
+    RunResult {
+        metrics: metrics.snapshot(),
+        ..RunResult::new(name, outcome, duration).with_input(input)
+    }
+}
+
+/// Skip reason of scenarios not run due to reached failure limit.
+pub(crate) const MAX_FAILURES_REASON: &str = "Maximum number of failures reached";
+
+/// Skip reason of disabled scenarios.
+pub(crate) const DISABLED_REASON: &str = "Disabled";
+
+/// Skip reason of scenarios not started after [`RunOptions::deadline`].
+pub(crate) const GLOBAL_TIMEOUT_REASON: &str = "Not run due to global timeout";
+
+/// Options of multi-scenario runs performed by [`TestContext::run_many`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Maximum execution time of each scenario, overrides scenario timeout.
+    pub timeout: Option<Duration>,
+
+    /// Time after which cancellation of each scenario is requested.
+    pub soft_timeout: Option<Duration>,
+
+    /// Number of failures after which remaining scenarios are not run.
+    pub max_failures: Option<usize>,
+
+    /// Number of scenarios run in parallel, scenarios are run sequentially if not set.
+    pub parallelism: Option<usize>,
+
+    /// Scenario toggles, disabled scenarios are reported as skipped.
+    pub toggles: ScenarioToggles,
+
+    /// Instant after which remaining scenarios are not started.
+    pub deadline: Option<Instant>,
+
+    /// Number of times a failed scenario is run again.
+    /// Overrides [`GroupPolicy::retries`], failed scenarios are not retried if neither is set.
+    pub retries: Option<usize>,
+
+    /// Failures are retried only if error contains this text, all failures are retried if not set.
+    pub retry_if_contains: Option<String>,
+
+    /// Run each scenario in a separate runner process, scenarios are run in this process if not set.
+    pub isolation: Option<Isolation>,
+
+    /// Collect resource usage of each scenario.
+    /// Usage is measured for the whole process, so it is only attributable to sequential runs.
+    pub resource_stats: bool,
+}
+
+/// Run scenario again while it fails with a retryable error, at most `retries` times.
+/// Outcome of the last run is returned.
+///
+/// * `retries` - Number of times a failed scenario is run again.
+/// * `retry_if_contains` - Failures are retried only if error contains this text.
+/// * `run` - Runs the scenario.
+pub(crate) fn run_with_retries(
+    retries: usize,
+    retry_if_contains: Option<&str>,
+    mut run: impl FnMut() -> Outcome,
+) -> Outcome {
+    let mut outcome = run();
+    for attempt in 1..=retries {
+        let retryable = !outcome.is_ok()
+            && retry_if_contains.map_or(true, |text| outcome.to_string().contains(text));
+        if !retryable {
+            break;
+        }
+        log_info!("Retrying failed scenario, attempt {attempt} of {retries}: {outcome}");
+        outcome = run();
+    }
+    outcome
+}
+
+/// Result of a single scenario run performed by [`TestContext::run_many`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunResult {
+    /// Name of the scenario.
+    pub name: String,
+
+    /// Scenario outcome.
+    pub outcome: Outcome,
+
+    /// Scenario execution time.
+    pub duration: Duration,
+
+    /// Metrics recorded by the scenario.
+    pub metrics: Vec<(String, f64)>,
+
+    /// Test scenario input the scenario was run with.
+    pub input: String,
+
+    /// Scenario is deprecated, see [`Scenario::deprecated`].
+    pub deprecated: bool,
+
+    /// Scenario took longer than [`Scenario::slow_after`], the outcome is not affected.
+    pub slow: bool,
+
+    /// Resource usage of the run, `None` if not collected.
+    pub resource_usage: Option<ResourceUsage>,
+}
+
+impl RunResult {
+    /// Create run result without metrics.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `outcome` - Scenario outcome.
+    /// * `duration` - Scenario execution time.
+    pub fn new(name: &str, outcome: Outcome, duration: Duration) -> Self {
+        RunResult {
+            name: name.to_string(),
+            outcome,
+            duration,
+            metrics: Vec::new(),
+            input: String::new(),
+            deprecated: false,
+            slow: false,
+            resource_usage: None,
+        }
+    }
+
+    /// Set test scenario input the scenario was run with.
+    ///
+    /// * `input` - Test scenario input.
+    pub fn with_input(mut self, input: &str) -> Self {
+        self.input = input.to_string();
+        self
+    }
+}
+
+/// Results of a multi-scenario run performed by [`TestContext::run_many_fallible`].
+/// Run might be aborted by an error, results of scenarios run before are kept.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PartialResults {
+    /// Results of the scenarios run, in order of runs.
+    pub results: Vec<RunResult>,
+
+    /// Error which aborted the run, `None` if all scenarios were run.
+    pub error: Option<String>,
+}
+
+impl PartialResults {
+    /// Aggregate statistics of the scenarios run.
+    pub fn stats(&self) -> RunStats {
+        RunStats::from_outcomes(&self.results)
+    }
+}
+
+impl From<Vec<RunResult>> for PartialResults {
+    fn from(results: Vec<RunResult>) -> Self {
+        PartialResults {
+            results,
+            error: None,
+        }
+    }
+}
+
+/// Aggregate statistics of a multi-scenario run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    /// Number of scenarios run.
+    pub total: usize,
+
+    /// Number of passed scenarios.
+    pub passed: usize,
+
+    /// Number of failed scenarios, including panicked ones.
+    pub failed: usize,
+
+    /// Number of skipped scenarios.
+    pub skipped: usize,
+
+    /// Number of scenarios which did not finish before timeout.
+    pub timed_out: usize,
+
+    /// Number of failed or timed out scenarios which are quarantined,
+    /// they are also counted in `failed` and `timed_out`.
+    pub quarantined: usize,
+
+    /// Sum of execution times of all scenarios.
+    pub total_duration: Duration,
+}
+
+impl RunStats {
+    /// Create statistics from outcomes of run scenarios.
+    ///
+    /// * `results` - Results of run scenarios.
+    pub fn from_outcomes(results: &[RunResult]) -> Self {
+        let mut stats = RunStats {
+            total: results.len(),
+            ..Default::default()
+        };
+        for result in results {
+            match result.outcome {
+                Outcome::Passed => stats.passed += 1,
+                Outcome::Failed(_) | Outcome::Panicked(_) => stats.failed += 1,
+                Outcome::Skipped(_) => stats.skipped += 1,
+                Outcome::TimedOut => stats.timed_out += 1,
+            }
+            stats.total_duration += result.duration;
+        }
+        stats
+    }
+
+    /// Create statistics from outcomes of run scenarios, counting quarantined failures.
+    ///
+    /// * `results` - Results of run scenarios.
+    /// * `quarantine` - Names of quarantined scenarios.
+    pub fn from_outcomes_with_quarantine(results: &[RunResult], quarantine: &[String]) -> Self {
+        let mut stats = RunStats::from_outcomes(results);
+        stats.quarantined = results
+            .iter()
+            .filter(|result| !result.outcome.is_ok() && quarantine.contains(&result.name))
+            .count();
+        stats
+    }
+
+    /// Serialize statistics to JSON.
+    /// Duration is reported in microseconds.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "total": self.total,
+            "passed": self.passed,
+            "failed": self.failed,
+            "skipped": self.skipped,
+            "timed_out": self.timed_out,
+            "quarantined": self.quarantined,
+            "total_duration_us": self.total_duration.as_micros() as u64,
+        })
+    }
+}
+
+/// Observer of scenario runs performed by [`TestContext::run_many`].
+pub trait RunObserver: Sync {
+    /// Called before scenario is run.
+    ///
+    /// * `name` - Name of the scenario.
+    fn on_start(&self, _name: &str) {}
+
+    /// Called before scenario is run, with input the scenario is run with.
+    /// Calls [`RunObserver::on_start`] by default.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `input` - Test scenario input.
+    fn on_start_with_input(&self, name: &str, _input: &str) {
+        self.on_start(name);
+    }
+
+    /// Called after scenario finished, including failures, panics and timeouts.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `outcome` - Scenario outcome.
+    /// * `duration` - Scenario execution time.
+    fn on_finish(&self, _name: &str, _outcome: &Outcome, _duration: Duration) {}
+
+    /// Called with result of each scenario, including scenarios not run, e.g., disabled.
+    /// Result is final, e.g., named with matrix label of the run, as returned to the caller.
+    ///
+    /// * `result` - Scenario run result.
+    fn on_result(&self, _result: &RunResult) {}
+}
+
+/// Observer ignoring all notifications.
+pub struct NoopObserver;
+
+impl RunObserver for NoopObserver {}
+
+/// Hook invoked with the scenario name, see [`TestContext::set_before_each`].
+type ScenarioHook = Box<dyn Fn(&str) + Send + Sync>;
+
 /// Test context. Responsible for listing and running scenarios.
 pub struct TestContext {
+    root_group: Arc<dyn ScenarioGroup>,
+    before_each: Option<ScenarioHook>,
+    after_each: Option<ScenarioHook>,
+    separator: char,
+    default_timeout: Option<Duration>,
+    case_insensitive: bool,
+}
+
+impl fmt::Debug for TestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestContext")
+            .field("root_group", &self.root_group)
+            .field("before_each", &self.before_each.is_some())
+            .field("after_each", &self.after_each.is_some())
+            .field("separator", &self.separator)
+            .field("default_timeout", &self.default_timeout)
+            .field("case_insensitive", &self.case_insensitive)
+            .finish()
+    }
+}
+
+/// Builder of [`TestContext`], for contexts with options not available in [`TestContext::new`].
+pub struct TestContextBuilder {
     root_group: Box<dyn ScenarioGroup>,
+    before_each: Option<ScenarioHook>,
+    after_each: Option<ScenarioHook>,
+    separator: char,
+    default_timeout: Option<Duration>,
+    case_insensitive: bool,
 }
 
-impl TestContext {
-    /// Create test context.
+impl TestContextBuilder {
+    /// Set separator of group and scenario names in fully-qualified names, `.` by default.
+    /// Affects names accepted and returned by the test context.
     ///
-    /// * `root_group` - Root test scenario group.
-    pub fn new(root_group: Box<dyn ScenarioGroup>) -> Self {
-        TestContext { root_group }
+    /// * `separator` - Name separator.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
     }
 
-    /// Run test scenario.
+    /// Set hook invoked before each scenario run, see [`TestContext::set_before_each`].
     ///
-    /// * `name` - Name of the scenario to run.
-    /// * `input` - Test scenario input.
-    pub fn run(&self, name: &str, input: &str) -> Result<(), String> {
-        let scenario = self.root_group.find_scenario(name);
-        match scenario {
-            Some(scenario) => scenario.run(input),
-            None => Err(format!("Scenario {name} not found")),
-        }
+    /// * `hook` - Hook to invoke.
+    pub fn before_each(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.before_each = Some(Box::new(hook));
+        self
     }
 
-    /// List available scenarios.
-    pub fn list_scenarios(&self) -> Vec<String> {
-        list_scenarios_recursive(self.root_group.as_ref(), "".to_string())
+    /// Set hook invoked after each scenario run, see [`TestContext::set_after_each`].
+    ///
+    /// * `hook` - Hook to invoke.
+    pub fn after_each(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.after_each = Some(Box::new(hook));
+        self
+    }
+
+    /// Set timeout of scenarios without timeout of their own or of their groups.
+    /// See [`TestContext::run_with_timeout`] for precedence.
+    ///
+    /// * `timeout` - Default scenario timeout.
+    pub fn default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Match scenario and group names regardless of case, exact matches take precedence.
+    ///
+    /// * `case_insensitive` - Ignore case of names.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Create test context.
+    /// Fails if scenario aliases conflict, see [`TestContext::try_new`],
+    /// or if separator is used in a scenario or group name.
+    pub fn build(self) -> Result<TestContext, String> {
+        let mut context = TestContext::try_new(self.root_group)?;
+        if self.separator != '.' {
+            let mut names = list_scenarios_recursive(context.root_group.as_ref(), String::new())
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            names.extend(
+                context
+                    .group_scenario_counts()
+                    .into_iter()
+                    .map(|(name, _)| name),
+            );
+            if let Some(name) = names.iter().find(|name| name.contains(self.separator)) {
+                return Err(format!(
+                    "Separator {:?} is used in name {name}",
+                    self.separator
+                ));
+            }
+        }
+        context.before_each = self.before_each;
+        context.after_each = self.after_each;
+        context.separator = self.separator;
+        context.default_timeout = self.default_timeout;
+        context.case_insensitive = self.case_insensitive;
+        Ok(context)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::scenario::{Scenario, ScenarioGroup, ScenarioGroupImpl};
-    use crate::test_context::TestContext;
+impl TestContext {
+    /// Create test context.
+    ///
+    /// * `root_group` - Root test scenario group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if scenario alias conflicts with another scenario, see [`TestContext::try_new`].
+    pub fn new(root_group: Box<dyn ScenarioGroup>) -> Self {
+        Self::try_new(root_group).unwrap_or_else(|e| panic!("{e}"))
+    }
 
-    struct ScenarioStub {
-        name: String,
+    /// Create test context.
+    /// Fails if scenario alias conflicts with name or alias of another scenario of the same group.
+    ///
+    /// * `root_group` - Root test scenario group.
+    pub fn try_new(root_group: Box<dyn ScenarioGroup>) -> Result<Self, String> {
+        check_aliases(root_group.as_ref(), "")?;
+        Ok(TestContext {
+            root_group: Arc::from(root_group),
+            before_each: None,
+            after_each: None,
+            separator: '.',
+            default_timeout: None,
+            case_insensitive: false,
+        })
     }
 
-    impl Scenario for ScenarioStub {
-        fn name(&self) -> &str {
-            &self.name
+    /// Create builder of test context.
+    ///
+    /// * `root_group` - Root test scenario group.
+    pub fn builder(root_group: Box<dyn ScenarioGroup>) -> TestContextBuilder {
+        TestContextBuilder {
+            root_group,
+            before_each: None,
+            after_each: None,
+            separator: '.',
+            default_timeout: None,
+            case_insensitive: false,
         }
+    }
 
-        fn run(&self, input: &str) -> Result<(), String> {
-            match input {
-                "ok" => Ok(()),
-                "error" => Err("Requested error".to_string()),
-                _ => Err("Missing input".to_string()),
-            }
+    /// Strip run label from scenario name, e.g., matrix label `name[param=a]` to `name`.
+    /// Labeled names identify separate runs of the same scenario and resolve to the scenario.
+    /// Names of existing scenarios and groups are returned unchanged.
+    ///
+    /// * `name` - Fully-qualified name using the context separator, possibly labeled.
+    pub(crate) fn unlabeled_name<'a>(&self, name: &'a str) -> &'a str {
+        let internal = name.replace(self.separator, ".");
+        if !name.ends_with(']')
+            || self.root_group.find_scenario(&internal).is_some()
+            || self.root_group.find_group(&internal).is_some()
+        {
+            return name;
         }
+        name.split_once('[').map_or(name, |(base, _)| base)
     }
 
-    fn init_group() -> Box<dyn ScenarioGroup> {
-        let scenario_inner = ScenarioStub {
-            name: "inner_scenario".to_string(),
-        };
-        let group_inner =
-            ScenarioGroupImpl::new("inner_group", vec![Box::new(scenario_inner)], vec![]);
-        let scenario_outer = ScenarioStub {
-            name: "outer_scenario".to_string(),
+    /// Translate name using the context separator to name using `.` separator,
+    /// as expected by [`ScenarioGroup`].
+    /// Run label is stripped, see [`TestContext::unlabeled_name`].
+    /// For case-insensitive contexts, name of scenario or group differing only in case is returned.
+    ///
+    /// * `name` - Fully-qualified name using the context separator.
+    fn internal_name(&self, name: &str) -> String {
+        let name = self.unlabeled_name(name).replace(self.separator, ".");
+        let exists = |name: &str| {
+            self.root_group.find_scenario(name).is_some()
+                || self.root_group.find_group(name).is_some()
         };
-        let group_outer = ScenarioGroupImpl::new(
-            "outer_group",
-            vec![Box::new(scenario_outer)],
-            vec![Box::new(group_inner)],
-        );
+        if !self.case_insensitive || exists(&name) {
+            return name;
+        }
 
-        Box::new(group_outer)
+        let lowercase = name.to_lowercase();
+        let mut candidates = list_scenarios_recursive(self.root_group.as_ref(), String::new())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        let mut groups = Vec::new();
+        group_counts_recursive(self.root_group.as_ref(), "", &mut groups);
+        candidates.extend(groups.into_iter().map(|(name, _)| name));
+        candidates
+            .into_iter()
+            .find(|candidate| candidate.to_lowercase() == lowercase)
+            .unwrap_or(name)
     }
 
-    #[test]
-    fn test_run_some_input_ok() {
-        let root_group = init_group();
-        let context = TestContext::new(root_group);
-        let result = context.run("inner_group.inner_scenario", "ok");
+    /// Translate name using `.` separator to name using the context separator.
+    ///
+    /// * `name` - Fully-qualified name using `.` separator.
+    fn external_name(&self, name: String) -> String {
+        if self.separator == '.' {
+            name
+        } else {
+            name.replace('.', &self.separator.to_string())
+        }
+    }
 
-        assert!(result.is_ok());
+    /// Set hook invoked with the scenario name before each scenario run.
+    /// Hook might be invoked concurrently, e.g., for parallel runs.
+    ///
+    /// * `hook` - Hook to invoke.
+    pub fn set_before_each(&mut self, hook: impl Fn(&str) + Send + Sync + 'static) {
+        self.before_each = Some(Box::new(hook));
     }
 
-    #[test]
-    fn test_run_some_input_err() {
-        let root_group = init_group();
-        let context = TestContext::new(root_group);
-        let result = context.run("inner_group.inner_scenario", "error");
+    /// Set hook invoked with the scenario name after each scenario run.
+    /// Hook is invoked regardless of the outcome, also after timeouts.
+    /// Hook might be invoked concurrently, e.g., for parallel runs.
+    ///
+    /// * `hook` - Hook to invoke.
+    pub fn set_after_each(&mut self, hook: impl Fn(&str) + Send + Sync + 'static) {
+        self.after_each = Some(Box::new(hook));
+    }
 
-        assert!(result.is_err_and(|e| e == "Requested error"));
+    /// Invoke `run` between before and after hooks.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `run` - Runs the scenario.
+    fn with_hooks<T>(&self, name: &str, run: impl FnOnce() -> T) -> T {
+        if let Some(before_each) = &self.before_each {
+            before_each(name);
+        }
+        let result = run();
+        if let Some(after_each) = &self.after_each {
+            after_each(name);
+        }
+        result
     }
 
-    #[test]
-    fn test_run_not_found() {
-        let root_group = init_group();
-        let context = TestContext::new(root_group);
-        let result = context.run("some_scenario", "");
+    /// Check whether scenario with fully-qualified name exists.
+    ///
+    /// * `name` - Name of the scenario to check.
+    pub fn contains_scenario(&self, name: &str) -> bool {
+        self.find_scenario(name).is_some()
+    }
 
-        assert!(result.is_err_and(|e| e == "Scenario some_scenario not found"));
+    /// Check whether group with fully-qualified name exists.
+    ///
+    /// * `name` - Name of the group to check.
+    pub fn contains_group(&self, name: &str) -> bool {
+        self.root_group
+            .find_group(&self.internal_name(name))
+            .is_some()
     }
 
-    #[test]
-    fn test_list_scenarios_ok() {
-        let root_group = init_group();
-        let context = TestContext::new(root_group);
-        let result = context.list_scenarios();
+    /// Check whether scenario with fully-qualified name is deprecated.
+    ///
+    /// * `name` - Name of the scenario to check.
+    pub(crate) fn is_deprecated(&self, name: &str) -> bool {
+        self.find_scenario(name)
+            .is_some_and(|scenario| scenario.deprecated().is_some())
+    }
 
-        assert_eq!(result.len(), 2);
-        assert_eq!(result[0], "inner_group.inner_scenario");
-        assert_eq!(result[1], "outer_scenario");
+    /// Check whether run of scenario with fully-qualified name exceeded [`Scenario::slow_after`],
+    /// warning if it did. Called once per result, the flag is stored in [`RunResult::slow`].
+    ///
+    /// * `name` - Name of the scenario to check.
+    /// * `duration` - Execution time of the run.
+    pub(crate) fn check_slow(&self, name: &str, duration: Duration) -> bool {
+        self.find_scenario(name)
+            .is_some_and(|scenario| check_slow(scenario, name, duration))
+    }
+
+    /// Get tags of scenario with fully-qualified name, including tags inherited from its groups.
+    /// Returns no tags if scenario is not found.
+    ///
+    /// * `name` - Name of the scenario.
+    pub(crate) fn tags(&self, name: &str) -> Vec<String> {
+        let Some(scenario) = self.find_scenario(name) else {
+            return Vec::new();
+        };
+        list_tagged_scenarios_recursive(self.root_group.as_ref(), "".to_string(), &[])
+            .into_iter()
+            .find(|s| std::ptr::addr_eq(s.scenario, scenario))
+            .map_or_else(Vec::new, |s| s.tags())
+    }
+
+    /// Get policy applied to scenario with fully-qualified name, combined from all its groups.
+    /// Returns default policy if scenario is not found.
+    ///
+    /// * `name` - Name of the scenario.
+    pub(crate) fn policy(&self, name: &str) -> GroupPolicy {
+        let name = self.internal_name(name);
+        resolve_policy(self.root_group.as_ref(), &name, &GroupPolicy::default()).unwrap_or_default()
+    }
+
+    /// Get execution priority of scenario with fully-qualified name.
+    /// Returns default priority if scenario is not found.
+    ///
+    /// * `name` - Name of the scenario.
+    pub(crate) fn priority(&self, name: &str) -> i32 {
+        self.find_scenario(name)
+            .map_or(0, |scenario| scenario.priority())
+    }
+
+    /// Resolve scenario name to its canonical fully-qualified name.
+    /// Query might use an alias, returns `None` if no scenario matches.
+    ///
+    /// * `name` - Fully-qualified name or alias of the scenario.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        let scenario = self.find_scenario(name)?;
+        self.list_scenarios().into_iter().find(|canonical| {
+            self.find_scenario(canonical)
+                .is_some_and(|candidate| std::ptr::addr_eq(candidate, scenario))
+        })
+    }
+
+    /// Find scenario by fully-qualified name.
+    ///
+    /// * `name` - Name of the scenario to find.
+    pub(crate) fn find_scenario(&self, name: &str) -> Option<&dyn Scenario> {
+        self.root_group.find_scenario(&self.internal_name(name))
+    }
+
+    /// Run test scenario.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    pub fn run(&self, name: &str, input: &str) -> Result<(), String> {
+        self.run_outcome(name, input).into()
+    }
+
+    /// Run test scenario and classify its outcome.
+    /// Panics are caught and reported as [`Outcome::Panicked`].
+    /// Timeouts are applied as in [`TestContext::run_with_timeout`] without provided timeout.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    pub fn run_outcome(&self, name: &str, input: &str) -> Outcome {
+        self.run_with_deadlines(name, input, None, None, &MetricsSink::new())
+    }
+
+    /// Validate test scenario input against input schema declared by the scenario, without running it.
+    /// Any input is valid for scenarios without schema.
+    ///
+    /// * `name` - Name of the scenario.
+    /// * `input` - Test scenario input.
+    pub fn validate_input(&self, name: &str, input: &str) -> Result<(), String> {
+        let scenario = match self.find_scenario(name) {
+            Some(scenario) => scenario,
+            None => return Err(format!("Scenario {name} not found")),
+        };
+        match scenario.input_schema() {
+            Some(schema) => validate_input(schema, input),
+            None => Ok(()),
+        }
+    }
+
+    /// Run test scenario and get its structured output.
+    /// Scenario is run as with [`TestContext::run_output_with_deadlines`] without provided timeouts,
+    /// outcomes other than passed, e.g., panics or skips, are reported as errors.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    pub fn run_output(&self, name: &str, input: &str) -> Result<Value, String> {
+        match self.run_output_with_deadlines(name, input, None, None) {
+            (Outcome::Passed, output) => Ok(output.unwrap_or(Value::Null)),
+            (outcome, _) => Err(outcome.to_string()),
+        }
+    }
+
+    /// Run test scenario with timeout.
+    /// Scenario is run on a separate thread, which is abandoned if timeout is exceeded.
+    /// Cancellation token passed to [`Scenario::run_with_cancel`] is cancelled on timeout.
+    ///
+    /// Timeout precedence:
+    /// 1. `timeout` provided to this method (e.g., `--timeout` CLI argument).
+    /// 2. [`Scenario::timeout`] declared by the scenario.
+    /// 3. [`GroupPolicy::timeout`] of the groups of the scenario.
+    /// 4. Default timeout of the context, see [`TestContextBuilder::default_timeout`].
+    /// 5. Unlimited - scenario is run on the current thread.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    /// * `timeout` - Maximum scenario execution time, overrides scenario timeout.
+    pub fn run_with_timeout(&self, name: &str, input: &str, timeout: Option<Duration>) -> Outcome {
+        self.run_with_metrics(name, input, timeout, &MetricsSink::new())
+    }
+
+    /// Run test scenario with timeout, collecting its metrics.
+    /// See [`TestContext::run_with_timeout`] for timeout handling.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    /// * `timeout` - Maximum scenario execution time, overrides scenario timeout.
+    /// * `metrics` - Sink collecting metrics recorded by the scenario.
+    pub fn run_with_metrics(
+        &self,
+        name: &str,
+        input: &str,
+        timeout: Option<Duration>,
+        metrics: &MetricsSink,
+    ) -> Outcome {
+        self.run_with_deadlines(name, input, None, timeout, metrics)
+    }
+
+    /// Run test scenario with two-stage timeout, collecting its metrics.
+    /// At soft timeout, cancellation token is cancelled and a warning is logged,
+    /// scenario is still awaited until hard timeout.
+    /// At hard timeout, scenario thread is abandoned and [`Outcome::TimedOut`] is returned.
+    /// Hard timeout defaults to [`Scenario::timeout`], then to [`GroupPolicy::timeout`],
+    /// then to default timeout of the context.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    /// * `soft_timeout` - Time after which cancellation is requested.
+    /// * `hard_timeout` - Maximum scenario execution time, overrides scenario timeout.
+    /// * `metrics` - Sink collecting metrics recorded by the scenario.
+    pub fn run_with_deadlines(
+        &self,
+        name: &str,
+        input: &str,
+        soft_timeout: Option<Duration>,
+        hard_timeout: Option<Duration>,
+        metrics: &MetricsSink,
+    ) -> Outcome {
+        self.with_hooks(name, || {
+            self.run_with_deadlines_unhooked(name, input, soft_timeout, hard_timeout, metrics, None)
+        })
+    }
+
+    /// Run test scenario with two-stage timeout and get its structured output.
+    /// Scenario is run with [`Scenario::run_output_with_env`], otherwise as with
+    /// [`TestContext::run_with_deadlines`], including skip checks and hooks.
+    /// Output is `None` unless scenario passed.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input.
+    /// * `soft_timeout` - Time after which cancellation is requested.
+    /// * `hard_timeout` - Maximum scenario execution time, overrides scenario timeout.
+    pub fn run_output_with_deadlines(
+        &self,
+        name: &str,
+        input: &str,
+        soft_timeout: Option<Duration>,
+        hard_timeout: Option<Duration>,
+    ) -> (Outcome, Option<Value>) {
+        let output = OutputSlot::default();
+        let outcome = self.with_hooks(name, || {
+            self.run_with_deadlines_unhooked(
+                name,
+                input,
+                soft_timeout,
+                hard_timeout,
+                &MetricsSink::new(),
+                Some(&output),
+            )
+        });
+        // Output of abandoned scenario thread is ignored.
+        let output = outcome
+            .is_ok()
+            .then(|| output.lock().unwrap_or_else(|e| e.into_inner()).take())
+            .flatten();
+        (outcome, output)
+    }
+
+    /// Run test scenario with two-stage timeout, without invoking hooks.
+    /// See [`TestContext::run_with_deadlines`].
+    fn run_with_deadlines_unhooked(
+        &self,
+        name: &str,
+        input: &str,
+        soft_timeout: Option<Duration>,
+        hard_timeout: Option<Duration>,
+        metrics: &MetricsSink,
+        output: Option<&OutputSlot>,
+    ) -> Outcome {
+        let scenario = match self.find_scenario(name) {
+            Some(scenario) => scenario,
+            None => return Outcome::Failed(format!("Scenario {name} not found")),
+        };
+        let token = CancellationToken::new();
+        let hard_timeout = hard_timeout
+            .or_else(|| scenario.timeout())
+            .or_else(|| self.policy(name).timeout)
+            .or(self.default_timeout);
+        if soft_timeout.is_none() && hard_timeout.is_none() {
+            return run_scenario_output(scenario, input, &token, metrics, None, output);
+        }
+
+        let root_group = Arc::clone(&self.root_group);
+        let thread_name = self.internal_name(name);
+        let input = input.to_string();
+        let thread_token = token.clone();
+        let thread_metrics = metrics.clone();
+        let thread_output = output.cloned();
+        let (sender, receiver) = mpsc::channel();
+        let start = Instant::now();
+        let deadline = hard_timeout.map(|hard_timeout| start + hard_timeout);
+        // Records logged by the scenario keep fields of the caller span, e.g., run id.
+        #[cfg(feature = "tracing")]
+        let span = tracing::Span::current();
+        thread::spawn(move || {
+            #[cfg(feature = "tracing")]
+            let _entered = span.enter();
+            let outcome = find_and_run_outcome(
+                root_group.as_ref(),
+                &thread_name,
+                &input,
+                &thread_token,
+                &thread_metrics,
+                deadline,
+                thread_output.as_ref(),
+            );
+            // Receiver might be already dropped after timeout.
+            let _ = sender.send(outcome);
+        });
+
+        // Soft timeout is only relevant if it precedes hard timeout.
+        let soft_timeout = match (soft_timeout, hard_timeout) {
+            (Some(soft), Some(hard)) if soft >= hard => None,
+            (soft, _) => soft,
+        };
+        if let Some(soft_timeout) = soft_timeout {
+            match receiver.recv_timeout(soft_timeout) {
+                Ok(outcome) => return outcome,
+                Err(RecvTimeoutError::Timeout) => {
+                    token.cancel();
+                    log_warn!("Scenario {name} exceeded soft timeout, cancellation requested");
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Outcome::Panicked("Scenario thread terminated unexpectedly".to_string())
+                }
+            }
+        }
+
+        let result = match hard_timeout {
+            Some(hard_timeout) => {
+                receiver.recv_timeout(hard_timeout.saturating_sub(start.elapsed()))
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match result {
+            Ok(outcome) => outcome,
+            Err(RecvTimeoutError::Timeout) => {
+                token.cancel();
+                Outcome::TimedOut
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                Outcome::Panicked("Scenario thread terminated unexpectedly".to_string())
+            }
+        }
+    }
+
+    /// Run multiple test scenarios in order.
+    /// Scenarios not run due to reached failure limit or deadline are reported as skipped.
+    /// With [`RunOptions::parallelism`] scenarios are started in order, but might finish in any order.
+    /// Results are always returned in order of `runs`.
+    ///
+    /// * `runs` - Names of the scenarios to run with their inputs.
+    /// * `options` - Run options.
+    /// * `observer` - Observer notified about start and finish of each scenario.
+    pub fn run_many(
+        &self,
+        runs: &[(String, String)],
+        options: &RunOptions,
+        observer: &dyn RunObserver,
+    ) -> Vec<RunResult> {
+        self.run_many_fallible(runs.iter().cloned().map(Ok), options, observer)
+            .results
+    }
+
+    /// Run multiple test scenarios in order, aborting on the first erroneous run.
+    /// Runs are taken from `runs` only when they are about to start,
+    /// so errors occurring while runs are produced abort the remaining runs.
+    /// Results of scenarios run before the abort are returned together with the error.
+    /// See [`TestContext::run_many`] for failure limit and parallelism handling.
+    ///
+    /// * `runs` - Names of the scenarios to run with their inputs, or errors aborting the run.
+    /// * `options` - Run options.
+    /// * `observer` - Observer notified about start and finish of each scenario.
+    pub fn run_many_fallible<I>(
+        &self,
+        runs: I,
+        options: &RunOptions,
+        observer: &dyn RunObserver,
+    ) -> PartialResults
+    where
+        I: IntoIterator<Item = Result<(String, String), String>>,
+        I::IntoIter: Send,
+    {
+        let failures = AtomicUsize::new(0);
+        let limit_reached = || {
+            options
+                .max_failures
+                .is_some_and(|max| failures.load(Ordering::SeqCst) >= max)
+        };
+
+        let run_or_skip = |name: &str, input: &str| {
+            if options
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                let outcome = Outcome::Skipped(GLOBAL_TIMEOUT_REASON.to_string());
+                return RunResult::new(name, outcome, Duration::ZERO).with_input(input);
+            }
+            if limit_reached() {
+                let outcome = Outcome::Skipped(MAX_FAILURES_REASON.to_string());
+                return RunResult::new(name, outcome, Duration::ZERO).with_input(input);
+            }
+            if !options.toggles.is_enabled(self.unlabeled_name(name)) {
+                let outcome = Outcome::Skipped(DISABLED_REASON.to_string());
+                return RunResult::new(name, outcome, Duration::ZERO).with_input(input);
+            }
+
+            let usage_start = options.resource_stats.then(ResourceUsage::current);
+            // Usage of isolated runs is collected from runner processes, summed over retries.
+            let isolated_usage = Cell::new(ResourceUsage::default());
+            let mut result = run_observed(name, input, observer, |metrics| {
+                let retry_if_contains = options.retry_if_contains.as_deref();
+                // Isolated runs are retried by the runner process.
+                let retries = options
+                    .retries
+                    .or_else(|| self.policy(name).retries)
+                    .filter(|_| options.isolation.is_none())
+                    .unwrap_or(0);
+                run_with_retries(retries, retry_if_contains, || match &options.isolation {
+                    Some(isolation) => {
+                        let (outcome, usage) = isolation.run_scenario_with_usage(
+                            name,
+                            input,
+                            options.soft_timeout,
+                            options.timeout,
+                        );
+                        isolated_usage.set(isolated_usage.get().combine(&usage));
+                        outcome
+                    }
+                    None => self.run_with_deadlines(
+                        name,
+                        input,
+                        options.soft_timeout,
+                        options.timeout,
+                        metrics,
+                    ),
+                })
+            });
+            result.deprecated = self.is_deprecated(name);
+            result.slow = self.check_slow(name, result.duration);
+            result.resource_usage = usage_start.map(|start| match options.isolation {
+                Some(_) => isolated_usage.get(),
+                None => ResourceUsage::current().since(&start),
+            });
+            if !result.outcome.is_ok() {
+                failures.fetch_add(1, Ordering::SeqCst);
+            }
+            result
+        };
+        let run_one = |(name, input): &(String, String)| {
+            let result = run_or_skip(name, input);
+            observer.on_result(&result);
+            result
+        };
+
+        let runs = runs.into_iter();
+        let parallelism = match options.parallelism {
+            Some(parallelism) if parallelism > 1 => parallelism,
+            _ => {
+                let mut partial = PartialResults::default();
+                for run in runs {
+                    match run {
+                        Ok(run) => partial.results.push(run_one(&run)),
+                        Err(error) => {
+                            partial.error = Some(error);
+                            break;
+                        }
+                    }
+                }
+                return partial;
+            }
+        };
+
+        // Runs are numbered when taken and results are sorted by that number,
+        // so order of results does not depend on order of completion.
+        let source = Mutex::new((runs.enumerate(), None));
+        let completed = Mutex::new(Vec::new());
+        thread::scope(|scope| {
+            for _ in 0..parallelism {
+                scope.spawn(|| loop {
+                    let (index, run) = {
+                        let mut source = source.lock().unwrap();
+                        let (runs, error) = &mut *source;
+                        if error.is_some() {
+                            break;
+                        }
+                        match runs.next() {
+                            Some((index, Ok(run))) => (index, run),
+                            Some((_, Err(e))) => {
+                                *error = Some(e);
+                                break;
+                            }
+                            None => break,
+                        }
+                    };
+                    let result = run_one(&run);
+                    completed.lock().unwrap().push((index, result));
+                });
+            }
+        });
+
+        let (_, error) = source.into_inner().unwrap();
+        let mut completed = completed.into_inner().unwrap();
+        completed.sort_by_key(|(index, _)| *index);
+        PartialResults {
+            results: completed.into_iter().map(|(_, result)| result).collect(),
+            error,
+        }
+    }
+
+    /// Run test scenario by reference, without name resolution.
+    /// Scenario is timed, panics are caught and observer is notified as in [`TestContext::run_many`].
+    /// Borrowed scenario cannot be abandoned: on [`Scenario::timeout`] cancellation token is
+    /// cancelled and [`Outcome::TimedOut`] is returned once scenario finishes.
+    ///
+    /// * `scenario` - Scenario to run.
+    /// * `input` - Test scenario input, empty if not provided.
+    /// * `observer` - Observer notified about start and finish of the scenario.
+    pub fn run_scenario(
+        &self,
+        scenario: &dyn Scenario,
+        input: Option<String>,
+        observer: &dyn RunObserver,
+    ) -> RunResult {
+        let input = input.unwrap_or_default();
+        let run = |metrics: &MetricsSink| {
+            let token = CancellationToken::new();
+            let timeout = match scenario.timeout() {
+                Some(timeout) => timeout,
+                None => return run_scenario_outcome(scenario, &input, &token, metrics, None),
+            };
+
+            let deadline = Instant::now() + timeout;
+            let (sender, receiver) = mpsc::channel();
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    let outcome =
+                        run_scenario_outcome(scenario, &input, &token, metrics, Some(deadline));
+                    // Receiver might be already dropped after timeout.
+                    let _ = sender.send(outcome);
+                });
+                match receiver.recv_timeout(timeout) {
+                    Ok(outcome) => outcome,
+                    Err(RecvTimeoutError::Timeout) => {
+                        token.cancel();
+                        Outcome::TimedOut
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        Outcome::Panicked("Scenario thread terminated unexpectedly".to_string())
+                    }
+                }
+            })
+        };
+        let result = run_observed(scenario.name(), &input, observer, |metrics| {
+            self.with_hooks(scenario.name(), || run(metrics))
+        });
+        let result = RunResult {
+            deprecated: scenario.deprecated().is_some(),
+            slow: check_slow(scenario, &result.name, result.duration),
+            ..result
+        };
+        observer.on_result(&result);
+        result
+    }
+
+    /// Run multiple copies of the same test scenario concurrently.
+    /// Fails if any of the copies failed.
+    ///
+    /// * `name` - Name of the scenario to run.
+    /// * `input` - Test scenario input, shared by all copies.
+    /// * `concurrency` - Number of copies to run.
+    pub fn run_concurrent(
+        &self,
+        name: &str,
+        input: &str,
+        concurrency: usize,
+    ) -> Result<(), String> {
+        let scenario = match self.find_scenario(name) {
+            Some(scenario) => scenario,
+            None => return Err(format!("Scenario {name} not found")),
+        };
+
+        let outcomes: Vec<Outcome> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..concurrency)
+                .map(|_| {
+                    scope.spawn(|| {
+                        self.with_hooks(name, || {
+                            run_scenario_outcome(
+                                scenario,
+                                input,
+                                &CancellationToken::new(),
+                                &MetricsSink::new(),
+                                None,
+                            )
+                        })
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| match handle.join() {
+                    Ok(outcome) => outcome,
+                    Err(payload) => Outcome::Panicked(panic_message(payload.as_ref())),
+                })
+                .collect()
+        });
+
+        let errors: Vec<String> = outcomes
+            .into_iter()
+            .filter(|o| !o.is_ok())
+            .map(|o| o.to_string())
+            .collect();
+        match errors.first() {
+            Some(first_error) => Err(format!(
+                "{} of {concurrency} concurrent runs failed, first error: {first_error}",
+                errors.len()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// List available scenarios.
+    pub fn list_scenarios(&self) -> Vec<String> {
+        self.list_scenarios_filtered(&ScenarioFilter::default())
+    }
+
+    /// List available scenarios selected by filter.
+    ///
+    /// * `filter` - Scenario selection filter.
+    pub fn list_scenarios_filtered(&self, filter: &ScenarioFilter) -> Vec<String> {
+        list_tagged_scenarios_recursive(self.root_group.as_ref(), "".to_string(), &[])
+            .into_iter()
+            .map(|s| (self.external_name(s.name.clone()), s))
+            .filter(|(name, s)| filter.matches_with_group_tags(name, s.scenario, &s.group_tags))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// List scenarios from group, selected by filter.
+    /// Returns `None` if group is not found.
+    ///
+    /// * `group_name` - Fully-qualified name of the group.
+    /// * `filter` - Scenario selection filter.
+    pub fn list_group_scenarios(
+        &self,
+        group_name: &str,
+        filter: &ScenarioFilter,
+    ) -> Option<Vec<String>> {
+        // Scenarios are listed from root, so tags of all ancestor groups are considered.
+        let group_name = self.internal_name(group_name);
+        self.root_group.find_group(&group_name)?;
+        let group_prefix = format!("{}{}", self.external_name(group_name), self.separator);
+        let names = self
+            .list_scenarios_filtered(filter)
+            .into_iter()
+            .filter(|name| name.starts_with(&group_prefix))
+            .collect();
+        Some(names)
+    }
+
+    /// Create JSON catalog of all groups and scenarios, preserving hierarchy.
+    pub fn catalog_json(&self) -> String {
+        catalog_recursive(self.root_group.as_ref()).to_string()
+    }
+
+    /// Create JSON Lines catalog of scenarios matching the filter, one object per scenario.
+    /// Each object contains fully-qualified `name`, `tags` including tags of its groups,
+    /// and `group_path` with names of its groups, root group excluded.
+    /// Scenarios are listed in the same order as [`TestContext::list_scenarios`].
+    ///
+    /// * `filter` - Scenario filter.
+    pub fn catalog_ndjson(&self, filter: &ScenarioFilter) -> Vec<String> {
+        list_tagged_scenarios_recursive(self.root_group.as_ref(), "".to_string(), &[])
+            .into_iter()
+            .filter(|s| {
+                filter.matches_with_group_tags(
+                    &self.external_name(s.name.clone()),
+                    s.scenario,
+                    &s.group_tags,
+                )
+            })
+            .map(|s| {
+                let tags = s.tags();
+                let group_path: Vec<&str> = match s.name.rsplit_once('.') {
+                    Some((group_name, _)) => group_name.split('.').collect(),
+                    None => Vec::new(),
+                };
+                json!({
+                    "name": self.external_name(s.name.clone()),
+                    "tags": tags,
+                    "group_path": group_path,
+                })
+                .to_string()
+            })
+            .collect()
+    }
+
+    /// Count available scenarios, regardless of filters.
+    /// Scenarios matching a filter are counted with [`TestContext::list_scenarios_filtered`].
+    pub fn count_scenarios(&self) -> usize {
+        list_scenarios_recursive(self.root_group.as_ref(), "".to_string()).len()
+    }
+
+    /// Count scenarios of each group, including scenarios of nested groups.
+    /// Groups are listed by fully-qualified name, parents before their nested groups.
+    pub fn group_scenario_counts(&self) -> Vec<(String, usize)> {
+        let mut counts = Vec::new();
+        group_counts_recursive(self.root_group.as_ref(), "", &mut counts);
+        counts
+            .into_iter()
+            .map(|(name, count)| (self.external_name(name), count))
+            .collect()
+    }
+
+    /// List groups and scenarios as indented tree lines, root group is not listed.
+    /// Group lines end with `/`, nested entries are indented by two spaces per level.
+    ///
+    /// * `counts` - Annotate group lines with number of scenarios, e.g., `group/ (3)`.
+    pub fn list_tree(&self, counts: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        tree_recursive(self.root_group.as_ref(), 0, counts, &mut lines);
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancellation::CancellationToken;
+    use crate::filter::{ScenarioFilter, ScenarioToggles};
+    use crate::metrics::MetricsSink;
+    use crate::outcome::Outcome;
+    use crate::scenario::{GroupPolicy, Scenario, ScenarioGroup, ScenarioGroupImpl};
+    use crate::scenario_env::ScenarioEnv;
+    use crate::subprocess::Isolation;
+    use crate::test_context::{
+        run_with_retries, NoopObserver, PartialResults, RunObserver, RunOptions, RunResult,
+        RunStats, TestContext, DISABLED_REASON, GLOBAL_TIMEOUT_REASON,
+    };
+    #[cfg(feature = "tracing")]
+    use crate::test_utils::SharedBuffer;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    struct ScenarioStub {
+        name: String,
+    }
+
+    impl Scenario for ScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            match input {
+                "ok" => Ok(()),
+                "error" => Err("Requested error".to_string()),
+                _ => Err("Missing input".to_string()),
+            }
+        }
+    }
+
+    fn init_group() -> Box<dyn ScenarioGroup> {
+        let scenario_inner = ScenarioStub {
+            name: "inner_scenario".to_string(),
+        };
+        let group_inner =
+            ScenarioGroupImpl::new("inner_group", vec![Box::new(scenario_inner)], vec![]);
+        let scenario_outer = ScenarioStub {
+            name: "outer_scenario".to_string(),
+        };
+        let group_outer = ScenarioGroupImpl::new(
+            "outer_group",
+            vec![Box::new(scenario_outer)],
+            vec![Box::new(group_inner)],
+        );
+
+        Box::new(group_outer)
+    }
+
+    #[test]
+    fn test_debug() {
+        let context = TestContext::new(init_group());
+        let debug = format!("{context:#?}");
+
+        assert!(debug.starts_with("TestContext {"));
+        for name in [
+            "outer_group",
+            "outer_scenario",
+            "inner_group",
+            "inner_scenario",
+        ] {
+            assert!(debug.contains(&format!("\"{name}\"")), "{debug}");
+        }
+    }
+
+    #[test]
+    fn test_unlabeled_name() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![
+                Box::new(ScenarioStub {
+                    name: "scenario".to_string(),
+                }),
+                Box::new(ScenarioStub {
+                    name: "bracketed[x]".to_string(),
+                }),
+            ],
+            vec![],
+        );
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(context.unlabeled_name("scenario[param=1.5]"), "scenario");
+        assert_eq!(context.unlabeled_name("bracketed[x]"), "bracketed[x]");
+        assert_eq!(context.unlabeled_name("scenario"), "scenario");
+        assert!(context.contains_scenario("scenario[param=a]"));
+        assert!(!context.contains_scenario("missing[param=a]"));
+    }
+
+    #[test]
+    fn test_contains_scenario_and_group() {
+        let nested_group = ScenarioGroupImpl::new(
+            "nested_group",
+            vec![Box::new(ScenarioStub {
+                name: "nested_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let inner_group =
+            ScenarioGroupImpl::new("inner_group", vec![], vec![Box::new(nested_group)]);
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(ScenarioStub {
+                name: "root_scenario".to_string(),
+            })],
+            vec![Box::new(inner_group)],
+        );
+        let context = TestContext::new(Box::new(root_group));
+
+        assert!(context.contains_scenario("root_scenario"));
+        assert!(context.contains_scenario("inner_group.nested_group.nested_scenario"));
+        assert!(!context.contains_scenario("nested_scenario"));
+        assert!(!context.contains_scenario("inner_group.nested_group"));
+        assert!(!context.contains_scenario("inner_group.nested_group.missing"));
+
+        assert!(context.contains_group("inner_group"));
+        assert!(context.contains_group("inner_group.nested_group"));
+        assert!(!context.contains_group("nested_group"));
+        assert!(!context.contains_group("root_scenario"));
+        assert!(!context.contains_group("inner_group.missing"));
+    }
+
+    #[test]
+    fn test_run_some_input_ok() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let result = context.run("inner_group.inner_scenario", "ok");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_run_some_input_err() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let result = context.run("inner_group.inner_scenario", "error");
+
+        assert!(result.is_err_and(|e| e == "Requested error"));
+    }
+
+    #[test]
+    fn test_run_not_found() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let result = context.run("some_scenario", "");
+
+        assert!(result.is_err_and(|e| e == "Scenario some_scenario not found"));
+    }
+
+    /// Check that panic message is `Requested panic` raised in this file, with its location.
+    fn is_requested_panic(message: &str) -> bool {
+        message.starts_with(&format!("panicked at {}:", file!()))
+            && message.ends_with(": Requested panic")
+    }
+
+    struct OutcomeScenarioStub {
+        name: String,
+    }
+
+    impl Scenario for OutcomeScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            match input {
+                "ok" => Ok(()),
+                "error" => Err("Requested error".to_string()),
+                "sleep" => {
+                    std::thread::sleep(Duration::from_millis(500));
+                    Ok(())
+                }
+                "panic" => panic!("Requested panic"),
+                _ => Err("Unknown value".to_string()),
+            }
+        }
+
+        fn skip_reason(&self, input: &str) -> Option<String> {
+            if input == "skip" {
+                Some("Requested skip".to_string())
+            } else {
+                None
+            }
+        }
+    }
+
+    fn init_outcome_context() -> TestContext {
+        let scenario = OutcomeScenarioStub {
+            name: "outcome_scenario".to_string(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_run_outcome_passed() {
+        let context = init_outcome_context();
+        let outcome = context.run_outcome("outcome_scenario", "ok");
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn test_validate_input_without_schema() {
+        let context = init_outcome_context();
+
+        assert!(context.validate_input("outcome_scenario", "any").is_ok());
+        assert!(context
+            .validate_input("some_scenario", "any")
+            .is_err_and(|e| e == "Scenario some_scenario not found"));
+    }
+
+    #[test]
+    fn test_run_outcome_failed() {
+        let context = init_outcome_context();
+        let outcome = context.run_outcome("outcome_scenario", "error");
+
+        assert_eq!(outcome, Outcome::Failed("Requested error".to_string()));
+    }
+
+    #[test]
+    fn test_run_outcome_not_found() {
+        let context = init_outcome_context();
+        let outcome = context.run_outcome("some_scenario", "ok");
+
+        assert_eq!(
+            outcome,
+            Outcome::Failed("Scenario some_scenario not found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_outcome_skipped() {
+        let context = init_outcome_context();
+        let outcome = context.run_outcome("outcome_scenario", "skip");
+
+        assert_eq!(outcome, Outcome::Skipped("Requested skip".to_string()));
+        assert!(context.run("outcome_scenario", "skip").is_ok());
+    }
+
+    #[test]
+    fn test_run_outcome_panicked() {
+        let context = init_outcome_context();
+        let outcome = context.run_outcome("outcome_scenario", "panic");
+
+        assert!(matches!(outcome, Outcome::Panicked(message) if is_requested_panic(&message)));
+        let result = context.run("outcome_scenario", "panic");
+        assert!(result.is_err_and(|e| e
+            .strip_prefix("Scenario panicked: ")
+            .is_some_and(is_requested_panic)));
+    }
+
+    #[test]
+    fn test_run_with_timeout_timed_out() {
+        let context = init_outcome_context();
+        let outcome =
+            context.run_with_timeout("outcome_scenario", "sleep", Some(Duration::from_millis(10)));
+
+        assert_eq!(outcome, Outcome::TimedOut);
+    }
+
+    #[test]
+    fn test_run_with_timeout_finished() {
+        let context = init_outcome_context();
+        let outcome =
+            context.run_with_timeout("outcome_scenario", "ok", Some(Duration::from_secs(5)));
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    struct TimeoutScenarioStub {
+        timeout: Option<Duration>,
+    }
+
+    impl Scenario for TimeoutScenarioStub {
+        fn name(&self) -> &str {
+            "timeout_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            std::thread::sleep(Duration::from_millis(100));
+            Ok(())
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            self.timeout
+        }
+    }
+
+    fn init_timeout_context(timeout: Option<Duration>) -> TestContext {
+        let scenario = TimeoutScenarioStub { timeout };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_run_with_timeout_scenario_timeout_trips() {
+        let context = init_timeout_context(Some(Duration::from_millis(10)));
+        let outcome = context.run_with_timeout("timeout_scenario", "", None);
+
+        assert_eq!(outcome, Outcome::TimedOut);
+    }
+
+    #[test]
+    fn test_run_with_timeout_override_extends_scenario_timeout() {
+        let context = init_timeout_context(Some(Duration::from_millis(10)));
+        let outcome =
+            context.run_with_timeout("timeout_scenario", "", Some(Duration::from_secs(5)));
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn test_run_with_timeout_override_trips() {
+        let context = init_timeout_context(Some(Duration::from_secs(5)));
+        let outcome =
+            context.run_with_timeout("timeout_scenario", "", Some(Duration::from_millis(10)));
+
+        assert_eq!(outcome, Outcome::TimedOut);
+    }
+
+    #[test]
+    fn test_run_with_timeout_unlimited() {
+        let context = init_timeout_context(None);
+        let outcome = context.run_with_timeout("timeout_scenario", "", None);
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    fn init_policy_context(
+        scenario_timeout: Option<Duration>,
+        policy_timeout: Option<Duration>,
+    ) -> TestContext {
+        let scenario = TimeoutScenarioStub {
+            timeout: scenario_timeout,
+        };
+        let inner_group = ScenarioGroupImpl::new("inner_group", vec![Box::new(scenario)], vec![]);
+        let outer_group =
+            ScenarioGroupImpl::new("outer_group", vec![], vec![Box::new(inner_group)]).with_policy(
+                GroupPolicy {
+                    timeout: policy_timeout,
+                    ..Default::default()
+                },
+            );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(outer_group)]);
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_run_with_timeout_group_policy_trips() {
+        let context = init_policy_context(None, Some(Duration::from_millis(10)));
+        let outcome =
+            context.run_with_timeout("outer_group.inner_group.timeout_scenario", "", None);
+
+        assert_eq!(outcome, Outcome::TimedOut);
+    }
+
+    #[test]
+    fn test_run_with_timeout_scenario_timeout_overrides_group_policy() {
+        let context = init_policy_context(
+            Some(Duration::from_secs(5)),
+            Some(Duration::from_millis(10)),
+        );
+        let outcome =
+            context.run_with_timeout("outer_group.inner_group.timeout_scenario", "", None);
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn test_run_with_timeout_override_overrides_group_policy() {
+        let context = init_policy_context(None, Some(Duration::from_millis(10)));
+        let outcome = context.run_with_timeout(
+            "outer_group.inner_group.timeout_scenario",
+            "",
+            Some(Duration::from_secs(5)),
+        );
+
+        assert_eq!(outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn test_policy_innermost_group_takes_precedence() {
+        let inner_group = ScenarioGroupImpl::new(
+            "",
+            vec![Box::new(ScenarioStub {
+                name: "example_scenario".to_string(),
+            })],
+            vec![],
+        )
+        .with_policy(GroupPolicy {
+            timeout: Some(Duration::from_secs(1)),
+            ..Default::default()
+        });
+        let outer_group =
+            ScenarioGroupImpl::new("outer_group", vec![], vec![Box::new(inner_group)]).with_policy(
+                GroupPolicy {
+                    timeout: Some(Duration::from_secs(10)),
+                    retries: Some(2),
+                    tags: vec!["slow".to_string()],
+                },
+            );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(outer_group)]);
+        let context = TestContext::new(Box::new(root_group));
+
+        let policy = context.policy("outer_group.example_scenario");
+
+        assert_eq!(policy.timeout, Some(Duration::from_secs(1)));
+        assert_eq!(policy.retries, Some(2));
+        assert_eq!(policy.tags, vec!["slow"]);
+        assert_eq!(context.policy("missing_scenario"), GroupPolicy::default());
+    }
+
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl RunObserver for RecordingObserver {
+        fn on_start(&self, name: &str) {
+            self.events.lock().unwrap().push(format!("start {name}"));
+        }
+
+        fn on_finish(&self, name: &str, outcome: &Outcome, _duration: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("finish {name} {}", outcome.status()));
+        }
+    }
+
+    #[test]
+    fn test_run_many_observer() {
+        let context = init_outcome_context();
+        let runs = vec![
+            ("outcome_scenario".to_string(), "panic".to_string()),
+            ("outcome_scenario".to_string(), "sleep".to_string()),
+        ];
+        let observer = RecordingObserver {
+            events: Mutex::new(Vec::new()),
+        };
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let results = context.run_many(&runs, &options, &observer);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            vec![
+                "start outcome_scenario",
+                "finish outcome_scenario panicked",
+                "start outcome_scenario",
+                "finish outcome_scenario timed_out",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_many_in_order() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let runs = vec![
+            ("outer_scenario".to_string(), "ok".to_string()),
+            (
+                "inner_group.inner_scenario".to_string(),
+                "error".to_string(),
+            ),
+            ("some_scenario".to_string(), "ok".to_string()),
+        ];
+        let results: Vec<(String, Outcome)> = context
+            .run_many(&runs, &RunOptions::default(), &NoopObserver)
+            .into_iter()
+            .map(|result| (result.name, result.outcome))
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                ("outer_scenario".to_string(), Outcome::Passed),
+                (
+                    "inner_group.inner_scenario".to_string(),
+                    Outcome::Failed("Requested error".to_string())
+                ),
+                (
+                    "some_scenario".to_string(),
+                    Outcome::Failed("Scenario some_scenario not found".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_many_max_failures() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let runs: Vec<(String, String)> = ["error", "ok", "error", "error", "ok"]
+            .iter()
+            .map(|input| ("outer_scenario".to_string(), input.to_string()))
+            .collect();
+        let options = RunOptions {
+            max_failures: Some(2),
+            ..Default::default()
+        };
+        let observer = RecordingObserver {
+            events: Mutex::new(Vec::new()),
+        };
+        let outcomes: Vec<Outcome> = context
+            .run_many(&runs, &options, &observer)
+            .into_iter()
+            .map(|result| result.outcome)
+            .collect();
+
+        let not_run = Outcome::Skipped("Maximum number of failures reached".to_string());
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Failed("Requested error".to_string()),
+                Outcome::Passed,
+                Outcome::Failed("Requested error".to_string()),
+                not_run.clone(),
+                not_run,
+            ]
+        );
+        assert_eq!(observer.events.lock().unwrap().len(), 6);
+    }
+
+    struct MetricsScenarioStub {
+        name: String,
+    }
+
+    impl Scenario for MetricsScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+            env.metrics().record(&format!("{}_latency", self.name), 1.5);
+            env.metrics().record("throughput", 100.0);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_many_metrics() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![
+                Box::new(MetricsScenarioStub {
+                    name: "first".to_string(),
+                }),
+                Box::new(MetricsScenarioStub {
+                    name: "second".to_string(),
+                }),
+            ],
+            vec![],
+        );
+        let context = TestContext::new(Box::new(root_group));
+        let runs = vec![
+            ("first".to_string(), "".to_string()),
+            ("second".to_string(), "".to_string()),
+        ];
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let results = context.run_many(&runs, &options, &NoopObserver);
+
+        assert_eq!(
+            results[0].metrics,
+            vec![
+                ("first_latency".to_string(), 1.5),
+                ("throughput".to_string(), 100.0)
+            ]
+        );
+        assert_eq!(
+            results[1].metrics,
+            vec![
+                ("second_latency".to_string(), 1.5),
+                ("throughput".to_string(), 100.0)
+            ]
+        );
+    }
+
+    struct CooperativeScenarioStub {
+        observed_cancel: Arc<AtomicBool>,
+    }
+
+    impl Scenario for CooperativeScenarioStub {
+        fn name(&self) -> &str {
+            "cooperative_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Cancellation token required".to_string())
+        }
+
+        fn run_with_cancel(&self, _input: &str, token: &CancellationToken) -> Result<(), String> {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_secs(10) {
+                if token.is_cancelled() {
+                    self.observed_cancel.store(true, Ordering::SeqCst);
+                    return Err("Cancelled".to_string());
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_with_timeout_cooperative_cancel() {
+        let observed_cancel = Arc::new(AtomicBool::new(false));
+        let scenario = CooperativeScenarioStub {
+            observed_cancel: observed_cancel.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        let start = Instant::now();
+        let outcome =
+            context.run_with_timeout("cooperative_scenario", "", Some(Duration::from_millis(20)));
+        assert_eq!(outcome, Outcome::TimedOut);
+
+        // Scenario is expected to observe cancellation promptly.
+        while !observed_cancel.load(Ordering::SeqCst) {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[derive(Debug)]
+    struct CauseError;
+
+    impl std::fmt::Display for CauseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Connection refused")
+        }
+    }
+
+    impl std::error::Error for CauseError {}
+
+    #[derive(Debug)]
+    struct ContextError(CauseError);
+
+    impl std::fmt::Display for ContextError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Failed to connect")
+        }
+    }
+
+    impl std::error::Error for ContextError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    struct ErrorChainScenarioStub;
+
+    impl Scenario for ErrorChainScenarioStub {
+        fn name(&self) -> &str {
+            "error_chain_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Boxed error required".to_string())
+        }
+
+        fn run_err(&self, _input: &str) -> Result<(), Box<dyn std::error::Error>> {
+            Err(Box::new(ContextError(CauseError)))
+        }
+    }
+
+    #[test]
+    fn test_run_error_chain() {
+        let root_group =
+            ScenarioGroupImpl::new("root", vec![Box::new(ErrorChainScenarioStub)], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        let result = context.run("error_chain_scenario", "");
+        assert!(result.is_err_and(|e| e == "Failed to connect: Connection refused"));
+    }
+
+    /// Scenario stub reporting three sub-checks, second one fails.
+    struct SubResultsScenarioStub;
+
+    impl Scenario for SubResultsScenarioStub {
+        fn name(&self) -> &str {
+            "sub_results_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Environment required".to_string())
+        }
+
+        fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+            let checks = env.sub_results();
+            checks.check("first", Ok(()));
+            checks.check("second", Err("Expected 1, got 2".to_string()));
+            checks.check("third", Ok(()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_sub_results() {
+        let root_group =
+            ScenarioGroupImpl::new("root", vec![Box::new(SubResultsScenarioStub)], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        let outcome = context.run_outcome("sub_results_scenario", "");
+        assert_eq!(
+            outcome,
+            Outcome::Failed(
+                "1 of 3 sub-checks failed: [passed] first, [failed] second: Expected 1, got 2, [passed] third"
+                    .to_string()
+            )
+        );
+    }
+
+    /// Scenario stub with input schema, counting runs.
+    struct SchemaScenarioStub {
+        schema: Option<&'static str>,
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl Scenario for SchemaScenarioStub {
+        fn name(&self) -> &str {
+            "schema_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn input_schema(&self) -> Option<&str> {
+            self.schema
+        }
+    }
+
+    fn init_schema_context(schema: Option<&'static str>) -> (TestContext, Arc<AtomicUsize>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let scenario = SchemaScenarioStub {
+            schema,
+            runs: runs.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        (TestContext::new(Box::new(root_group)), runs)
+    }
+
+    const SCHEMA: &str = r#"{"type": "object", "required": ["name"]}"#;
+
+    #[test]
+    fn test_run_input_schema_valid() {
+        let (context, runs) = init_schema_context(Some(SCHEMA));
+        let result = context.run("schema_scenario", r#"{"name": "example"}"#);
+        assert!(result.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_run_input_schema_missing_field() {
+        let (context, runs) = init_schema_context(Some(SCHEMA));
+        let result = context.run("schema_scenario", r#"{"other": 1}"#);
+        assert!(result.is_err_and(|e| e == "Invalid input: missing required field 'name'"));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_run_no_input_schema() {
+        let (context, runs) = init_schema_context(None);
+        let result = context.run("schema_scenario", "not json");
+        assert!(result.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    /// Scenario stub recording deadline passed in environment.
+    struct DeadlineScenarioStub {
+        deadline: Arc<Mutex<Option<Instant>>>,
+    }
+
+    impl Scenario for DeadlineScenarioStub {
+        fn name(&self) -> &str {
+            "deadline_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Environment required".to_string())
+        }
+
+        fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+            *self.deadline.lock().unwrap() = env.deadline();
+            Ok(())
+        }
+    }
+
+    fn init_deadline_context() -> (TestContext, Arc<Mutex<Option<Instant>>>) {
+        let deadline = Arc::new(Mutex::new(None));
+        let scenario = DeadlineScenarioStub {
+            deadline: deadline.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        (TestContext::new(Box::new(root_group)), deadline)
+    }
+
+    #[test]
+    fn test_run_with_timeout_deadline() {
+        let (context, deadline) = init_deadline_context();
+
+        let before = Instant::now();
+        let outcome =
+            context.run_with_timeout("deadline_scenario", "", Some(Duration::from_secs(5)));
+        let after = Instant::now();
+
+        assert_eq!(outcome, Outcome::Passed);
+        let deadline = deadline.lock().unwrap().unwrap();
+        assert!(deadline >= before + Duration::from_secs(5));
+        assert!(deadline <= after + Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_run_outcome_no_deadline() {
+        let (context, deadline) = init_deadline_context();
+        *deadline.lock().unwrap() = Some(Instant::now());
+
+        let outcome = context.run_outcome("deadline_scenario", "");
+
+        assert_eq!(outcome, Outcome::Passed);
+        assert_eq!(*deadline.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn test_run_with_deadlines_soft_cancel() {
+        let observed_cancel = Arc::new(AtomicBool::new(false));
+        let scenario = CooperativeScenarioStub {
+            observed_cancel: observed_cancel.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        let outcome = context.run_with_deadlines(
+            "cooperative_scenario",
+            "",
+            Some(Duration::from_millis(20)),
+            Some(Duration::from_secs(5)),
+            &MetricsSink::new(),
+        );
+
+        // Scenario returns on its own after cancellation, before hard timeout.
+        assert_eq!(outcome, Outcome::Failed("Cancelled".to_string()));
+        assert!(observed_cancel.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_run_with_deadlines_hard_abandon() {
+        let context = init_outcome_context();
+
+        let start = Instant::now();
+        let outcome = context.run_with_deadlines(
+            "outcome_scenario",
+            "sleep",
+            Some(Duration::from_millis(20)),
+            Some(Duration::from_millis(100)),
+            &MetricsSink::new(),
+        );
+
+        assert_eq!(outcome, Outcome::TimedOut);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(500));
+    }
+
+    struct TempDirScenarioStub {
+        temp_dirs: Arc<Mutex<Vec<PathBuf>>>,
+    }
+
+    impl Scenario for TempDirScenarioStub {
+        fn name(&self) -> &str {
+            "temp_dir_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Scenario environment required".to_string())
+        }
+
+        fn run_with_env(&self, env: &ScenarioEnv) -> Result<(), String> {
+            let temp_dir = env.temp_dir()?;
+            let file_path = temp_dir.join("scratch.txt");
+            std::fs::write(&file_path, env.input()).map_err(|e| e.to_string())?;
+            if !file_path.is_file() {
+                return Err("Scratch file not created".to_string());
+            }
+            self.temp_dirs.lock().unwrap().push(temp_dir.to_path_buf());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_temp_dir_removed_after_run() {
+        let temp_dirs = Arc::new(Mutex::new(Vec::new()));
+        let scenario = TempDirScenarioStub {
+            temp_dirs: temp_dirs.clone(),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert!(context.run("temp_dir_scenario", "content").is_ok());
+        assert!(context
+            .run_concurrent("temp_dir_scenario", "content", 4)
+            .is_ok());
+
+        // Directories are expected to be distinct and removed after run.
+        let temp_dirs = temp_dirs.lock().unwrap();
+        assert_eq!(temp_dirs.len(), 5);
+        for (index, temp_dir) in temp_dirs.iter().enumerate() {
+            assert!(!temp_dir.exists());
+            assert!(!temp_dirs[index + 1..].contains(temp_dir));
+        }
+    }
+
+    struct CountingScenarioStub {
+        counter: Arc<AtomicUsize>,
+        fail_first: bool,
+    }
+
+    impl Scenario for CountingScenarioStub {
+        fn name(&self) -> &str {
+            "counting_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            let previous = self.counter.fetch_add(1, Ordering::SeqCst);
+            if self.fail_first && previous == 0 {
+                Err("Requested error".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn init_counting_context(fail_first: bool) -> (TestContext, Arc<AtomicUsize>) {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let scenario = CountingScenarioStub {
+            counter: counter.clone(),
+            fail_first,
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        (TestContext::new(Box::new(root_group)), counter)
+    }
+
+    #[test]
+    fn test_run_many_group_policy_retries() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let scenario = CountingScenarioStub {
+            counter: counter.clone(),
+            fail_first: true,
+        };
+        let group = ScenarioGroupImpl::new("group", vec![Box::new(scenario)], vec![]).with_policy(
+            GroupPolicy {
+                retries: Some(1),
+                ..Default::default()
+            },
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let context = TestContext::new(Box::new(root_group));
+        let runs = vec![("group.counting_scenario".to_string(), String::new())];
+
+        let results = context.run_many(&runs, &RunOptions::default(), &NoopObserver);
+        assert_eq!(results[0].outcome, Outcome::Passed);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        // Run options override group policy.
+        counter.store(0, Ordering::SeqCst);
+        let options = RunOptions {
+            retries: Some(0),
+            ..Default::default()
+        };
+        let results = context.run_many(&runs, &options, &NoopObserver);
+        assert!(matches!(results[0].outcome, Outcome::Failed(_)));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_run_concurrent_ok() {
+        let (context, counter) = init_counting_context(false);
+        let result = context.run_concurrent("counting_scenario", "", 8);
+
+        assert!(result.is_ok());
+        assert_eq!(counter.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn test_run_concurrent_single_failure() {
+        let (context, counter) = init_counting_context(true);
+        let result = context.run_concurrent("counting_scenario", "", 4);
+
+        assert!(result
+            .is_err_and(|e| e == "1 of 4 concurrent runs failed, first error: Requested error"));
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_run_concurrent_not_found() {
+        let (context, counter) = init_counting_context(false);
+        let result = context.run_concurrent("some_scenario", "", 4);
+
+        assert!(result.is_err_and(|e| e == "Scenario some_scenario not found"));
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_list_scenarios_ok() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let result = context.list_scenarios();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], "inner_group.inner_scenario");
+        assert_eq!(result[1], "outer_scenario");
+    }
+
+    #[test]
+    fn test_count_scenarios_nested() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+
+        assert_eq!(context.count_scenarios(), 2);
+    }
+
+    #[test]
+    fn test_count_scenarios_empty() {
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(context.count_scenarios(), 0);
+    }
+
+    #[test]
+    fn test_list_scenarios_filtered() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+        let filter = ScenarioFilter {
+            tags: vec![],
+            skip: vec!["inner_group".to_string()],
+            shard: None,
+        };
+        let result = context.list_scenarios_filtered(&filter);
+
+        assert_eq!(result, vec!["outer_scenario"]);
+    }
+
+    #[test]
+    fn test_list_group_scenarios() {
+        let root_group = init_group();
+        let context = TestContext::new(root_group);
+
+        let result = context.list_group_scenarios("inner_group", &ScenarioFilter::default());
+        assert_eq!(result, Some(vec!["inner_group.inner_scenario".to_string()]));
+
+        let result = context.list_group_scenarios("invalid_group", &ScenarioFilter::default());
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_list_scenarios_group_tags() {
+        let nested_group = ScenarioGroupImpl::new(
+            "nested_group",
+            vec![Box::new(ScenarioStub {
+                name: "nested_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let integration_group = ScenarioGroupImpl::new(
+            "integration_group",
+            vec![Box::new(ScenarioStub {
+                name: "untagged_scenario".to_string(),
+            })],
+            vec![Box::new(nested_group)],
+        )
+        .with_tags(vec!["integration".to_string()]);
+        let other_group = ScenarioGroupImpl::new(
+            "other_group",
+            vec![Box::new(ScenarioStub {
+                name: "other_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![],
+            vec![Box::new(integration_group), Box::new(other_group)],
+        );
+        let context = TestContext::new(Box::new(root_group));
+        let filter = ScenarioFilter {
+            tags: vec!["integration".to_string()],
+            skip: vec![],
+            shard: None,
+        };
+
+        assert_eq!(
+            context.list_scenarios_filtered(&filter),
+            vec![
+                "integration_group.nested_group.nested_scenario",
+                "integration_group.untagged_scenario"
+            ]
+        );
+        assert_eq!(
+            context.list_group_scenarios("integration_group.nested_group", &filter),
+            Some(vec![
+                "integration_group.nested_group.nested_scenario".to_string()
+            ])
+        );
+        assert_eq!(
+            context.list_group_scenarios("other_group", &filter),
+            Some(vec![])
+        );
+    }
+
+    struct DescribedScenarioStub {
+        tags: Vec<String>,
+    }
+
+    impl Scenario for DescribedScenarioStub {
+        fn name(&self) -> &str {
+            "described \"scenario\""
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn tags(&self) -> &[String] {
+            &self.tags
+        }
+
+        fn description(&self) -> &str {
+            "Scenario with\nescaped description"
+        }
+    }
+
+    #[test]
+    fn test_catalog_json() {
+        let described_scenario = DescribedScenarioStub {
+            tags: vec!["fast".to_string(), "smoke".to_string()],
+        };
+        let inner_group =
+            ScenarioGroupImpl::new("inner_group", vec![Box::new(described_scenario)], vec![]);
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(ScenarioStub {
+                name: "outer_scenario".to_string(),
+            })],
+            vec![Box::new(inner_group)],
+        );
+        let context = TestContext::new(Box::new(root_group));
+
+        let catalog: serde_json::Value = serde_json::from_str(&context.catalog_json()).unwrap();
+        let expected = serde_json::json!({
+            "name": "root",
+            "groups": [{
+                "name": "inner_group",
+                "groups": [],
+                "scenarios": [{
+                    "name": "described \"scenario\"",
+                    "tags": ["fast", "smoke"],
+                    "description": "Scenario with\nescaped description",
+                }],
+            }],
+            "scenarios": [{
+                "name": "outer_scenario",
+                "tags": [],
+                "description": "",
+            }],
+        });
+        assert_eq!(catalog, expected);
+    }
+
+    #[test]
+    fn test_catalog_ndjson() {
+        let described_scenario = DescribedScenarioStub {
+            tags: vec!["fast".to_string(), "smoke".to_string()],
+        };
+        let inner_group =
+            ScenarioGroupImpl::new("inner_group", vec![Box::new(described_scenario)], vec![])
+                .with_tags(vec!["smoke".to_string(), "nightly".to_string()]);
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(ScenarioStub {
+                name: "outer_scenario".to_string(),
+            })],
+            vec![Box::new(inner_group)],
+        );
+        let context = TestContext::new(Box::new(root_group));
+
+        let lines = context.catalog_ndjson(&ScenarioFilter::default());
+        assert_eq!(lines, context.catalog_ndjson(&ScenarioFilter::default()));
+        let entries: Vec<Value> = lines
+            .iter()
+            .map(|line| {
+                assert!(!line.contains('\n'));
+                serde_json::from_str(line).unwrap()
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                json!({
+                    "name": "inner_group.described \"scenario\"",
+                    "tags": ["fast", "smoke", "nightly"],
+                    "group_path": ["inner_group"],
+                }),
+                json!({
+                    "name": "outer_scenario",
+                    "tags": [],
+                    "group_path": [],
+                }),
+            ]
+        );
+
+        let filter = ScenarioFilter {
+            tags: vec!["nightly".to_string()],
+            skip: vec![],
+            shard: None,
+        };
+        assert_eq!(context.catalog_ndjson(&filter), lines[..1]);
+    }
+
+    #[test]
+    fn test_tags_inherited_from_groups() {
+        let described_scenario = DescribedScenarioStub {
+            tags: vec!["fast".to_string(), "smoke".to_string()],
+        };
+        let inner_group =
+            ScenarioGroupImpl::new("inner_group", vec![Box::new(described_scenario)], vec![])
+                .with_tags(vec!["smoke".to_string(), "nightly".to_string()]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(inner_group)])
+            .with_tags(vec!["all".to_string()]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(
+            context.tags("inner_group.described \"scenario\""),
+            vec!["fast", "smoke", "all", "nightly"]
+        );
+        assert!(context.tags("inner_group.unknown").is_empty());
+    }
+
+    #[test]
+    fn test_list_scenarios_empty() {
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+        let context = TestContext::new(Box::new(root_group));
+        let result = context.list_scenarios();
+
+        assert_eq!(result.len(), 0);
+    }
+
+    struct OutputScenarioStub;
+
+    impl Scenario for OutputScenarioStub {
+        fn name(&self) -> &str {
+            "output_scenario"
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            self.run_output(input).map(|_| ())
+        }
+
+        fn run_output(&self, input: &str) -> Result<Value, String> {
+            match input {
+                "error" => Err("Requested error".to_string()),
+                "panic" => panic!("Requested panic"),
+                _ => Ok(json!({"id": 42, "input": input})),
+            }
+        }
+    }
+
+    fn init_output_context() -> TestContext {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![
+                Box::new(OutputScenarioStub),
+                Box::new(ScenarioStub {
+                    name: "plain_scenario".to_string(),
+                }),
+            ],
+            vec![],
+        );
+        TestContext::new(Box::new(root_group))
+    }
+
+    #[test]
+    fn test_run_output_object() {
+        let context = init_output_context();
+        let result = context.run_output("output_scenario", "value");
+
+        assert_eq!(result, Ok(json!({"id": 42, "input": "value"})));
     }
 
     #[test]
-    fn test_list_scenarios_empty() {
-        let root_group = ScenarioGroupImpl::new("root", vec![], vec![]);
+    fn test_run_output_default_null() {
+        let context = init_output_context();
+
+        assert_eq!(context.run_output("plain_scenario", "ok"), Ok(Value::Null));
+        assert!(context
+            .run_output("plain_scenario", "error")
+            .is_err_and(|e| e == "Requested error"));
+    }
+
+    #[test]
+    fn test_run_output_panicked() {
+        let context = init_output_context();
+        let result = context.run_output("output_scenario", "panic");
+
+        assert!(result.is_err_and(|e| e
+            .strip_prefix("Scenario panicked: ")
+            .is_some_and(is_requested_panic)));
+    }
+
+    #[test]
+    fn test_run_output_not_found() {
+        let context = init_output_context();
+        let result = context.run_output("missing_scenario", "value");
+
+        assert!(result.is_err_and(|e| e == "Scenario missing_scenario not found"));
+    }
+
+    #[test]
+    fn test_empty_group_name() {
+        let group_unnamed = ScenarioGroupImpl::new(
+            "",
+            vec![Box::new(ScenarioStub {
+                name: "unnamed_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let group_outer =
+            ScenarioGroupImpl::new("outer_group", vec![], vec![Box::new(group_unnamed)]);
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group_outer)]);
         let context = TestContext::new(Box::new(root_group));
-        let result = context.list_scenarios();
 
-        assert_eq!(result.len(), 0);
+        let names = context.list_scenarios();
+        assert_eq!(names, vec!["outer_group.unnamed_scenario"]);
+        for name in names {
+            assert!(context.find_scenario(&name).is_some());
+        }
+    }
+
+    #[test]
+    fn test_run_scenario_by_reference() {
+        // Scenario is not part of the context tree.
+        let context = TestContext::new(Box::new(ScenarioGroupImpl::new("root", vec![], vec![])));
+        let scenario = OutcomeScenarioStub {
+            name: "detached_scenario".to_string(),
+        };
+        let observer = RecordingObserver {
+            events: Mutex::new(Vec::new()),
+        };
+
+        let result = context.run_scenario(&scenario, Some("error".to_string()), &observer);
+
+        assert_eq!(result.name, "detached_scenario");
+        assert_eq!(
+            result.outcome,
+            Outcome::Failed("Requested error".to_string())
+        );
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            vec!["start detached_scenario", "finish detached_scenario failed"]
+        );
+
+        let result = context.run_scenario(&scenario, Some("panic".to_string()), &NoopObserver);
+        assert!(
+            matches!(result.outcome, Outcome::Panicked(message) if is_requested_panic(&message))
+        );
+    }
+
+    #[test]
+    fn test_run_scenario_by_reference_timeout() {
+        let observed_cancel = Arc::new(AtomicBool::new(false));
+        let scenario = TimeoutCooperativeScenarioStub {
+            observed_cancel: observed_cancel.clone(),
+        };
+        let context = TestContext::new(Box::new(ScenarioGroupImpl::new("root", vec![], vec![])));
+
+        let result = context.run_scenario(&scenario, None, &NoopObserver);
+
+        assert_eq!(result.outcome, Outcome::TimedOut);
+        assert!(result.duration >= Duration::from_millis(20));
+        assert!(observed_cancel.load(Ordering::SeqCst));
+    }
+
+    /// Cooperative scenario stub declaring its own timeout.
+    struct TimeoutCooperativeScenarioStub {
+        observed_cancel: Arc<AtomicBool>,
+    }
+
+    impl Scenario for TimeoutCooperativeScenarioStub {
+        fn name(&self) -> &str {
+            "timeout_cooperative_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Cancellation token required".to_string())
+        }
+
+        fn run_with_cancel(&self, _input: &str, token: &CancellationToken) -> Result<(), String> {
+            while !token.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            self.observed_cancel.store(true, Ordering::SeqCst);
+            Err("Cancelled".to_string())
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            Some(Duration::from_millis(20))
+        }
+    }
+
+    struct AliasScenarioStub {
+        name: String,
+        aliases: Vec<String>,
+    }
+
+    impl Scenario for AliasScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn aliases(&self) -> &[String] {
+            &self.aliases
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn alias_stub(name: &str, aliases: &[&str]) -> Box<dyn Scenario> {
+        Box::new(AliasScenarioStub {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_scenario_aliases() {
+        let group = ScenarioGroupImpl::new(
+            "group",
+            vec![alias_stub("new_name", &["old_name", "older_name"])],
+            vec![],
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+        let context = TestContext::new(Box::new(root_group));
+
+        for name in ["group.new_name", "group.old_name", "group.older_name"] {
+            assert!(context
+                .find_scenario(name)
+                .is_some_and(|s| s.name() == "new_name"));
+            assert!(context.run(name, "").is_ok());
+        }
+        assert!(!context.contains_scenario("old_name"));
+        assert_eq!(context.list_scenarios(), vec!["group.new_name"]);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let inner_group =
+            ScenarioGroupImpl::new("inner", vec![alias_stub("new_name", &["old_name"])], vec![]);
+        let transparent_group =
+            ScenarioGroupImpl::new("", vec![alias_stub("hidden", &["hidden_alias"])], vec![]);
+        let outer_group = ScenarioGroupImpl::new(
+            "outer",
+            vec![],
+            vec![Box::new(inner_group), Box::new(transparent_group)],
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(outer_group)]);
+        let context = TestContext::new(Box::new(root_group));
+
+        assert_eq!(
+            context.resolve("outer.inner.old_name"),
+            Some("outer.inner.new_name".to_string())
+        );
+        assert_eq!(
+            context.resolve("outer.inner.new_name"),
+            Some("outer.inner.new_name".to_string())
+        );
+        assert_eq!(
+            context.resolve("outer.hidden_alias"),
+            Some("outer.hidden".to_string())
+        );
+        assert_eq!(context.resolve("outer.inner.missing"), None);
+        assert_eq!(context.resolve("old_name"), None);
+    }
+
+    #[test]
+    fn test_scenario_alias_conflict() {
+        let group = ScenarioGroupImpl::new(
+            "group",
+            vec![alias_stub("first", &[]), alias_stub("second", &["first"])],
+            vec![],
+        );
+        let root_group = ScenarioGroupImpl::new("root", vec![], vec![Box::new(group)]);
+
+        let result = TestContext::try_new(Box::new(root_group));
+        assert!(result.is_err_and(
+            |e| e == "Alias first of scenario group.second conflicts with scenario group.first"
+        ));
+    }
+
+    #[test]
+    fn test_scenario_alias_conflict_between_aliases() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![
+                alias_stub("first", &["old"]),
+                alias_stub("second", &["old"]),
+            ],
+            vec![],
+        );
+
+        let result = TestContext::try_new(Box::new(root_group));
+        assert!(result
+            .is_err_and(|e| e == "Alias old of scenario first conflicts with scenario second"));
+    }
+
+    struct SleepScenarioStub {
+        name: String,
+    }
+
+    impl Scenario for SleepScenarioStub {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn run(&self, input: &str) -> Result<(), String> {
+            let millis = input.parse::<u64>().map_err(|e| e.to_string())?;
+            std::thread::sleep(Duration::from_millis(millis));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_many_parallel_order() {
+        let scenarios: Vec<Box<dyn Scenario>> = (0..32)
+            .map(|index| {
+                Box::new(SleepScenarioStub {
+                    name: format!("scenario_{index}"),
+                }) as Box<dyn Scenario>
+            })
+            .collect();
+        let root_group = ScenarioGroupImpl::new("root", scenarios, vec![]);
+        let context = TestContext::new(Box::new(root_group));
+        let options = RunOptions {
+            parallelism: Some(16),
+            ..Default::default()
+        };
+
+        // Pseudo-random sleep durations, different in each iteration.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..5 {
+            let runs: Vec<(String, String)> = (0..32)
+                .map(|index| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (format!("scenario_{index}"), (state % 20).to_string())
+                })
+                .collect();
+
+            let results = context.run_many(&runs, &options, &NoopObserver);
+
+            let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+            let expected: Vec<&str> = runs.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, expected);
+            assert!(results.iter().all(|r| r.outcome == Outcome::Passed));
+        }
+    }
+
+    #[test]
+    fn test_run_many_disabled() {
+        let context = TestContext::new(init_group());
+        let runs = [
+            ("outer_scenario".to_string(), "ok".to_string()),
+            ("inner_group.inner_scenario".to_string(), "ok".to_string()),
+        ];
+        let options = RunOptions {
+            toggles: ScenarioToggles {
+                enable: vec![],
+                disable: vec!["inner_group".to_string()],
+            },
+            ..Default::default()
+        };
+
+        let results = context.run_many(&runs, &options, &NoopObserver);
+        assert_eq!(results[0].outcome, Outcome::Passed);
+        assert_eq!(
+            results[1].outcome,
+            Outcome::Skipped(DISABLED_REASON.to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_many_parallel_max_failures() {
+        let root_group = ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(SleepScenarioStub {
+                name: "sleep_scenario".to_string(),
+            })],
+            vec![],
+        );
+        let context = TestContext::new(Box::new(root_group));
+        let runs: Vec<(String, String)> = ["invalid", "invalid", "0", "0", "0", "0"]
+            .iter()
+            .map(|input| ("sleep_scenario".to_string(), input.to_string()))
+            .collect();
+        let options = RunOptions {
+            max_failures: Some(1),
+            parallelism: Some(2),
+            ..Default::default()
+        };
+
+        let results = context.run_many(&runs, &options, &NoopObserver);
+        assert_eq!(results.len(), runs.len());
+        let failed = results.iter().filter(|r| !r.outcome.is_ok()).count();
+        assert!((1..=2).contains(&failed));
+    }
+
+    #[cfg(feature = "tracing")]
+    struct DeprecatedScenarioStub {
+        hint: String,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl Scenario for DeprecatedScenarioStub {
+        fn name(&self) -> &str {
+            "deprecated_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            Err("Real failure".to_string())
+        }
+
+        fn deprecated(&self) -> Option<&str> {
+            Some(&self.hint)
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_run_deprecated_warns() {
+        let scenario = Box::new(DeprecatedScenarioStub {
+            hint: "use new_scenario".to_string(),
+        });
+        let group = Box::new(ScenarioGroupImpl::new("root", vec![scenario], vec![]));
+        let context = TestContext::new(group);
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::FmtSubscriber::builder()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        let outcome = tracing::subscriber::with_default(subscriber, || {
+            context.run_outcome("deprecated_scenario", "")
+        });
+
+        let logs = buffer.contents();
+        assert!(logs.contains("WARN"), "{logs}");
+        assert!(
+            logs.contains("Scenario deprecated_scenario is deprecated: use new_scenario"),
+            "{logs}"
+        );
+        assert!(matches!(outcome, Outcome::Failed(ref e) if e == "Real failure"));
+
+        let runs = vec![("deprecated_scenario".to_string(), String::new())];
+        let results = context.run_many(&runs, &RunOptions::default(), &NoopObserver);
+        assert!(results[0].deprecated);
+        assert!(matches!(results[0].outcome, Outcome::Failed(_)));
+    }
+
+    struct SlowScenarioStub;
+
+    impl Scenario for SlowScenarioStub {
+        fn name(&self) -> &str {
+            "slow_scenario"
+        }
+
+        fn run(&self, _input: &str) -> Result<(), String> {
+            std::thread::sleep(Duration::from_millis(50));
+            Ok(())
+        }
+
+        fn slow_after(&self) -> Option<Duration> {
+            Some(Duration::from_millis(10))
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_run_slow_warns() {
+        let group = Box::new(ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(SlowScenarioStub)],
+            vec![],
+        ));
+        let context = TestContext::new(group);
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::FmtSubscriber::builder()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        let runs = vec![("slow_scenario".to_string(), String::new())];
+        let results = tracing::subscriber::with_default(subscriber, || {
+            context.run_many(&runs, &RunOptions::default(), &NoopObserver)
+        });
+
+        let logs = buffer.contents();
+        assert!(logs.contains("WARN"), "{logs}");
+        assert!(
+            logs.contains("Scenario slow_scenario is slow, took ")
+                && logs.contains(" ms, expected at most 10 ms"),
+            "{logs}"
+        );
+        assert_eq!(results[0].outcome, Outcome::Passed);
+        assert!(results[0].slow);
+        // Warning is based on the reported duration and logged once.
+        let took = format!("took {} ms", results[0].duration.as_millis());
+        assert_eq!(logs.matches(&took).count(), 1, "{logs}");
+        assert_eq!(logs.matches(" is slow").count(), 1, "{logs}");
+    }
+
+    #[test]
+    fn test_run_slow_flag() {
+        let group = Box::new(ScenarioGroupImpl::new(
+            "root",
+            vec![Box::new(SlowScenarioStub)],
+            vec![],
+        ));
+        let context = TestContext::new(group);
+        let runs = vec![("slow_scenario".to_string(), String::new())];
+
+        let results = context.run_many(&runs, &RunOptions::default(), &NoopObserver);
+        assert_eq!(results[0].outcome, Outcome::Passed);
+        assert!(results[0].slow);
+
+        let result = context.run_scenario(&SlowScenarioStub, None, &NoopObserver);
+        assert!(result.slow);
+        assert!(!context.check_slow("slow_scenario", Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_hooks_invoked_for_each_run() {
+        let mut context = init_outcome_context();
+        let before = Arc::new(AtomicUsize::new(0));
+        let after = Arc::new(AtomicUsize::new(0));
+        let before_counter = Arc::clone(&before);
+        context.set_before_each(move |name| {
+            assert_eq!(name, "outcome_scenario");
+            before_counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let after_counter = Arc::clone(&after);
+        context.set_after_each(move |_| {
+            after_counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let runs: Vec<(String, String)> = ["ok", "error", "panic", "ok"]
+            .iter()
+            .map(|input| ("outcome_scenario".to_string(), input.to_string()))
+            .collect();
+        let options = RunOptions {
+            parallelism: Some(2),
+            ..Default::default()
+        };
+        let results = context.run_many(&runs, &options, &NoopObserver);
+        context.run_outcome("outcome_scenario", "ok");
+
+        assert_eq!(before.load(Ordering::SeqCst), results.len() + 1);
+        assert_eq!(after.load(Ordering::SeqCst), results.len() + 1);
+    }
+
+    fn init_tree_group() -> Box<dyn ScenarioGroup> {
+        let stub = |name: &str| -> Box<dyn Scenario> {
+            Box::new(ScenarioStub {
+                name: name.to_string(),
+            })
+        };
+        let inner_group =
+            ScenarioGroupImpl::new("inner_group", vec![stub("inner_scenario")], vec![]);
+        let outer_group = ScenarioGroupImpl::new(
+            "outer_group",
+            vec![stub("first_scenario"), stub("second_scenario")],
+            vec![Box::new(inner_group)],
+        );
+        let empty_group = ScenarioGroupImpl::new("empty_group", vec![], vec![]);
+        Box::new(ScenarioGroupImpl::new(
+            "root",
+            vec![stub("root_scenario")],
+            vec![Box::new(outer_group), Box::new(empty_group)],
+        ))
+    }
+
+    #[test]
+    fn test_group_scenario_counts() {
+        let context = TestContext::new(init_tree_group());
+        assert_eq!(
+            context.group_scenario_counts(),
+            vec![
+                ("outer_group".to_string(), 3),
+                ("outer_group.inner_group".to_string(), 1),
+                ("empty_group".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_tree() {
+        let context = TestContext::new(init_tree_group());
+        assert_eq!(
+            context.list_tree(false),
+            vec![
+                "outer_group/",
+                "  inner_group/",
+                "    inner_scenario",
+                "  first_scenario",
+                "  second_scenario",
+                "empty_group/",
+                "root_scenario",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_tree_counts() {
+        let context = TestContext::new(init_tree_group());
+        assert_eq!(
+            context.list_tree(true),
+            vec![
+                "outer_group/ (3)",
+                "  inner_group/ (1)",
+                "    inner_scenario",
+                "  first_scenario",
+                "  second_scenario",
+                "empty_group/ (0)",
+                "root_scenario",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_many_fallible_aborted() {
+        let context = init_outcome_context();
+        for parallelism in [None, Some(2)] {
+            let runs = vec![
+                Ok(("outcome_scenario".to_string(), "ok".to_string())),
+                Ok(("outcome_scenario".to_string(), "error".to_string())),
+                Err("Input file disappeared".to_string()),
+                Ok(("outcome_scenario".to_string(), "ok".to_string())),
+            ];
+            let options = RunOptions {
+                parallelism,
+                ..Default::default()
+            };
+            let PartialResults { results, error } =
+                context.run_many_fallible(runs, &options, &NoopObserver);
+
+            assert_eq!(error, Some("Input file disappeared".to_string()));
+            let outcomes: Vec<Outcome> = results.into_iter().map(|r| r.outcome).collect();
+            assert_eq!(
+                outcomes,
+                vec![
+                    Outcome::Passed,
+                    Outcome::Failed("Requested error".to_string())
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_many_fallible_completed() {
+        let context = init_outcome_context();
+        let runs = vec![Ok(("outcome_scenario".to_string(), "ok".to_string()))];
+        let partial = context.run_many_fallible(runs, &RunOptions::default(), &NoopObserver);
+
+        assert_eq!(partial.results.len(), 1);
+        assert!(partial.error.is_none());
+    }
+
+    #[test]
+    fn test_run_stats_from_outcomes() {
+        let results = vec![
+            RunResult::new("first", Outcome::Passed, Duration::from_millis(10)),
+            RunResult::new("second", Outcome::Passed, Duration::from_millis(20)),
+            RunResult::new(
+                "third",
+                Outcome::Failed("error".to_string()),
+                Duration::from_millis(30),
+            ),
+            RunResult::new(
+                "fourth",
+                Outcome::Panicked("panic".to_string()),
+                Duration::ZERO,
+            ),
+            RunResult::new(
+                "fifth",
+                Outcome::Skipped("reason".to_string()),
+                Duration::ZERO,
+            ),
+            RunResult::new("sixth", Outcome::TimedOut, Duration::from_millis(40)),
+        ];
+        let stats = RunStats::from_outcomes(&results);
+
+        assert_eq!(stats.total, 6);
+        assert_eq!(stats.passed, 2);
+        assert_eq!(stats.failed, 2);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.timed_out, 1);
+        assert_eq!(stats.total_duration, Duration::from_millis(100));
+        assert_eq!(
+            stats.to_json(),
+            json!({
+                "total": 6,
+                "passed": 2,
+                "failed": 2,
+                "skipped": 1,
+                "timed_out": 1,
+                "quarantined": 0,
+                "total_duration_us": 100_000,
+            })
+        );
+
+        let quarantine = [
+            "third".to_string(),
+            "sixth".to_string(),
+            "first".to_string(),
+        ];
+        let stats = RunStats::from_outcomes_with_quarantine(&results, &quarantine);
+        assert_eq!(stats.quarantined, 2);
+        assert_eq!(stats.failed, 2);
+    }
+
+    #[test]
+    fn test_run_stats_empty() {
+        assert_eq!(RunStats::from_outcomes(&[]), RunStats::default());
+    }
+
+    #[test]
+    fn test_run_many_fallible_stats() {
+        let context = init_outcome_context();
+        let runs = vec![
+            Ok(("outcome_scenario".to_string(), "ok".to_string())),
+            Ok(("outcome_scenario".to_string(), "error".to_string())),
+            Ok(("outcome_scenario".to_string(), "skip".to_string())),
+        ];
+        let stats = context
+            .run_many_fallible(runs, &RunOptions::default(), &NoopObserver)
+            .stats();
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.passed, 1);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.timed_out, 0);
+    }
+
+    #[test]
+    fn test_run_many_deadline_exceeded() {
+        let context = init_outcome_context();
+        let runs = vec![("outcome_scenario".to_string(), "ok".to_string())];
+        let options = RunOptions {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        let results = context.run_many(&runs, &options, &NoopObserver);
+
+        assert_eq!(
+            results[0].outcome,
+            Outcome::Skipped(GLOBAL_TIMEOUT_REASON.to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_many_isolated_crash() {
+        let context = init_outcome_context();
+        // Shell script standing in for the runner, crashes on "crash" input.
+        let script = r#"[ "$TEST_SCENARIO_INPUT" = crash ] && kill -SEGV $$;
+            echo '{"outcome":{"status":"passed","message":null}}' > "$4""#;
+        let options = RunOptions {
+            isolation: Some(Isolation {
+                executable: PathBuf::from("sh"),
+                arguments: vec!["-c".to_string(), script.to_string(), "runner".to_string()],
+                process_group: false,
+            }),
+            ..Default::default()
+        };
+        let runs = [
+            ("outcome_scenario".to_string(), "crash".to_string()),
+            ("outcome_scenario".to_string(), "ok".to_string()),
+        ];
+
+        let results = context.run_many(&runs, &options, &NoopObserver);
+
+        let outcomes: Vec<Outcome> = results.into_iter().map(|result| result.outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Failed("Isolated scenario terminated by signal 11".to_string()),
+                Outcome::Passed
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_with_retries() {
+        // Passes on third attempt.
+        let mut attempts = 0;
+        let outcome = run_with_retries(3, None, || {
+            attempts += 1;
+            match attempts {
+                3 => Outcome::Passed,
+                _ => Outcome::Failed("Connection reset".to_string()),
+            }
+        });
+        assert_eq!(outcome, Outcome::Passed);
+        assert_eq!(attempts, 3);
+
+        // Not retryable error is not retried.
+        let mut attempts = 0;
+        let outcome = run_with_retries(3, Some("Connection"), || {
+            attempts += 1;
+            Outcome::Failed("Invalid input".to_string())
+        });
+        assert_eq!(outcome, Outcome::Failed("Invalid input".to_string()));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_builder_defaults_match_new() {
+        let context = TestContext::builder(init_group()).build().unwrap();
+
+        assert_eq!(
+            context.list_scenarios(),
+            TestContext::new(init_group()).list_scenarios()
+        );
+        assert!(context.run("inner_group.inner_scenario", "ok").is_ok());
+    }
+
+    #[test]
+    fn test_builder_separator() {
+        let context = TestContext::builder(init_group())
+            .separator('/')
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            context.list_scenarios(),
+            vec!["inner_group/inner_scenario", "outer_scenario"]
+        );
+        assert!(context.run("inner_group/inner_scenario", "ok").is_ok());
+        assert!(context.contains_group("inner_group"));
+        assert_eq!(
+            context.list_group_scenarios("inner_group", &ScenarioFilter::default()),
+            Some(vec!["inner_group/inner_scenario".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_builder_separator_used_in_name() {
+        let result = TestContext::builder(init_group()).separator('_').build();
+
+        assert!(result
+            .unwrap_err()
+            .contains("Separator '_' is used in name"));
+    }
+
+    #[test]
+    fn test_builder_default_timeout() {
+        let scenario = TimeoutScenarioStub { timeout: None };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let context = TestContext::builder(Box::new(root_group))
+            .default_timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            context.run_outcome("timeout_scenario", ""),
+            Outcome::TimedOut
+        );
+        assert!(context.run("timeout_scenario", "").is_err());
+    }
+
+    #[test]
+    fn test_builder_default_timeout_overridden_by_scenario() {
+        let scenario = TimeoutScenarioStub {
+            timeout: Some(Duration::from_secs(5)),
+        };
+        let root_group = ScenarioGroupImpl::new("root", vec![Box::new(scenario)], vec![]);
+        let context = TestContext::builder(Box::new(root_group))
+            .default_timeout(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        assert_eq!(context.run_outcome("timeout_scenario", ""), Outcome::Passed);
+    }
+
+    #[test]
+    fn test_builder_case_insensitive() {
+        let context = TestContext::builder(init_group())
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+
+        assert!(context.run("Inner_Group.INNER_SCENARIO", "ok").is_ok());
+        assert!(context.contains_group("INNER_GROUP"));
+        assert!(TestContext::new(init_group())
+            .run("Inner_Group.INNER_SCENARIO", "ok")
+            .is_err());
+    }
+
+    #[test]
+    fn test_builder_hooks() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let before_calls = Arc::clone(&calls);
+        let after_calls = Arc::clone(&calls);
+        let context = TestContext::builder(init_group())
+            .before_each(move |name| before_calls.lock().unwrap().push(format!("before {name}")))
+            .after_each(move |name| after_calls.lock().unwrap().push(format!("after {name}")))
+            .build()
+            .unwrap();
+
+        context.run("outer_scenario", "ok").unwrap();
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["before outer_scenario", "after outer_scenario"]
+        );
     }
 }